@@ -0,0 +1,533 @@
+//! Driver configuration.
+//!
+//! An [`AskConfig`] collects every setting that [`AskDriver::new()`](crate::driver::AskDriver::new)
+//! needs besides the pins. It can only be created through [`AskConfigBuilder`], which
+//! checks the values up front so that a driver is never built with settings the
+//! software PLL or the transmit buffer cannot handle.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ask433::config::{AskConfig, BitRate};
+//!
+//! // 1000 bps from a 16 kHz timer interrupt
+//! let config = AskConfig::builder()
+//!     .bit_rate(BitRate::Bps1000)
+//!     .tick_hz(16_000)
+//!     .address(0x42)
+//!     .rx_inverted(true)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(config.ticks_per_bit(), 16);
+//! assert_eq!(config.address(), 0x42);
+//! ```
+
+use crate::consts::{
+    ASK_DEFAULT_CAD_WINDOW_BITS, ASK_DEFAULT_CSMA_ATTEMPTS, ASK_DEFAULT_DUPLICATE_EXPIRY_TICKS,
+    ASK_DEFAULT_TICKS_PER_BIT, ASK_MAX_PREAMBLE_LEN, ASK_MIN_CAD_WINDOW_BITS, ASK_MIN_PREAMBLE_LEN,
+    ASK_MIN_TICKS_PER_BIT, ASK_PREAMBLE_LEN, BROADCAST_ADDRESS,
+};
+use crate::error::ConfigError;
+
+/// On-air bit rates supported by the driver.
+///
+/// These match the speeds offered by RadioHead's `RH_ASK`. Slower rates trade
+/// throughput for range and robustness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BitRate {
+    /// 500 bits per second
+    Bps500,
+    /// 1000 bits per second
+    Bps1000,
+    /// 2000 bits per second (the RadioHead default)
+    #[default]
+    Bps2000,
+    /// 4000 bits per second
+    Bps4000,
+    /// 9600 bits per second
+    Bps9600,
+}
+
+impl BitRate {
+    /// The bit rate in bits per second.
+    pub const fn bits_per_second(self) -> u32 {
+        match self {
+            BitRate::Bps500 => 500,
+            BitRate::Bps1000 => 1_000,
+            BitRate::Bps2000 => 2_000,
+            BitRate::Bps4000 => 4_000,
+            BitRate::Bps9600 => 9_600,
+        }
+    }
+
+    /// The duration of one bit in picoseconds (truncated).
+    pub const fn picoseconds_per_bit(self) -> u64 {
+        1_000_000_000_000 / self.bits_per_second() as u64
+    }
+
+    /// Number of `tick()` calls per bit for a tick source running at `tick_hz`,
+    /// rounded to the nearest whole tick.
+    pub const fn ticks_per_bit(self, tick_hz: u32) -> u32 {
+        let bps = self.bits_per_second();
+        (tick_hz + bps / 2) / bps
+    }
+}
+
+/// Validated settings for an [`AskDriver`](crate::driver::AskDriver).
+///
+/// Use [`AskConfig::builder()`] to create one, or [`AskConfig::default()`] for
+/// 2000 bps at 8 ticks per bit, non-inverted pins, the broadcast address and an
+/// 8 symbol preamble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AskConfig {
+    ticks_per_bit: u8,
+    tick_hz: Option<u32>,
+    bit_rate: BitRate,
+    ptt_inverted: bool,
+    rx_inverted: bool,
+    address: u8,
+    promiscuous: bool,
+    preamble_len: u8,
+    rx_warmup_ticks: u16,
+    cad_window_bits: u8,
+    csma: bool,
+    csma_max_attempts: u8,
+    duplicate_filter: bool,
+    duplicate_expiry_ticks: u32,
+    fec: bool,
+    rc_switch_rx: bool,
+}
+
+impl AskConfig {
+    /// Returns a builder initialized with the default settings.
+    pub fn builder() -> AskConfigBuilder {
+        AskConfigBuilder::new()
+    }
+
+    /// Number of `tick()` calls per bit period.
+    pub fn ticks_per_bit(&self) -> u8 {
+        self.ticks_per_bit
+    }
+
+    /// The on-air bit rate.
+    pub fn bit_rate(&self) -> BitRate {
+        self.bit_rate
+    }
+
+    /// The rate (in Hz) at which `tick()` must be called.
+    ///
+    /// This is the rate given to [`AskConfigBuilder::tick_hz()`], or the bit rate times
+    /// the ticks per bit if none was given.
+    pub fn tick_hz(&self) -> u32 {
+        self.tick_hz
+            .unwrap_or(self.bit_rate.bits_per_second() * self.ticks_per_bit as u32)
+    }
+
+    /// Whether the PTT pin is active `LOW`.
+    pub fn ptt_inverted(&self) -> bool {
+        self.ptt_inverted
+    }
+
+    /// Whether the RX pin reads `LOW` when a carrier is present.
+    pub fn rx_inverted(&self) -> bool {
+        self.rx_inverted
+    }
+
+    /// The address of this node.
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Whether frames addressed to other nodes are accepted too.
+    pub fn promiscuous(&self) -> bool {
+        self.promiscuous
+    }
+
+    /// Number of symbols in the transmitted preamble, start symbol included.
+    pub fn preamble_len(&self) -> u8 {
+        self.preamble_len
+    }
+
+    /// Number of `tick()` calls the receiver is given to settle after waking from sleep.
+    pub fn rx_warmup_ticks(&self) -> u16 {
+        self.rx_warmup_ticks
+    }
+
+    /// Length (in bit periods) of a channel activity detection window.
+    pub fn cad_window_bits(&self) -> u8 {
+        self.cad_window_bits
+    }
+
+    /// Whether the channel is checked for activity before each frame is transmitted.
+    pub fn csma(&self) -> bool {
+        self.csma
+    }
+
+    /// How many times CSMA checks the channel for a frame before dropping it.
+    pub fn csma_max_attempts(&self) -> u8 {
+        self.csma_max_attempts
+    }
+
+    /// Whether frames repeating the last header id of their sender are dropped.
+    pub fn duplicate_filter(&self) -> bool {
+        self.duplicate_filter
+    }
+
+    /// Number of `tick()` calls after which the duplicate filter accepts a repeated
+    /// header id again.
+    pub fn duplicate_expiry_ticks(&self) -> u32 {
+        self.duplicate_expiry_ticks
+    }
+
+    /// Whether frames are sent with forward error correction.
+    pub fn fec(&self) -> bool {
+        self.fec
+    }
+
+    /// Whether fixed-code remotes are decoded next to RadioHead frames.
+    pub fn rc_switch_rx(&self) -> bool {
+        self.rc_switch_rx
+    }
+}
+
+impl Default for AskConfig {
+    fn default() -> Self {
+        Self {
+            ticks_per_bit: ASK_DEFAULT_TICKS_PER_BIT,
+            tick_hz: None,
+            bit_rate: BitRate::Bps2000,
+            ptt_inverted: false,
+            rx_inverted: false,
+            address: BROADCAST_ADDRESS,
+            promiscuous: false,
+            preamble_len: ASK_PREAMBLE_LEN,
+            rx_warmup_ticks: 0,
+            cad_window_bits: ASK_DEFAULT_CAD_WINDOW_BITS,
+            csma: false,
+            csma_max_attempts: ASK_DEFAULT_CSMA_ATTEMPTS,
+            duplicate_filter: false,
+            duplicate_expiry_ticks: ASK_DEFAULT_DUPLICATE_EXPIRY_TICKS,
+            fec: false,
+            rc_switch_rx: false,
+        }
+    }
+}
+
+/// Builder for [`AskConfig`].
+///
+/// Every setter takes and returns the builder by value, so calls can be chained.
+/// Nothing is checked until [`build()`](AskConfigBuilder::build).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AskConfigBuilder {
+    config: AskConfig,
+}
+
+impl AskConfigBuilder {
+    /// Creates a builder initialized with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of `tick()` calls per bit period.
+    ///
+    /// The tick rate divided by this value is the bit rate, e.g. 8 ticks per bit
+    /// with a 16 kHz tick gives 2000 bits per second. Must be at least
+    /// [`ASK_MIN_TICKS_PER_BIT`].
+    pub fn ticks_per_bit(mut self, ticks_per_bit: u8) -> Self {
+        self.config.ticks_per_bit = ticks_per_bit;
+        self
+    }
+
+    /// Sets the on-air bit rate.
+    ///
+    /// Together with [`ticks_per_bit()`](AskConfigBuilder::ticks_per_bit) or
+    /// [`tick_hz()`](AskConfigBuilder::tick_hz) this determines how often `tick()`
+    /// has to be called.
+    pub fn bit_rate(mut self, bit_rate: BitRate) -> Self {
+        self.config.bit_rate = bit_rate;
+        self
+    }
+
+    /// Sets the rate (in Hz) of the timer that calls `tick()`.
+    ///
+    /// When set, the ticks per bit are derived from this and the bit rate at
+    /// [`build()`](AskConfigBuilder::build) time, overriding any value passed to
+    /// [`ticks_per_bit()`](AskConfigBuilder::ticks_per_bit).
+    pub fn tick_hz(mut self, tick_hz: u32) -> Self {
+        self.config.tick_hz = Some(tick_hz);
+        self
+    }
+
+    /// Sets whether the PTT pin is active `LOW`.
+    pub fn ptt_inverted(mut self, inverted: bool) -> Self {
+        self.config.ptt_inverted = inverted;
+        self
+    }
+
+    /// Sets whether the RX pin reads `LOW` when a carrier is present.
+    pub fn rx_inverted(mut self, inverted: bool) -> Self {
+        self.config.rx_inverted = inverted;
+        self
+    }
+
+    /// Sets the address of this node.
+    ///
+    /// Also used as the `from` header of frames sent with [`send()`](crate::driver::AskDriver::send).
+    pub fn address(mut self, address: u8) -> Self {
+        self.config.address = address;
+        self
+    }
+
+    /// Sets whether frames addressed to other nodes are accepted too.
+    pub fn promiscuous(mut self, promiscuous: bool) -> Self {
+        self.config.promiscuous = promiscuous;
+        self
+    }
+
+    /// Sets the number of symbols in the transmitted preamble, start symbol included.
+    ///
+    /// Must be within [`ASK_MIN_PREAMBLE_LEN`]..=[`ASK_MAX_PREAMBLE_LEN`].
+    pub fn preamble_len(mut self, preamble_len: u8) -> Self {
+        self.config.preamble_len = preamble_len;
+        self
+    }
+
+    /// Sets how many `tick()` calls the receiver is given to settle after waking
+    /// from [`AskMode::Sleep`](crate::driver::AskMode::Sleep).
+    ///
+    /// Superheterodyne receivers typically need a few milliseconds after power-up
+    /// before their output is usable; the PLL ignores the RX pin until this many
+    /// ticks have passed. At 16 kHz, 80 ticks are 5 ms. Defaults to `0`.
+    pub fn rx_warmup_ticks(mut self, ticks: u16) -> Self {
+        self.config.rx_warmup_ticks = ticks;
+        self
+    }
+
+    /// Sets how many bit periods [`start_cad()`](crate::driver::AskDriver::start_cad)
+    /// listens before deciding whether the channel is busy.
+    ///
+    /// Must be at least [`ASK_MIN_CAD_WINDOW_BITS`]. Defaults to [`ASK_DEFAULT_CAD_WINDOW_BITS`].
+    pub fn cad_window_bits(mut self, bits: u8) -> Self {
+        self.config.cad_window_bits = bits;
+        self
+    }
+
+    /// Enables listen-before-talk (CSMA).
+    ///
+    /// Before each frame goes out, the driver runs a channel activity detection
+    /// window. If the channel is busy it waits a random backoff and tries again,
+    /// up to [`csma_max_attempts()`](AskConfigBuilder::csma_max_attempts) times.
+    pub fn csma(mut self, enabled: bool) -> Self {
+        self.config.csma = enabled;
+        self
+    }
+
+    /// Sets how many times CSMA checks the channel for a frame before dropping it.
+    ///
+    /// Must be at least `1`. Defaults to [`ASK_DEFAULT_CSMA_ATTEMPTS`].
+    pub fn csma_max_attempts(mut self, attempts: u8) -> Self {
+        self.config.csma_max_attempts = attempts;
+        self
+    }
+
+    /// Enables the duplicate filter.
+    ///
+    /// The driver remembers the last header id received from each of up to
+    /// [`ASK_DUPLICATE_TABLE_LEN`](crate::consts::ASK_DUPLICATE_TABLE_LEN) senders and
+    /// drops frames repeating it, counting them in
+    /// [`rx_duplicates`](crate::stats::AskStats::rx_duplicates).
+    ///
+    /// # Notes
+    /// ACK frames are never filtered. [`ReliableDatagram`](crate::reliable::ReliableDatagram)
+    /// suppresses duplicates itself and must see retransmissions to acknowledge them
+    /// again, so leave the filter off when using it.
+    pub fn duplicate_filter(mut self, enabled: bool) -> Self {
+        self.config.duplicate_filter = enabled;
+        self
+    }
+
+    /// Sets after how many `tick()` calls the duplicate filter accepts a repeated
+    /// header id again, e.g. once a sender's id counter has wrapped around.
+    ///
+    /// Defaults to [`ASK_DEFAULT_DUPLICATE_EXPIRY_TICKS`].
+    pub fn duplicate_expiry_ticks(mut self, ticks: u32) -> Self {
+        self.config.duplicate_expiry_ticks = ticks;
+        self
+    }
+
+    /// Sends frames with forward error correction (see [`fec`](crate::fec)).
+    ///
+    /// Receivers decode FEC frames whether or not this is set, so it only has to be
+    /// enabled on the nodes whose transmissions should be protected.
+    ///
+    /// # Notes
+    /// FEC roughly halves the longest message, see
+    /// [`AskDriver::max_message_len()`](crate::driver::AskDriver::max_message_len).
+    /// Frames without headers carry no flags to signal FEC in and are always sent
+    /// uncoded.
+    pub fn fec(mut self, enabled: bool) -> Self {
+        self.config.fec = enabled;
+        self
+    }
+
+    /// Decodes fixed-code remotes (see [`rcswitch`](crate::rcswitch)) from the same
+    /// RX samples as RadioHead frames.
+    ///
+    /// Read decoded codes with
+    /// [`AskDriver::receive_rc_code()`](crate::driver::AskDriver::receive_rc_code).
+    /// Off by default, since the decoder adds work to every `tick()`.
+    pub fn rc_switch_rx(mut self, enabled: bool) -> Self {
+        self.config.rc_switch_rx = enabled;
+        self
+    }
+
+    /// Checks the settings and returns the finished [`AskConfig`].
+    ///
+    /// # Errors
+    /// - [`ConfigError::TicksPerBitTooLow`] if fewer than [`ASK_MIN_TICKS_PER_BIT`] ticks per bit were set
+    /// - [`ConfigError::InvalidTickRate`] if the tick rate does not give between
+    ///   [`ASK_MIN_TICKS_PER_BIT`] and `u8::MAX` ticks per bit at the chosen bit rate
+    /// - [`ConfigError::InvalidPreambleLen`] if the preamble length is out of range
+    /// - [`ConfigError::InvalidCadWindow`] if the CAD window is too short
+    /// - [`ConfigError::InvalidCsmaAttempts`] if CSMA was given zero attempts
+    pub fn build(self) -> Result<AskConfig, ConfigError> {
+        let mut config = self.config;
+        if let Some(tick_hz) = config.tick_hz {
+            config.ticks_per_bit = ticks_per_bit_for(tick_hz, config.bit_rate)
+                .ok_or(ConfigError::InvalidTickRate(tick_hz))?;
+        }
+        if config.ticks_per_bit < ASK_MIN_TICKS_PER_BIT {
+            return Err(ConfigError::TicksPerBitTooLow(config.ticks_per_bit));
+        }
+        if !(ASK_MIN_PREAMBLE_LEN..=ASK_MAX_PREAMBLE_LEN).contains(&config.preamble_len) {
+            return Err(ConfigError::InvalidPreambleLen(config.preamble_len));
+        }
+        if config.cad_window_bits < ASK_MIN_CAD_WINDOW_BITS {
+            return Err(ConfigError::InvalidCadWindow(config.cad_window_bits));
+        }
+        if config.csma_max_attempts == 0 {
+            return Err(ConfigError::InvalidCsmaAttempts(0));
+        }
+        Ok(config)
+    }
+}
+
+/// Ticks per bit for a tick source running at `tick_hz`, if the PLL can work with it.
+pub(crate) fn ticks_per_bit_for(tick_hz: u32, bit_rate: BitRate) -> Option<u8> {
+    u8::try_from(bit_rate.ticks_per_bit(tick_hz))
+        .ok()
+        .filter(|ticks| *ticks >= ASK_MIN_TICKS_PER_BIT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_builds() {
+        assert_eq!(AskConfig::builder().build(), Ok(AskConfig::default()));
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_values() {
+        assert_eq!(
+            AskConfig::builder().ticks_per_bit(2).build(),
+            Err(ConfigError::TicksPerBitTooLow(2))
+        );
+        assert_eq!(
+            AskConfig::builder().preamble_len(3).build(),
+            Err(ConfigError::InvalidPreambleLen(3))
+        );
+        assert_eq!(
+            AskConfig::builder()
+                .preamble_len(ASK_MAX_PREAMBLE_LEN + 1)
+                .build(),
+            Err(ConfigError::InvalidPreambleLen(ASK_MAX_PREAMBLE_LEN + 1))
+        );
+        assert_eq!(
+            AskConfig::builder().cad_window_bits(4).build(),
+            Err(ConfigError::InvalidCadWindow(4))
+        );
+        assert_eq!(
+            AskConfig::builder().csma_max_attempts(0).build(),
+            Err(ConfigError::InvalidCsmaAttempts(0))
+        );
+    }
+
+    #[test]
+    fn test_tick_hz_derives_ticks_per_bit() {
+        let config = AskConfig::builder()
+            .bit_rate(BitRate::Bps9600)
+            .tick_hz(76_800)
+            .build()
+            .unwrap();
+        assert_eq!(config.ticks_per_bit(), 8);
+        assert_eq!(config.tick_hz(), 76_800);
+
+        let config = AskConfig::builder()
+            .bit_rate(BitRate::Bps500)
+            .tick_hz(16_000)
+            .build()
+            .unwrap();
+        assert_eq!(config.ticks_per_bit(), 32);
+
+        // 50 kHz is not a whole multiple of 9600 bps, the rate is kept as given
+        let config = AskConfig::builder()
+            .bit_rate(BitRate::Bps9600)
+            .tick_hz(50_000)
+            .build()
+            .unwrap();
+        assert_eq!(config.ticks_per_bit(), 5);
+        assert_eq!(config.tick_hz(), 50_000);
+
+        assert_eq!(
+            AskConfig::builder()
+                .bit_rate(BitRate::Bps9600)
+                .tick_hz(16_000)
+                .build(),
+            Err(ConfigError::InvalidTickRate(16_000))
+        );
+        assert_eq!(
+            AskConfig::builder()
+                .bit_rate(BitRate::Bps500)
+                .tick_hz(1_000_000)
+                .build(),
+            Err(ConfigError::InvalidTickRate(1_000_000))
+        );
+    }
+
+    #[test]
+    fn test_builder_sets_all_fields() {
+        let config = AskConfig::builder()
+            .ticks_per_bit(16)
+            .ptt_inverted(true)
+            .rx_inverted(true)
+            .address(0x42)
+            .promiscuous(true)
+            .preamble_len(12)
+            .rx_warmup_ticks(80)
+            .cad_window_bits(64)
+            .csma(true)
+            .csma_max_attempts(8)
+            .duplicate_filter(true)
+            .duplicate_expiry_ticks(500)
+            .fec(true)
+            .rc_switch_rx(true)
+            .build()
+            .unwrap();
+        assert_eq!(config.ticks_per_bit(), 16);
+        assert!(config.ptt_inverted());
+        assert!(config.rx_inverted());
+        assert_eq!(config.address(), 0x42);
+        assert!(config.promiscuous());
+        assert_eq!(config.preamble_len(), 12);
+        assert_eq!(config.rx_warmup_ticks(), 80);
+        assert_eq!(config.cad_window_bits(), 64);
+        assert!(config.csma());
+        assert_eq!(config.csma_max_attempts(), 8);
+        assert!(config.duplicate_filter());
+        assert_eq!(config.duplicate_expiry_ticks(), 500);
+        assert!(config.fec());
+        assert!(config.rc_switch_rx());
+    }
+}
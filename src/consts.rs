@@ -17,24 +17,45 @@
 //! These values should be used wherever framing or buffer logic is implemented to ensure
 //! consistent message boundaries and timing alignment.
 
-use core::u8;
-
-/// Bitmask for protocol-level flags reserved for future use.
+/// Bitmask for protocol-level flags.
 ///
 /// These upper 4 bits of the flags byte are reserved by the protocol
-/// and should not be modified by application code.
-pub const ASK_FLAGS_RESERVED: i16 = 0xf0;
+/// and cannot be set by application code, see [`HeaderFlags`](crate::flags::HeaderFlags).
+pub const ASK_FLAGS_RESERVED: u8 = 0xf0;
 
 /// Bitmask for application-specific flags.
 ///
 /// These lower 4 bits of the flags byte can be freely used by
 /// application-layer logic for metadata, priority markers, etc.
-pub const ASK_FLAGS_APPLICATION_SPECIFIC: i16 = 0x0f;
+pub const ASK_FLAGS_APPLICATION_SPECIFIC: u8 = 0x0f;
 
 /// Constant for a "no flags" state (all bits zero).
 ///
 /// Used when a message has no control or application flags set.
-pub const ASK_FLAGS_NONE: i16 = 0;
+pub const ASK_FLAGS_NONE: u8 = 0;
+
+/// Header flag marking a frame as an acknowledgement (RadioHead `RH_FLAGS_ACK`).
+///
+/// One of the protocol-reserved bits, see [`ASK_FLAGS_RESERVED`].
+pub const ASK_FLAGS_ACK: u8 = 0x80;
+
+/// Header flag marking a frame as a retransmission (RadioHead `RH_FLAGS_RETRY`).
+///
+/// One of the protocol-reserved bits, see [`ASK_FLAGS_RESERVED`].
+pub const ASK_FLAGS_RETRY: u8 = 0x40;
+
+/// Header flag marking a frame as one fragment of a longer message,
+/// see [`fragment`](crate::fragment).
+///
+/// One of the protocol-reserved bits, see [`ASK_FLAGS_RESERVED`].
+pub const ASK_FLAGS_FRAGMENT: u8 = 0x20;
+
+/// Header flag marking a frame whose payload and FCS are sent with forward error
+/// correction, see [`fec`](crate::fec).
+///
+/// One of the protocol-reserved bits, see [`ASK_FLAGS_RESERVED`]. Set and cleared
+/// by the driver itself, never by the layers above it.
+pub const ASK_FLAGS_FEC: u8 = 0x10;
 
 /// Length (in bytes) of the fixed-length packet header.
 ///
@@ -57,6 +78,49 @@ pub const ASK_MAX_PAYLOAD_LEN_USIZE: usize = ASK_MAX_PAYLOAD_LEN as usize;
 /// the receiver synchronize its sampling phase.
 pub const ASK_PREAMBLE_LEN: u8 = 8;
 
+/// Shortest preamble (in symbols, start symbol included) accepted by `AskConfig`.
+///
+/// Two training symbols followed by the two start symbols.
+pub const ASK_MIN_PREAMBLE_LEN: u8 = 4;
+
+/// Longest preamble (in symbols, start symbol included) accepted by `AskConfig`.
+///
+/// Longer preambles give receivers with a slow AGC more time to settle.
+pub const ASK_MAX_PREAMBLE_LEN: u8 = 32;
+
+/// Default number of `tick()` calls per bit.
+pub const ASK_DEFAULT_TICKS_PER_BIT: u8 = 8;
+
+/// Fewest `tick()` calls per bit that the software PLL can lock onto.
+///
+/// Below this the majority vote in the PLL integrator has too few samples to
+/// tell a bit from noise.
+pub const ASK_MIN_TICKS_PER_BIT: u8 = 4;
+
+/// Default length (in bit periods) of a channel activity detection window.
+///
+/// 32 bits is less than the 8 symbol default preamble, so a frame that starts
+/// while the window is open is still caught.
+pub const ASK_DEFAULT_CAD_WINDOW_BITS: u8 = 32;
+
+/// Shortest channel activity detection window (in bit periods) accepted by `AskConfig`.
+///
+/// Fewer edges than this give no useful picture of how regular they are.
+pub const ASK_MIN_CAD_WINDOW_BITS: u8 = 8;
+
+/// Default number of times CSMA checks the channel for a frame before giving up on it.
+pub const ASK_DEFAULT_CSMA_ATTEMPTS: u8 = 4;
+
+/// Number of senders whose last header id the duplicate filter remembers.
+///
+/// When the table is full, the sender heard from least recently is forgotten.
+pub const ASK_DUPLICATE_TABLE_LEN: usize = 8;
+
+/// Default time (in `tick()` calls) after which a repeated header id is accepted again.
+///
+/// 16 000 ticks are one second at the default 2000 bps and 8 ticks per bit.
+pub const ASK_DEFAULT_DUPLICATE_EXPIRY_TICKS: u32 = 16_000;
+
 /// Maximum size (in bytes) of user message content.
 ///
 /// This is derived from the maximum payload size minus header and trailer bytes (e.g., CRC).
@@ -67,13 +131,25 @@ pub const ASK_MAX_MESSAGE_LEN_USIZE: usize = ASK_MAX_MESSAGE_LEN as usize;
 
 /// Maximum size (in bytes) of the full transmission buffer, after encoding and preamble.
 ///
-/// Includes space for the 4b6b-encoded payload and the preamble prefix.
+/// Includes space for the 4b6b-encoded payload and the longest allowed preamble prefix.
 /// Each payload byte becomes two encoded symbols, hence the `* 2`.
-pub const ASK_MAX_BUF_LEN: u8 = (ASK_MAX_PAYLOAD_LEN * 2) + ASK_PREAMBLE_LEN;
+pub const ASK_MAX_BUF_LEN: u8 = (ASK_MAX_PAYLOAD_LEN * 2) + ASK_MAX_PREAMBLE_LEN;
 
 /// See [ASK_MAX_BUF_LEN]
 pub const ASK_MAX_BUF_LEN_USIZE: usize = ASK_MAX_BUF_LEN as usize;
 
+/// Default number of frames that can wait in the transmit queue of an `AskDriver`.
+///
+/// Only used in `no_std` builds; under `std` the queue grows as needed.
+pub const ASK_TX_QUEUE_LEN: usize = 4;
+
+/// Default number of validated frames that can wait in the receive queue of an `AskDriver`.
+pub const ASK_RX_QUEUE_LEN: usize = 4;
+
+/// Number of extra unicast and group addresses a driver can be subscribed to,
+/// besides its own address and [`BROADCAST_ADDRESS`].
+pub const ASK_ADDRESS_FILTER_LEN: usize = 8;
+
 /// The default address for the RF module.
 pub const BROADCAST_ADDRESS: u8 = u8::MAX;
 
@@ -1,12 +1,117 @@
+//! Frame check sequences.
+//!
+//! Every frame ends with a frame check sequence (FCS) computed over the count
+//! byte, the headers and the payload. The driver is generic over a [`Checksum`]
+//! that computes and verifies it:
+//!
+//! | Checksum        | FCS bytes | Notes                                        |
+//! |-----------------|-----------|----------------------------------------------|
+//! | [`Crc16Ccitt`]  | 2         | Default, compatible with RadioHead and VirtualWire |
+//! | [`Crc32`]       | 4         | Stronger check for long frames               |
+//! | [`NoChecksum`]  | 0         | For legacy devices that send no FCS          |
+//!
+//! The FCS is sent low byte first. A longer FCS leaves less room for the message,
+//! see [`AskDriver::MAX_MESSAGE_LEN`](crate::driver::AskDriver::MAX_MESSAGE_LEN).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ask433::crc::{Checksum, Crc16Ccitt, Crc32};
+//!
+//! let mut frame = [0x05, b'H', b'i', 0, 0];
+//! let fcs = Crc16Ccitt::fcs(&frame[..3]);
+//! frame[3..].copy_from_slice(&fcs.to_le_bytes()[..2]);
+//! assert!(Crc16Ccitt::verify(&frame));
+//! assert!(!Crc32::verify(&frame));
+//! ```
+
+/// Computes and verifies the frame check sequence at the end of a frame.
+pub trait Checksum {
+    /// Number of FCS bytes appended to each frame, at most 4.
+    const LEN: u8;
+
+    /// Computes the FCS of `data`. Only the lower [`LEN`](Checksum::LEN) bytes are
+    /// sent, low byte first.
+    fn fcs(data: &[u8]) -> u32;
+
+    /// Checks the FCS at the end of `frame`.
+    ///
+    /// Returns `false` if the frame is too short to hold an FCS.
+    fn verify(frame: &[u8]) -> bool {
+        let Some(split) = frame.len().checked_sub(Self::LEN as usize) else {
+            return false;
+        };
+        let (data, fcs) = frame.split_at(split);
+        Self::fcs(data).to_le_bytes()[..Self::LEN as usize] == *fcs
+    }
+}
+
+/// The one's complement of CRC-16/CCITT (X.25), as used by RadioHead and VirtualWire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Crc16Ccitt;
+
+impl Checksum for Crc16Ccitt {
+    const LEN: u8 = 2;
+
+    fn fcs(data: &[u8]) -> u32 {
+        // Caution: VW expects the _ones_complement_ of the CCITT CRC-16 as the FCS
+        let crc = data.iter().fold(0xffff, crc_ccitt_update);
+        u32::from(!crc)
+    }
+
+    fn verify(frame: &[u8]) -> bool {
+        // CRC when buffer and expected CRC are CRC'd
+        frame.len() >= Self::LEN as usize && frame.iter().fold(0xffff, crc_ccitt_update) == 0xf0b8
+    }
+}
+
+/// CRC-32 (IEEE 802.3), as used by Ethernet and zip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Crc32;
+
+impl Checksum for Crc32 {
+    const LEN: u8 = 4;
+
+    fn fcs(data: &[u8]) -> u32 {
+        !data
+            .iter()
+            .fold(0xffff_ffff, |crc, b| crc32_update(crc, *b))
+    }
+}
+
+/// No frame check sequence. Corrupt frames are only caught by invalid symbols
+/// or a bad length byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoChecksum;
+
+impl Checksum for NoChecksum {
+    const LEN: u8 = 0;
+
+    fn fcs(_data: &[u8]) -> u32 {
+        0
+    }
+}
+
 pub(crate) fn crc_ccitt_update(crc: u16, data: &u8) -> u16 {
     let mut d = *data as u16;
-    d = d ^ lo8(crc);
-    d = d ^ (d << 4);
+    d ^= lo8(crc);
+    d ^= d << 4;
     d = (d as u8) as u16; // Ensure d is treated as a u8 for the next operations
 
     ((d << 8) | hi8(crc)) ^ (((d >> 4) as u8) as u16) ^ (d << 3)
 }
 
+/// Bitwise, reflected CRC-32 update; slower than a table but without the 1 KiB of flash.
+fn crc32_update(crc: u32, data: u8) -> u32 {
+    (0..8).fold(crc ^ u32::from(data), |crc, _| {
+        if crc & 1 != 0 {
+            (crc >> 1) ^ 0xedb8_8320
+        } else {
+            crc >> 1
+        }
+    })
+}
+
 pub(crate) fn lo8(x: u16) -> u16 {
     x & 0xff
 }
@@ -14,3 +119,24 @@ pub(crate) fn lo8(x: u16) -> u16 {
 pub(crate) fn hi8(x: u16) -> u16 {
     x >> 8
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksums_match_reference_values() {
+        // Check values of the "123456789" test vector
+        assert_eq!(Crc32::fcs(b"123456789"), 0xcbf4_3926);
+        assert_eq!(Crc16Ccitt::fcs(b"123456789"), 0x906e);
+
+        let mut frame = *b"123456789\0\0\0\0";
+        frame[9..].copy_from_slice(&Crc32::fcs(b"123456789").to_le_bytes());
+        assert!(Crc32::verify(&frame));
+        frame[0] ^= 0x01;
+        assert!(!Crc32::verify(&frame));
+
+        assert!(NoChecksum::verify(b"anything"));
+        assert!(!Crc16Ccitt::verify(&[0x00]));
+    }
+}
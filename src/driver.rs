@@ -20,15 +20,17 @@
 //! ```rust
 //! # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
 //! # use embedded_hal::digital::OutputPin;
+//! use ask433::config::AskConfig;
 //! use ask433::driver::AskDriver;
 //!
 //! fn main() {
 //!     # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
 //!     # let rx_pin = Pin::new(&[]);
-//!     let mut driver: AskDriver<Pin, Pin, Pin> = AskDriver::new(tx_pin, rx_pin, None, 8, None, None);
+//!     let mut driver: AskDriver<Pin, Pin, Pin> =
+//!         AskDriver::new(tx_pin, rx_pin, None, AskConfig::default()).unwrap();
 //!
 //!     loop {
-//!         driver.tick(); // Called every 62.5 µs by a delay or timer interrupt
+//!         driver.tick().unwrap(); // Called every 62.5 µs by a delay or timer interrupt
 //!         # break; // For testing purposes
 //!     }
 //!     # driver.tx.done();
@@ -47,18 +49,31 @@
 //! For timer and tick scheduling helpers, see [`crate::timer`].
 
 #[cfg(not(feature = "std"))]
-use crate::consts::{ASK_MAX_BUF_LEN_USIZE, ASK_MAX_MESSAGE_LEN_USIZE};
-
-use crate::consts::{ASK_HEADER_LEN, ASK_MAX_MESSAGE_LEN, ASK_PREAMBLE_LEN, BROADCAST_ADDRESS};
-use crate::crc::crc_ccitt_update;
-use crate::encoding::{SYMBOLS, encode_4b6b};
+use crate::consts::{ASK_MAX_BUF_LEN_USIZE, ASK_MAX_MESSAGE_LEN_USIZE, ASK_MAX_PAYLOAD_LEN_USIZE};
+
+use crate::config::{AskConfig, BitRate, ticks_per_bit_for};
+use crate::consts::{
+    ASK_ADDRESS_FILTER_LEN, ASK_DUPLICATE_TABLE_LEN, ASK_FLAGS_FEC, ASK_HEADER_LEN,
+    ASK_MAX_MESSAGE_LEN, ASK_MAX_PAYLOAD_LEN, ASK_RX_QUEUE_LEN, ASK_TX_QUEUE_LEN,
+    BROADCAST_ADDRESS,
+};
+use crate::crc::{Checksum, Crc16Ccitt};
+use crate::encoding::{FourBSixB, LineCode};
+use crate::error::{AskDriverError, AskError, ConfigError};
+use crate::fec::{FEC_BLOCK_LEN, FEC_DATA_LEN, encode_block};
+use crate::flags::HeaderFlags;
+use crate::format::{FrameFormat, RadioHead};
+use crate::packet::AskPacket;
 use crate::pll::SoftwarePLL;
+use crate::rcswitch::{RcCode, RcSwitchDecoder, RcTransmitter};
+use crate::stats::AskStats;
+use core::marker::PhantomData;
 use embedded_hal::digital::{InputPin, OutputPin};
-use nb::block;
 
-use core::convert::Infallible;
 #[cfg(not(feature = "std"))]
-use heapless::Vec;
+use heapless::{Deque, Vec};
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
 #[cfg(feature = "std")]
 use std::vec::Vec;
 
@@ -88,10 +103,31 @@ pub enum AskMode {
     Rx,
     ///   Channel Activity Detection mode. This is a temporary sniffing state used to detect
     ///   whether the channel is currently in use, before transmitting.
-    ///   Typically a short-duration passive RX phase that ends in either [`Idle`](AskMode::Idle) or [`Tx`](AskMode::Tx).
+    ///   Entered with [`AskDriver::start_cad()`]; once the window is over the driver returns
+    ///   to the mode it was started from ([`Idle`](AskMode::Idle) or [`Rx`](AskMode::Rx)).
     Cad,
 }
 
+/// Kind of an address a driver is subscribed to with [`AskDriver::subscribe()`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AddressKind {
+    ///   An additional address of this node. Frames sent to it are answered like
+    ///   frames sent to the node's own address, e.g. acknowledged by
+    ///   [`ReliableDatagram`](crate::reliable::ReliableDatagram).
+    Unicast,
+    ///   A group (multicast) address shared by several nodes, such as "all lights on
+    ///   floor 2". Like broadcasts, frames sent to it are never acknowledged.
+    Group,
+}
+
+/// The last header id received from a sender, for the duplicate filter.
+#[derive(Debug, Clone, Copy)]
+struct SeenId {
+    from: u8,
+    id: u8,
+    at: u32,
+}
+
 /// A software-driven Amplitude Shift Keying (ASK) modem for 433 MHz RF transceivers.
 ///
 /// `AskDriver` provides both transmission and reception support for low-cost OOK/ASK
@@ -119,21 +155,33 @@ pub enum AskMode {
 ///
 /// - `TX`: A type implementing [`embedded_hal::digital::OutputPin`] used for RF transmission
 /// - `RX`: A type implementing [`embedded_hal::digital::InputPin`] used for RF reception
+/// - `PTT`: A type implementing [`embedded_hal::digital::OutputPin`] used to key the transmitter
+/// - `TXQ`: The number of frames that can wait in the transmit queue
+///   (defaults to [`ASK_TX_QUEUE_LEN`])
+/// - `RXQ`: The number of validated frames that can wait in the receive queue
+///   (defaults to [`ASK_RX_QUEUE_LEN`])
+/// - `LC`: The [`LineCode`] frames are sent in (defaults to RadioHead's [`FourBSixB`])
+/// - `CS`: The [`Checksum`] ending each frame (defaults to RadioHead's [`Crc16Ccitt`])
+/// - `FF`: The [`FrameFormat`] of the headers (defaults to [`RadioHead`])
+/// - `RXEN`, `TXEN`: The [`OutputPin`] types switching the receiver and transmitter
+///   supply (default to `PTT`), see [`set_power_pins()`](AskDriver::set_power_pins)
 ///
 /// ## Example
 ///
 /// ```rust
 /// # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
 /// # use embedded_hal::digital::OutputPin;
+/// use ask433::config::AskConfig;
 /// use ask433::driver::AskDriver;
 ///
 /// fn main() {
 ///     # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
 ///     # let rx_pin = Pin::new(&[]);
-///     let mut driver: AskDriver<Pin, Pin, Pin> = AskDriver::new(tx_pin, rx_pin, None, 8, None, None);
+///     let mut driver: AskDriver<Pin, Pin, Pin> =
+///         AskDriver::new(tx_pin, rx_pin, None, AskConfig::default()).unwrap();
 ///
 ///     loop {
-///         driver.tick(); // Called every 62.5 µs by a delay or timer interrupt
+///         driver.tick().unwrap(); // Called every 62.5 µs by a delay or timer interrupt
 ///         # break; // For testing purposes
 ///     }
 ///     # driver.tx.done();
@@ -146,16 +194,33 @@ pub enum AskMode {
 /// - Only one `AskDriver` instance should be active if you're using interrupts.
 /// - You are responsible for calling `tick()` at the correct interval using either
 ///   a hardware timer interrupt or a polling loop.
-/// - Message encoding (4b6b) and decoding are not yet implemented.
+/// - Frames are 4b6b encoded by default; both ends of a link must use the same `LC`.
+/// - Frames carry the RadioHead headers by default; use [`VirtualWire`](crate::format::VirtualWire)
+///   as `FF` to talk to headerless VirtualWire nodes.
 ///
 /// ## See also
 /// - [`SoftwarePLL`]: internal demodulation logic
 #[derive(Debug)]
-pub struct AskDriver<TX, RX, PTT>
-where
+pub struct AskDriver<
+    TX,
+    RX,
+    PTT,
+    const TXQ: usize = ASK_TX_QUEUE_LEN,
+    const RXQ: usize = ASK_RX_QUEUE_LEN,
+    LC = FourBSixB,
+    CS = Crc16Ccitt,
+    FF = RadioHead,
+    RXEN = PTT,
+    TXEN = PTT,
+> where
     TX: OutputPin,
     RX: InputPin,
     PTT: OutputPin,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
 {
     /// The current mode of the RF module
     pub mode: AskMode,
@@ -165,18 +230,50 @@ where
     pub rx: RX,
     /// Push To Talk (PTT) pin
     pub ptt: Option<PTT>,
+    /// Optional pin switching the receiver module's supply, `HIGH` = powered.
+    /// Set with [`set_power_pins()`](AskDriver::set_power_pins).
+    pub rx_enable: Option<RXEN>,
+    /// Optional pin switching the transmitter module's supply, `HIGH` = powered.
+    /// Set with [`set_power_pins()`](AskDriver::set_power_pins).
+    pub tx_enable: Option<TXEN>,
     /// [`SoftwarePLL`] instance
-    pub pll: SoftwarePLL,
+    pub pll: SoftwarePLL<LC>,
     ticks_per_bit: u8,
+    bit_rate: BitRate,
+    tick_hz: u32,
     tick_counter: u8,
-    /// Holds the transmission buffer
+    ticks: u32,
+    rx_warmup_ticks: u16,
+    rx_warmup_remaining: u16,
+    cad_window_bits: u8,
+    cad_remaining: u16,
+    cad_return_mode: AskMode,
+    cad_result: Option<bool>,
+    csma: bool,
+    csma_max_attempts: u8,
+    csma_pending: bool,
+    csma_attempt: u8,
+    csma_backoff: u16,
+    rng: u32,
+    duplicate_filter: bool,
+    duplicate_expiry_ticks: u32,
+    seen_ids: [Option<SeenId>; ASK_DUPLICATE_TABLE_LEN],
+    /// Holds the encoded symbols (preamble included) of the frame being transmitted
     #[cfg(feature = "std")]
     pub tx_buf: Vec<u8>,
-    /// Holds the transmission buffer
+    /// Holds the encoded symbols (preamble included) of the frame being transmitted
     #[cfg(not(feature = "std"))]
     pub tx_buf: Vec<u8, ASK_MAX_BUF_LEN_USIZE>,
+    /// Frames (length, headers, payload and FCS) waiting to be encoded and transmitted
+    #[cfg(feature = "std")]
+    tx_queue: VecDeque<Vec<u8>>,
+    /// Frames (length, headers, payload and FCS) waiting to be encoded and transmitted
+    #[cfg(not(feature = "std"))]
+    tx_queue: Deque<Vec<u8, ASK_MAX_PAYLOAD_LEN_USIZE>, TXQ>,
     this_address: u8,
     promiscuous: bool,
+    subscriptions: [Option<(u8, AddressKind)>; ASK_ADDRESS_FILTER_LEN],
+    preamble_len: u8,
 
     /// Destination address for the outgoing message header.
     /// This field is used when preparing the `to` header byte during transmission.
@@ -190,9 +287,9 @@ where
     /// Typically incremented per message to help receivers detect duplicates.
     pub tx_header_id: u8,
 
-    /// Custom user-defined flags for the outgoing message.
-    /// Useful for acknowledgments, control bits, or application-specific use.
-    pub tx_header_flags: u8,
+    /// Flags for the outgoing message.
+    /// Only the application bits can be set, see [`HeaderFlags::from_application()`].
+    pub tx_header_flags: HeaderFlags,
 
     /// Destination address from the last received message.
     /// Parsed from the incoming packet header.
@@ -206,9 +303,9 @@ where
     /// Can be used to detect duplicate packets or correlate replies.
     pub rx_header_id: u8,
 
-    /// Custom flags from the last received message.
-    /// Application-specific semantics (e.g., ACK bit, message type).
-    pub rx_header_flags: u8,
+    /// Flags from the last received message.
+    /// Holds both the protocol bits (e.g. ACK) and the application bits.
+    pub rx_header_flags: HeaderFlags,
     ptt_inverted: bool,
 
     /// Index into the transmission buffer, pointing to the current symbol being transmitted.
@@ -222,95 +319,182 @@ where
     tx_sample: u8,
     tx_buf_len: u8,
 
-    /// Counter of successfully completed transmissions.
-    /// Incremented when the transmit buffer is sent in full and the driver returns to idle.
-    pub tx_good: u16,
+    /// Transmit and receive counters, see [`stats()`](AskDriver::stats).
+    stats: AskStats,
+
+    /// Whether frames are sent with forward error correction.
+    fec: bool,
+
+    /// Whether `rc_decoder` is fed the RX samples.
+    rc_switch_rx: bool,
+
+    /// Decodes fixed-code remotes next to the PLL.
+    rc_decoder: RcSwitchDecoder,
 
-    /// Counter of failed or invalid received messages.
-    /// Typically incremented when a CRC check fails or an invalid header is detected.
-    pub rx_bad: u16,
+    /// The fixed-code remote code word being sent, if any.
+    rc_tx: Option<RcTransmitter>,
+
+    /// The frame check sequence used to frame and validate frames
+    checksum: PhantomData<CS>,
+
+    /// The headers sent in and expected from frames
+    format: PhantomData<FF>,
+
+    /// Validated packets waiting to be read by `receive()`
+    #[cfg(feature = "std")]
+    rx_queue: VecDeque<AskPacket>,
+    /// Validated packets waiting to be read by `receive()`
+    #[cfg(not(feature = "std"))]
+    rx_queue: Deque<AskPacket, RXQ>,
 
-    /// Counter of successfully received and validated messages.
-    /// Incremented after a complete and CRC-valid packet is accepted.
-    pub rx_good: u16,
-    rx_buf_valid: bool,
+    /// Set when the last queued frame has been fully transmitted.
+    /// Read and cleared by [`take_tx_complete()`](AskDriver::take_tx_complete).
+    tx_complete: bool,
 }
 
-impl<TX, RX, PTT> AskDriver<TX, RX, PTT>
+impl<TX, RX, PTT, const TXQ: usize, const RXQ: usize, LC, CS, FF, RXEN, TXEN>
+    AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>
 where
     TX: OutputPin,
     RX: InputPin,
     PTT: OutputPin,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
 {
-    const PREAMBLE: [u8; ASK_PREAMBLE_LEN as usize] =
-        [0x2a, 0x2a, 0x2a, 0x2a, 0x2a, 0x2a, 0x38, 0x2c];
+    /// Symbol sent repeatedly at the start of the preamble to train the receiver PLL.
+    const TRAINING_SYMBOL: u8 = 0x2a;
+
+    /// The two 6-bit symbols that make up the 12-bit start symbol ending the preamble.
+    const START_SYMBOLS: [u8; 2] = [0x38, 0x2c];
+
+    /// The longest message that fits a frame with this driver's [`Checksum`] and
+    /// [`FrameFormat`].
+    ///
+    /// [`ASK_MAX_MESSAGE_LEN`] with the default [`Crc16Ccitt`], less with a longer FCS.
+    pub const MAX_MESSAGE_LEN: u8 = {
+        let fits = ASK_MAX_PAYLOAD_LEN - 1 - FF::HEADER_LEN - CS::LEN;
+        if fits < ASK_MAX_MESSAGE_LEN {
+            fits
+        } else {
+            ASK_MAX_MESSAGE_LEN
+        }
+    };
+
+    /// The longest message that fits a frame sent with forward error correction.
+    ///
+    /// The frame, with its payload and FCS coded, still has to fit [`ASK_MAX_PAYLOAD_LEN`].
+    /// [`MAX_MESSAGE_LEN`](AskDriver::MAX_MESSAGE_LEN) for a [`FrameFormat`] without
+    /// header flags, whose frames are never coded.
+    pub const MAX_FEC_MESSAGE_LEN: u8 = match FF::FLAGS_OFFSET {
+        Some(_) => {
+            ((ASK_MAX_PAYLOAD_LEN - 1 - FF::HEADER_LEN) as usize / FEC_BLOCK_LEN * FEC_DATA_LEN)
+                as u8
+                - CS::LEN
+        }
+        None => Self::MAX_MESSAGE_LEN,
+    };
 
-    /// Creates a new `AskDriver` instance with the given TX and RX pins.
+    /// Creates a new `AskDriver` instance with the given pins and configuration.
     ///
     /// # Arguments
-    /// - `tx`: The output pin used to drive the 433 MHz transmitter (carrier on/off).
+    /// - `tx`: The output pin used to drive the 433 MHz transmitter (carrier on/off).
     /// - `rx`: The input pin used to sample the receiver output (OOK signal).
     /// - `ptt`: The optional push to talk pin output singnal.
-    /// - `ticks_per_bit`: Number of `tick()` calls per bit period (e.g., 8 for 2 kbps).
-    /// - `ptt_inverted`: Whether the ptt signals should be inverted (On = LOW, Off = HIGH)
+    /// - `config`: The validated [`AskConfig`] (ticks per bit, pin polarity, address, ...).
     ///
     /// # Returns
     /// A fully initialized `AskDriver` ready to use with scheduled `tick()` calls.
     ///
+    /// # Errors
+    /// - [`AskError::Tx`] if the TX pin could not be driven `LOW`
+    /// - [`AskError::Ptt`] if the PTT pin could not be released
+    ///
     /// # Notes
-    /// TX is driven `LOW` initially (carrier off).
+    /// TX is driven `LOW` initially (carrier off). The configured address is used both
+    /// to filter incoming frames and as the initial `tx_header_from`.
     pub fn new(
         tx: TX,
         rx: RX,
         ptt: Option<PTT>,
-        ticks_per_bit: u8,
-        ptt_inverted: Option<bool>,
-        rx_inverted: Option<bool>,
-    ) -> Self {
-        let ptt_invert = match ptt_inverted {
-            Some(ptt) => ptt,
-            None => false,
-        };
-        #[allow(unused_mut)]
+        config: AskConfig,
+    ) -> Result<Self, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
         let mut tx = tx;
-        let _ = tx.set_low(); // Ensure idle
-        let mut tx_buf = Vec::new();
-        let _ = tx_buf.extend_from_slice(&Self::PREAMBLE);
-        let rx_invert = match rx_inverted {
-            Some(rxi) => rxi,
-            None => false,
-        };
+        tx.set_low().map_err(AskError::Tx)?; // Ensure idle
+        let ticks_per_bit = config.ticks_per_bit();
         let mut cls = Self {
             mode: AskMode::Idle,
             tx,
             rx,
-            pll: SoftwarePLL::new(ticks_per_bit, rx_invert),
+            pll: SoftwarePLL::with_min_count(
+                ticks_per_bit,
+                config.rx_inverted(),
+                1 + FF::HEADER_LEN + CS::LEN,
+            )
+            .with_flags_index(FF::FLAGS_OFFSET.map(|offset| 1 + offset)),
             ticks_per_bit,
+            bit_rate: config.bit_rate(),
+            tick_hz: config.tick_hz(),
             tick_counter: 0,
-            tx_buf,
+            ticks: 0,
+            rx_warmup_ticks: config.rx_warmup_ticks(),
+            rx_warmup_remaining: 0,
+            cad_window_bits: config.cad_window_bits(),
+            cad_remaining: 0,
+            cad_return_mode: AskMode::Idle,
+            cad_result: None,
+            csma: config.csma(),
+            csma_max_attempts: config.csma_max_attempts(),
+            csma_pending: false,
+            csma_attempt: 0,
+            csma_backoff: 0,
+            // Nodes with different addresses draw different backoffs
+            rng: 0x2545_f491 ^ u32::from(config.address()) << 16,
+            duplicate_filter: config.duplicate_filter(),
+            duplicate_expiry_ticks: config.duplicate_expiry_ticks(),
+            seen_ids: [None; ASK_DUPLICATE_TABLE_LEN],
+            tx_buf: Vec::new(),
+            #[cfg(feature = "std")]
+            tx_queue: VecDeque::new(),
+            #[cfg(not(feature = "std"))]
+            tx_queue: Deque::new(),
             tx_header_to: BROADCAST_ADDRESS,
-            tx_header_from: BROADCAST_ADDRESS,
+            tx_header_from: config.address(),
             tx_header_id: 0,
-            tx_header_flags: 0,
+            tx_header_flags: HeaderFlags::NONE,
             rx_header_to: 0,
             rx_header_from: 0,
             rx_header_id: 0,
-            rx_header_flags: 0,
-            this_address: BROADCAST_ADDRESS,
-            promiscuous: false,
-            tx_good: 0,
+            rx_header_flags: HeaderFlags::NONE,
+            this_address: config.address(),
+            promiscuous: config.promiscuous(),
+            subscriptions: [None; ASK_ADDRESS_FILTER_LEN],
+            preamble_len: config.preamble_len(),
+            stats: AskStats::default(),
+            fec: config.fec(),
+            rc_switch_rx: config.rc_switch_rx(),
+            rc_decoder: RcSwitchDecoder::new(config.tick_hz()),
+            rc_tx: None,
+            checksum: PhantomData,
+            format: PhantomData,
             ptt,
-            ptt_inverted: ptt_invert,
+            rx_enable: None,
+            tx_enable: None,
+            ptt_inverted: config.ptt_inverted(),
             tx_index: 0,
             tx_bit: 0,
             tx_sample: 0,
             tx_buf_len: 0,
-            rx_good: 0,
-            rx_bad: 0,
-            rx_buf_valid: false,
+            #[cfg(feature = "std")]
+            rx_queue: VecDeque::new(),
+            #[cfg(not(feature = "std"))]
+            rx_queue: Deque::new(),
+            tx_complete: false,
         };
-        cls.set_mode_idle();
-        cls
+        cls.set_mode_idle()?;
+        Ok(cls)
     }
 
     /// Sets the address for this RF module.
@@ -319,388 +503,1238 @@ where
         self.this_address = addr;
     }
 
-    fn write_tx(&mut self, mode: bool) {
+    /// Returns the address of this RF module.
+    pub fn address(&self) -> u8 {
+        self.this_address
+    }
+
+    /// Sets whether frames addressed to other nodes are accepted too.
+    pub fn set_promiscuous(&mut self, promiscuous: bool) {
+        self.promiscuous = promiscuous;
+    }
+
+    /// Subscribes this node to frames sent to `address`, on top of its own address
+    /// and [`BROADCAST_ADDRESS`].
+    ///
+    /// Subscribing to an address again only updates its [`AddressKind`].
+    ///
+    /// # Errors
+    /// [`AskError::BufferOverflow`] if the driver is already subscribed to
+    /// [`ASK_ADDRESS_FILTER_LEN`] addresses.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+    /// use ask433::config::AskConfig;
+    /// use ask433::driver::{AddressKind, AskDriver};
+    ///
+    /// # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
+    /// # let rx_pin = Pin::new(&[]);
+    /// let config = AskConfig::builder().address(0x21).build().unwrap();
+    /// let mut driver: AskDriver<Pin, Pin, Pin> = AskDriver::new(tx_pin, rx_pin, None, config).unwrap();
+    ///
+    /// // Also answer to the light's old address, and listen to all lights on floor 2
+    /// driver.subscribe(0x11, AddressKind::Unicast).unwrap();
+    /// driver.subscribe(0xe2, AddressKind::Group).unwrap();
+    /// assert!(driver.is_group_address(0xe2));
+    /// # driver.tx.done();
+    /// # driver.rx.done();
+    /// ```
+    pub fn subscribe(
+        &mut self,
+        address: u8,
+        kind: AddressKind,
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let slot = match self
+            .subscriptions
+            .iter()
+            .position(|s| s.is_some_and(|(a, _)| a == address))
+        {
+            Some(index) => index,
+            None => self
+                .subscriptions
+                .iter()
+                .position(Option::is_none)
+                .ok_or(AskError::BufferOverflow)?,
+        };
+        self.subscriptions[slot] = Some((address, kind));
+        Ok(())
+    }
+
+    /// Stops accepting frames sent to `address`. Returns `false` if the driver was
+    /// not subscribed to it.
+    pub fn unsubscribe(&mut self, address: u8) -> bool {
+        match self
+            .subscriptions
+            .iter_mut()
+            .find(|s| s.is_some_and(|(a, _)| a == address))
+        {
+            Some(slot) => {
+                *slot = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Iterates over the subscribed addresses and their kinds.
+    pub fn subscriptions(&self) -> impl Iterator<Item = (u8, AddressKind)> + '_ {
+        self.subscriptions.iter().flatten().copied()
+    }
+
+    /// Returns `true` if `address` is [`BROADCAST_ADDRESS`] or a subscribed
+    /// [`AddressKind::Group`] address, i.e. frames sent to it reach several nodes.
+    pub fn is_group_address(&self, address: u8) -> bool {
+        address == BROADCAST_ADDRESS
+            || self
+                .subscriptions()
+                .any(|s| s == (address, AddressKind::Group))
+    }
+
+    /// Returns `true` if frames sent to `address` are accepted by this node.
+    pub fn accepts(&self, address: u8) -> bool {
+        self.promiscuous
+            || address == self.this_address
+            || address == BROADCAST_ADDRESS
+            || self.subscriptions().any(|(a, _)| a == address)
+    }
+
+    /// Sets whether the channel is checked for activity before each frame is transmitted.
+    ///
+    /// See [`AskConfigBuilder::csma()`](crate::config::AskConfigBuilder::csma).
+    /// Frames that are already waiting for a clear channel are not affected.
+    pub fn set_csma(&mut self, enabled: bool) {
+        self.csma = enabled;
+    }
+
+    /// Sets whether frames are sent with forward error correction.
+    ///
+    /// See [`AskConfigBuilder::fec()`](crate::config::AskConfigBuilder::fec).
+    /// Frames that are already queued are sent as they were queued.
+    pub fn set_fec(&mut self, enabled: bool) {
+        self.fec = enabled;
+    }
+
+    /// Returns the longest message [`send()`](AskDriver::send) accepts:
+    /// [`MAX_FEC_MESSAGE_LEN`](AskDriver::MAX_FEC_MESSAGE_LEN) with forward error
+    /// correction enabled, [`MAX_MESSAGE_LEN`](AskDriver::MAX_MESSAGE_LEN) otherwise.
+    pub fn max_message_len(&self) -> u8 {
+        if self.fec_active() {
+            Self::MAX_FEC_MESSAGE_LEN
+        } else {
+            Self::MAX_MESSAGE_LEN
+        }
+    }
+
+    /// Sets whether frames repeating the last header id of their sender are dropped.
+    ///
+    /// See [`AskConfigBuilder::duplicate_filter()`](crate::config::AskConfigBuilder::duplicate_filter).
+    /// Turning the filter off forgets the ids seen so far.
+    pub fn set_duplicate_filter(&mut self, enabled: bool) {
+        self.duplicate_filter = enabled;
+        if !enabled {
+            self.seen_ids = [None; ASK_DUPLICATE_TABLE_LEN];
+        }
+    }
+
+    /// Returns the current on-air bit rate.
+    pub fn bit_rate(&self) -> BitRate {
+        self.bit_rate
+    }
+
+    /// Returns the rate (in Hz) at which `tick()` is expected to be called.
+    pub fn tick_hz(&self) -> u32 {
+        self.tick_hz
+    }
+
+    /// Returns the number of `tick()` calls per bit at the current bit rate.
+    pub fn ticks_per_bit(&self) -> u8 {
+        self.ticks_per_bit
+    }
+
+    /// Changes the on-air bit rate without changing the tick rate.
+    ///
+    /// The ticks per bit are recomputed from the tick rate the driver was configured
+    /// with ([`AskConfig::tick_hz()`]), and the PLL is re-timed. Any frame being
+    /// received is dropped.
+    ///
+    /// # Errors
+    /// - [`AskError::Busy`] while frames are being transmitted or queued
+    /// - [`AskError::Config`] with [`ConfigError::InvalidTickRate`]
+    ///   if the tick rate gives too few or too many ticks per bit at `bit_rate`
+    ///
+    /// # Example
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+    /// use ask433::config::{AskConfig, BitRate};
+    /// use ask433::driver::AskDriver;
+    ///
+    /// # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
+    /// # let rx_pin = Pin::new(&[]);
+    /// let config = AskConfig::builder().tick_hz(16_000).build().unwrap();
+    /// let mut driver: AskDriver<Pin, Pin, Pin> =
+    ///     AskDriver::new(tx_pin, rx_pin, None, config).unwrap();
+    ///
+    /// driver.set_bitrate(BitRate::Bps1000).unwrap();
+    /// assert_eq!(driver.ticks_per_bit(), 16);
+    /// # driver.tx.done();
+    /// # driver.rx.done();
+    /// ```
+    pub fn set_bitrate(
+        &mut self,
+        bit_rate: BitRate,
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if self.is_tx_busy() {
+            return Err(AskError::Busy);
+        }
+        let ticks_per_bit = ticks_per_bit_for(self.tick_hz, bit_rate)
+            .ok_or(AskError::Config(ConfigError::InvalidTickRate(self.tick_hz)))?;
+        self.bit_rate = bit_rate;
+        self.ticks_per_bit = ticks_per_bit;
+        self.tick_counter = 0;
+        self.pll.set_ticks_per_bit(ticks_per_bit);
+        Ok(())
+    }
+
+    /// Hands the driver the pins that switch the receiver and transmitter modules' supply.
+    ///
+    /// Both pins are active `HIGH` and optional. They are driven to match the current
+    /// mode straight away and from then on by every mode change:
+    /// - the receiver is powered in every mode except [`AskMode::Sleep`]
+    /// - the transmitter is only powered in [`AskMode::Tx`]
+    ///
+    /// # Errors
+    /// - [`AskError::RxEnable`] if the receiver enable pin could not be driven
+    /// - [`AskError::TxEnable`] if the transmitter enable pin could not be driven
+    ///
+    /// # Example
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+    /// use ask433::config::AskConfig;
+    /// use ask433::driver::AskDriver;
+    ///
+    /// # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
+    /// # let rx_pin = Pin::new(&[]);
+    /// # let rx_en = Pin::new(&[PinTransaction::set(PinState::High)]);
+    /// # let tx_en = Pin::new(&[PinTransaction::set(PinState::Low)]);
+    /// let mut driver: AskDriver<Pin, Pin, Pin> =
+    ///     AskDriver::new(tx_pin, rx_pin, None, AskConfig::default()).unwrap();
+    /// driver.set_power_pins(Some(rx_en), Some(tx_en)).unwrap();
+    /// # driver.tx.done();
+    /// # driver.rx.done();
+    /// # driver.rx_enable.as_mut().unwrap().done();
+    /// # driver.tx_enable.as_mut().unwrap().done();
+    /// ```
+    pub fn set_power_pins(
+        &mut self,
+        rx_enable: Option<RXEN>,
+        tx_enable: Option<TXEN>,
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        self.rx_enable = rx_enable;
+        self.tx_enable = tx_enable;
+        self.write_rx_enable(self.mode != AskMode::Sleep)?;
+        self.write_tx_enable(self.mode == AskMode::Tx)
+    }
+
+    fn write_tx(&mut self, mode: bool) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
         if mode {
-            self.tx.set_high().unwrap();
+            self.tx.set_high().map_err(AskError::Tx)
         } else {
-            self.tx.set_low().unwrap();
+            self.tx.set_low().map_err(AskError::Tx)
         }
     }
 
-    fn write_ptt(&mut self, mode: bool) {
+    fn write_ptt(&mut self, mode: bool) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
         let state = if self.ptt_inverted { !mode } else { mode };
         if let Some(ref mut ptt) = self.ptt {
             if state {
-                let _ = ptt.set_high();
+                ptt.set_high().map_err(AskError::Ptt)?;
+            } else {
+                ptt.set_low().map_err(AskError::Ptt)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_rx_enable(&mut self, on: bool) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if let Some(ref mut pin) = self.rx_enable {
+            if on {
+                pin.set_high().map_err(AskError::RxEnable)?;
+            } else {
+                pin.set_low().map_err(AskError::RxEnable)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_tx_enable(&mut self, on: bool) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if let Some(ref mut pin) = self.tx_enable {
+            if on {
+                pin.set_high().map_err(AskError::TxEnable)?;
             } else {
-                let _ = ptt.set_low();
+                pin.set_low().map_err(AskError::TxEnable)?;
             }
         }
+        Ok(())
+    }
+
+    /// Powers the receiver back up if the driver is asleep and starts its warm-up time.
+    fn wake(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if self.mode == AskMode::Sleep {
+            self.write_rx_enable(true)?;
+            self.rx_warmup_remaining = self.rx_warmup_ticks;
+        }
+        Ok(())
     }
 
-    /// Sets the driver into sleep mode.
-    pub fn set_mode_idle(&mut self) {
+    /// Sets the driver into idle mode.
+    ///
+    /// Wakes the driver if it is asleep.
+    ///
+    /// # Errors
+    /// - [`AskError::Ptt`], [`AskError::Tx`] or [`AskError::TxEnable`] if the transmitter
+    ///   could not be keyed off
+    /// - [`AskError::RxEnable`] if the receiver could not be powered up
+    pub fn set_mode_idle(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
         if self.mode != AskMode::Idle {
-            self.write_ptt(false);
-            self.write_tx(false);
+            self.wake()?;
+            self.write_ptt(false)?;
+            self.write_tx(false)?;
+            self.write_tx_enable(false)?;
             self.mode = AskMode::Idle;
         }
+        Ok(())
     }
 
     /// Sets the driver into receive mode.
-    pub fn set_mode_rx(&mut self) {
+    ///
+    /// Wakes the driver if it is asleep; the RX pin is then ignored for the
+    /// configured [`rx_warmup_ticks()`](AskConfig::rx_warmup_ticks).
+    ///
+    /// # Errors
+    /// - [`AskError::Ptt`], [`AskError::Tx`] or [`AskError::TxEnable`] if the transmitter
+    ///   could not be keyed off
+    /// - [`AskError::RxEnable`] if the receiver could not be powered up
+    pub fn set_mode_rx(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
         if self.mode != AskMode::Rx {
-            self.write_ptt(false);
-            self.write_tx(false);
+            self.wake()?;
+            self.write_ptt(false)?;
+            self.write_tx(false)?;
+            self.write_tx_enable(false)?;
             self.mode = AskMode::Rx;
         }
+        Ok(())
     }
 
     /// Sets the driver into transmit mode.
-    pub fn set_mode_tx(&mut self) {
+    ///
+    /// # Errors
+    /// - [`AskError::TxEnable`] if the transmitter could not be powered up
+    /// - [`AskError::Ptt`] if the PTT pin could not be asserted
+    /// - [`AskError::RxEnable`] if the driver was asleep and the receiver could not be powered up
+    pub fn set_mode_tx(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
         if self.mode != AskMode::Tx {
             self.tx_index = 0;
             self.tx_bit = 0;
             self.tx_sample = 0;
 
-            self.write_ptt(true);
+            self.wake()?;
+            self.write_tx_enable(true)?;
+            self.write_ptt(true)?;
             self.mode = AskMode::Tx;
         }
+        Ok(())
+    }
+
+    /// Puts the driver to sleep.
+    ///
+    /// The transmitter is keyed off, both modules are powered down through the
+    /// [power pins](AskDriver::set_power_pins) and `tick()` stops sampling the RX pin.
+    /// Any frame being received is dropped; packets already in the receive queue are kept.
+    ///
+    /// Call [`set_mode_idle()`](AskDriver::set_mode_idle) or
+    /// [`set_mode_rx()`](AskDriver::set_mode_rx) to wake up again.
+    ///
+    /// # Errors
+    /// - [`AskError::Busy`] while frames are being transmitted or queued
+    /// - [`AskError::Ptt`], [`AskError::Tx`], [`AskError::TxEnable`] or
+    ///   [`AskError::RxEnable`] if a pin could not be driven
+    pub fn set_mode_sleep(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if self.mode != AskMode::Sleep {
+            if self.is_tx_busy() {
+                return Err(AskError::Busy);
+            }
+            self.write_ptt(false)?;
+            self.write_tx(false)?;
+            self.write_tx_enable(false)?;
+            self.write_rx_enable(false)?;
+            self.pll.set_ticks_per_bit(self.ticks_per_bit);
+            self.mode = AskMode::Sleep;
+        }
+        Ok(())
+    }
+
+    /// Starts listening for channel activity.
+    ///
+    /// The driver switches to [`AskMode::Cad`] and samples the RX pin through the
+    /// software PLL for the configured [`cad_window_bits()`](AskConfig::cad_window_bits).
+    /// The channel is reported busy if a start symbol is seen, or if the edges on
+    /// the RX pin are spaced by whole bit periods the way a real ASK transmission is
+    /// (receiver noise is not). The window ends early as soon as a start symbol is seen.
+    ///
+    /// Afterwards the driver goes back to [`AskMode::Idle`] or [`AskMode::Rx`], whichever
+    /// it was in, and the outcome can be read with [`cad_result()`](AskDriver::cad_result).
+    /// Started from receive mode, a frame that is being received is not lost.
+    ///
+    /// # Errors
+    /// - [`AskError::Busy`] while a frame is being transmitted
+    /// - [`AskError::InvalidMode`] while the driver is asleep
+    ///
+    /// # Example
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+    /// use ask433::config::AskConfig;
+    /// use ask433::driver::AskDriver;
+    ///
+    /// # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
+    /// # let rx_pin = Pin::new(&core::array::from_fn::<_, 64, _>(|_| PinTransaction::get(PinState::Low)));
+    /// let config = AskConfig::builder().cad_window_bits(8).build().unwrap();
+    /// let mut driver: AskDriver<Pin, Pin, Pin> =
+    ///     AskDriver::new(tx_pin, rx_pin, None, config).unwrap();
+    ///
+    /// driver.start_cad().unwrap();
+    /// let busy = loop {
+    ///     driver.tick().unwrap(); // Normally done by the timer interrupt
+    ///     match driver.cad_result() {
+    ///         Err(nb::Error::WouldBlock) => continue,
+    ///         result => break result.unwrap(),
+    ///     }
+    /// };
+    /// assert!(!busy);
+    /// # driver.tx.done();
+    /// # driver.rx.done();
+    /// ```
+    pub fn start_cad(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        match self.mode {
+            AskMode::Tx => return Err(AskError::Busy),
+            AskMode::Sleep => return Err(AskError::InvalidMode),
+            AskMode::Cad => return Ok(()),
+            AskMode::Idle => {
+                // The PLL has not been sampling, start from a clean slate
+                self.pll.set_ticks_per_bit(self.ticks_per_bit);
+            }
+            AskMode::Rx => {}
+        }
+        self.cad_return_mode = self.mode;
+        self.cad_remaining = self.cad_window_bits as u16 * self.ticks_per_bit as u16;
+        self.cad_result = None;
+        self.pll.clear_activity();
+        self.mode = AskMode::Cad;
+        Ok(())
+    }
+
+    /// Returns the outcome of the last channel activity detection window.
+    ///
+    /// # Returns
+    /// - `Ok(true)`: The channel was busy
+    /// - `Ok(false)`: The channel was clear
+    ///
+    /// # Errors
+    /// - [`nb::Error::WouldBlock`] while the window is still open
+    /// - [`AskError::InvalidMode`] if no window has completed since the last
+    ///   [`start_cad()`](AskDriver::start_cad), e.g. because it was cut short by a mode change
+    pub fn cad_result(&self) -> nb::Result<bool, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if self.mode == AskMode::Cad {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.cad_result
+            .ok_or(nb::Error::Other(AskError::InvalidMode))
+    }
+
+    /// Counts down the CAD window and, once it is over, records whether the channel was busy.
+    fn cad_tick(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        self.cad_remaining = self.cad_remaining.saturating_sub(1);
+        let start_seen = self.pll.active;
+        if !start_seen && self.cad_remaining > 0 {
+            return Ok(());
+        }
+        let edges = self.pll.edges();
+        let min_edges = (self.cad_window_bits / 4) as u16;
+        // The first edge has nothing to be measured against
+        let regular = edges >= min_edges
+            && u32::from(self.pll.regular_edges()) * 4 >= u32::from(edges.saturating_sub(1)) * 3;
+        let busy = start_seen || regular;
+        if busy {
+            self.stats.cad_busy = self.stats.cad_busy.saturating_add(1);
+        } else {
+            self.stats.cad_clear = self.stats.cad_clear.saturating_add(1);
+        }
+        self.cad_result = Some(busy);
+        self.mode = self.cad_return_mode;
+
+        if self.csma_pending {
+            self.csma_decide(busy)
+        } else if !self.tx_queue.is_empty() {
+            // Frames were queued while the channel was being checked
+            self.start_tx()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sends the frame at the front of the transmit queue, or starts listening
+    /// to the channel first if CSMA is enabled.
+    fn start_tx(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if self.csma {
+            self.csma_pending = true;
+            self.csma_attempt = 0;
+            self.start_cad()
+        } else {
+            self.start_next_frame().map(|_| ())
+        }
+    }
+
+    /// Acts on the outcome of a CSMA channel check: transmit, back off or give up.
+    fn csma_decide(&mut self, busy: bool) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if !busy {
+            self.csma_pending = false;
+            return self.start_next_frame().map(|_| ());
+        }
+        self.csma_attempt += 1;
+        if self.csma_attempt < self.csma_max_attempts {
+            self.stats.csma_backoffs = self.stats.csma_backoffs.saturating_add(1);
+            self.csma_backoff = self.backoff_ticks();
+            return Ok(());
+        }
+        // The channel stayed busy, give up on this frame
+        let _ = self.tx_queue.pop_front();
+        self.stats.csma_failures = self.stats.csma_failures.saturating_add(1);
+        self.csma_pending = false;
+        if self.tx_queue.is_empty() {
+            self.tx_complete = true;
+            Ok(())
+        } else {
+            self.start_tx()
+        }
+    }
+
+    /// Draws a random backoff: up to one CAD window for the first retry,
+    /// doubling with every further retry (binary exponential backoff).
+    fn backoff_ticks(&mut self) -> u16 {
+        // xorshift32, stirred with the tick counter so that nodes sharing a seed drift apart
+        let mut x = self.rng ^ self.ticks;
+        if x == 0 {
+            x = 0x2545_f491;
+        }
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.rng = x;
+
+        let window = u32::from(self.cad_window_bits) * u32::from(self.ticks_per_bit);
+        let max = (window << (self.csma_attempt - 1).min(5)).min(u16::MAX as u32);
+        (1 + x % max) as u16
+    }
+
+    /// Returns `true` while a frame or remote code is being transmitted, or frames are
+    /// waiting in the transmit queue.
+    pub fn is_tx_busy(&self) -> bool {
+        self.mode == AskMode::Tx || !self.tx_queue.is_empty()
+    }
+
+    /// Returns the number of frames waiting in the transmit queue,
+    /// not counting the frame currently on air.
+    pub fn tx_queue_len(&self) -> usize {
+        self.tx_queue.len()
+    }
+
+    /// Returns `true` once if the transmit queue has drained since the last call.
+    ///
+    /// The flag is set by [`tick()`](AskDriver::tick) after the last bit of the last
+    /// queued frame has been sent, and cleared by this call or when the next frame is queued.
+    pub fn take_tx_complete(&mut self) -> bool {
+        core::mem::take(&mut self.tx_complete)
     }
 
     /// Checks whether a valid, complete message is available to be received.
     ///
-    /// This method transitions the driver into receive mode (if not already there),
-    /// checks whether the internal demodulation buffer is full, and if so,
-    /// runs validation (via `validate_rx_buf()`) to determine whether the message is valid.
+    /// This method transitions the driver into receive mode (if not already there)
+    /// and reports whether any validated message is waiting in the receive queue.
     ///
     /// # Behavior
-    /// - If the driver is currently transmitting ([`AskMode::Tx`]), returns `false`
+    /// - If the driver is currently transmitting ([`AskMode::Tx`]), asleep
+    ///   ([`AskMode::Sleep`]) or checking the channel ([`AskMode::Cad`]), the mode is left
+    ///   alone and only messages that were already queued are reported
     /// - If the software PLL has filled its buffer (`AskDriver.pll.full == true`), calls
-    ///   [`validate_rx_buf()`](AskDriver::validate_rx_buf) to perform a CRC check and extract headers
-    /// - Marks the buffer as no longer full after validation
-    /// - Returns the value of `self.rx_buf_valid`, indicating whether a valid message is now present
+    ///   [`validate_rx_buf()`](AskDriver::validate_rx_buf) to move the frame into the queue
+    /// - Returns whether the receive queue holds at least one message
     ///
     /// # Returns
-    /// - `true`: A valid, fully received message is available in the RX buffer
-    /// - `false`: No valid message is available, or the driver is currently transmitting
+    /// - `Ok(true)`: A valid, fully received message is waiting in the receive queue
+    /// - `Ok(false)`: The receive queue is empty
+    ///
+    /// # Errors
+    /// Returns the pin error if switching into receive mode failed.
     ///
     /// # Notes
     /// - Calling this function does **not** consume the message; use [`receive()`](AskDriver::receive)
     ///   to access the payload after this returns `true`.
-    /// - This function may trigger a transition from [`AskMode::Idle`] or [`AskMode::Sleep`]
-    ///   to [`AskMode::Rx`] if receive mode is not already active.
+    /// - This function may trigger a transition from [`AskMode::Idle`] to [`AskMode::Rx`]
+    ///   if receive mode is not already active.
     ///
     /// # Side Effects
     /// - May cause internal state transition via `set_mode_rx()`
-    /// - Clears the `pll.full` flag after validation
     ///
     /// # See also
     /// - [`AskDriver::receive()`]
     /// - [`AskDriver::validate_rx_buf()`]
-    pub fn availabile(&mut self) -> bool {
-        if self.mode == AskMode::Tx {
-            return false;
-        }
-        self.set_mode_rx();
-        if self.pll.full {
-            self.validate_rx_buf();
-            self.pll.full = false;
+    pub fn availabile(&mut self) -> Result<bool, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if !matches!(self.mode, AskMode::Tx | AskMode::Sleep | AskMode::Cad) {
+            self.set_mode_rx()?;
+            if self.pll.full && !self.pll.active {
+                self.validate_rx_buf();
+            }
         }
-        return self.rx_buf_valid;
+        Ok(!self.rx_queue.is_empty())
+    }
+
+    /// Returns the last code received from a fixed-code remote, if one arrived since
+    /// the last call.
+    ///
+    /// Codes are only decoded while receiving with
+    /// [`AskConfigBuilder::rc_switch_rx()`](crate::config::AskConfigBuilder::rc_switch_rx)
+    /// set. A code repeated for as long as a button is held is reported every other repeat.
+    pub fn receive_rc_code(&mut self) -> Option<RcCode> {
+        self.rc_decoder.take_code()
+    }
+
+    /// Sets whether fixed-code remotes are decoded next to RadioHead frames.
+    ///
+    /// See [`AskConfigBuilder::rc_switch_rx()`](crate::config::AskConfigBuilder::rc_switch_rx).
+    pub fn set_rc_switch_rx(&mut self, enabled: bool) {
+        self.rc_switch_rx = enabled;
+    }
+
+    /// Returns the number of validated messages waiting in the receive queue.
+    pub fn rx_queue_len(&self) -> usize {
+        self.rx_queue.len()
     }
 
-    /// Validates the received message buffer using CRC-CCITT and extracts header metadata.
+    /// Validates the frame in the PLL buffer and moves it into the receive queue.
     ///
-    /// This method is called after a full message has been received and decoded into
-    /// `rx_buf`. It performs a CRC check using the CRC-CCITT (XModem) algorithm
-    /// to verify message integrity, and if valid, extracts the protocol headers
-    /// (to, from, id, flags) from the payload.
+    /// This method is called by `tick()` as soon as a full message has been received
+    /// and decoded into `pll.buf`. It verifies the frame check sequence with the
+    /// driver's [`Checksum`] to check message integrity, and if valid, checks the `to`
+    /// header against this node's address.
     ///
     /// # Behavior
-    /// - Computes the CRC over the entire `rx_buf`
-    /// - Compares the result against the expected terminal CRC value `0xF0B8`
-    /// - If the CRC is invalid:
-    ///   - Increments `rx_bad`
-    ///   - Marks the message as invalid (`rx_buf_valid = false`)
-    /// - If the CRC is valid:
-    ///   - Extracts the four header fields from the buffer:
-    ///     - `to` (at index 1)
-    ///     - `from` (at index 2)
-    ///     - `id` (at index 3)
-    ///     - `flags` (at index 4)
-    ///   - Increments `rx_good`
-    ///   - Marks the buffer as valid (`rx_buf_valid = true`) if:
-    ///     - The message is broadcast, or
-    ///     - The receiver is in promiscuous mode, or
-    ///     - The `to` address matches `this_address`
+    /// - Verifies the FCS at the end of `pll.buf`
+    /// - If the FCS is invalid, counts a CRC error and drops the frame
+    /// - If the FCS is valid, an [`AskPacket`] is queued and counted as good if:
+    ///   - The message is broadcast, or
+    ///   - The receiver is in promiscuous mode, or
+    ///   - The `to` header (at index 1) matches `this_address`
+    /// - If the receive queue is full, the new packet is dropped and counted as a queue overflow
+    /// - The PLL buffer is cleared in every case, ready for the next frame
     ///
     /// # Notes
-    /// - Assumes the message format aligns with the RadioHead convention, where
-    ///   the first byte is the length and the next four are protocol headers.
-    /// - This method also transitions the driver to [`AskMode::Idle`] as part of
-    ///   RX completion handling.
+    /// - The first byte is the length, followed by the headers of the driver's
+    ///   [`FrameFormat`]. Headerless frames are treated as broadcasts.
+    /// - The packet is stamped with the current [`ticks()`](AskDriver::ticks) and the
+    ///   PLL's [`quality()`](SoftwarePLL::quality) for the frame.
     pub fn validate_rx_buf(&mut self) {
-        let mut crc: u16 = 0xffff;
-        for b in &self.pll.buf {
-            crc = crc_ccitt_update(crc, b);
-        }
-        if crc != 0xf0b8 {
-            // CRC when buffer and expected CRC are CRC'd
+        self.pll.full = false;
+        if !CS::verify(&self.pll.buf) {
             // Reject and drop the message
-            self.rx_bad += 1;
-            self.rx_buf_valid = false;
-            return;
-        }
-
-        // Extract the 4 headers that follow the message length
-        self.rx_header_to = self.pll.buf[1];
-        self.rx_header_from = self.pll.buf[2];
-        self.rx_header_id = self.pll.buf[3];
-        self.rx_header_flags = self.pll.buf[4];
-        if self.promiscuous
-            || self.rx_header_to == self.this_address
-            || self.rx_header_to == BROADCAST_ADDRESS
-        {
-            self.rx_good += 1;
-            self.rx_buf_valid = true;
+            self.stats.rx_crc_errors = self.stats.rx_crc_errors.saturating_add(1);
+        } else if let Some(packet) = self.rx_packet() {
+            if !self.accepts(packet.to) {
+                self.stats.rx_filtered = self.stats.rx_filtered.saturating_add(1);
+            } else if self.is_duplicate(&packet) {
+                self.stats.rx_duplicates = self.stats.rx_duplicates.saturating_add(1);
+            } else if self.queue_rx_packet(packet) {
+                self.stats.rx_good = self.stats.rx_good.saturating_add(1);
+            } else {
+                self.stats.rx_queue_overflows = self.stats.rx_queue_overflows.saturating_add(1);
+            }
+        } else {
+            self.stats.rx_bad_length = self.stats.rx_bad_length.saturating_add(1);
         }
+        self.pll.buf.clear();
+        self.pll.buf_len = 0;
     }
 
-    /// Returns a slice of the received message payload, if a valid message is available.
-    ///
-    /// This method checks whether a complete and validated message is available
-    /// in the internal receive buffer (via [`AskDriver::availabile()`]), and if so,
-    /// returns a slice containing just the message payload, excluding the protocol headers
-    /// and CRC trailer.
-    ///
-    /// # Returns
-    /// - `Some(Vec<u8>)`: The message payload
-    /// - `None`: If no valid message has been received or the buffer is incomplete
+    /// Builds an [`AskPacket`] from the frame in `pll.buf`.
     ///
-    /// # Message Layout
-    /// The internal buffer (`self.pll.buf`) is assumed to follow the structure:
-    /// `[len, to, from, id, flags, ...payload..., crc_hi, crc_lo]`
-    ///
-    /// This function:
-    /// - Reads the total message length from `self.pll.buf_len`
-    /// - Calculates the offset of the payload by skipping the 4-byte header and 2-byte CRC
-    /// - Returns a slice over just the user data portion of the message
-    ///
-    /// # Notes
-    /// - This does **not** clear the receive buffer; call `self.set_mode_idle()` or
-    ///   a specific buffer-reset method after processing.
-    /// - The returned slice is valid until the next driver state change that modifies the RX buffer.
+    /// The frame is assumed to follow the structure:
+    /// `[len, ...headers..., ...payload..., ...fcs...]`
+    fn rx_packet(&self) -> Option<AskPacket> {
+        let frame = self.pll.buf.as_slice();
+        let payload_start = 1 + FF::HEADER_LEN as usize;
+        let payload = frame.get(payload_start..frame.len().checked_sub(CS::LEN as usize)?)?;
+        let [to, from, id, flags] = FF::decode_header(&frame[1..payload_start]);
+        Some(AskPacket {
+            to,
+            from,
+            id,
+            flags: HeaderFlags::from_bits_retain(flags),
+            #[cfg(feature = "std")]
+            payload: Vec::from(payload),
+            #[cfg(not(feature = "std"))]
+            payload: Vec::from_slice(payload).ok()?,
+            received_at_tick: self.ticks,
+            quality: self.pll.quality(),
+        })
+    }
+
+    /// Records the header id of `packet` and returns `true` if the duplicate filter
+    /// is on and its sender used the same id less than the expiry time ago.
     ///
-    /// # Panics
-    /// May panic if `buf_len` is smaller than `ASK_HEADER_LEN + 1`, but this should never
-    /// happen in a valid message.
-    #[cfg(not(feature = "std"))]
-    pub fn receive(&mut self) -> Option<Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE>> {
-        if !self.availabile() {
-            return None;
+    /// ACK frames are let through, since they repeat the id of the frame they answer,
+    /// and so are headerless frames, which carry no id.
+    fn is_duplicate(&mut self, packet: &AskPacket) -> bool {
+        if !self.duplicate_filter || FF::HEADER_LEN == 0 || packet.flags.is_ack() {
+            return false;
+        }
+        let now = self.ticks;
+        if let Some(seen) = self
+            .seen_ids
+            .iter_mut()
+            .flatten()
+            .find(|s| s.from == packet.from)
+        {
+            let duplicate =
+                seen.id == packet.id && now.wrapping_sub(seen.at) < self.duplicate_expiry_ticks;
+            seen.id = packet.id;
+            seen.at = now;
+            return duplicate;
         }
+        // New sender: take a free slot, or forget the one heard from least recently
+        let slot = match self.seen_ids.iter().position(Option::is_none) {
+            Some(index) => index,
+            None => (0..ASK_DUPLICATE_TABLE_LEN)
+                .max_by_key(|&i| self.seen_ids[i].map_or(0, |s| now.wrapping_sub(s.at)))
+                .unwrap_or(0),
+        };
+        self.seen_ids[slot] = Some(SeenId {
+            from: packet.from,
+            id: packet.id,
+            at: now,
+        });
+        false
+    }
 
-        let message_len: u8 = self.pll.buf_len - 2;
-        self.rx_buf_valid = false;
-        let message =
-            Vec::from_slice(&self.pll.buf[((ASK_HEADER_LEN + 1) as usize)..(message_len as usize)])
-                .unwrap();
-        Some(message)
+    /// Appends `packet` to the receive queue.
+    ///
+    /// Returns `false` if the queue is full.
+    fn queue_rx_packet(&mut self, packet: AskPacket) -> bool {
+        #[cfg(feature = "std")]
+        {
+            if self.rx_queue.len() >= RXQ {
+                return false;
+            }
+            self.rx_queue.push_back(packet);
+            true
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.rx_queue.push_back(packet).is_ok()
+        }
     }
 
-    /// Returns a slice of the received message payload, if a valid message is available.
+    /// Returns the oldest received packet, if a valid message is available.
     ///
-    /// This method checks whether a complete and validated message is available
-    /// in the internal receive buffer (via [`AskDriver::availabile()`]), and if so,
-    /// returns a slice containing just the message payload, excluding the protocol headers
-    /// and CRC trailer.
+    /// This method checks whether a complete and validated message is waiting
+    /// in the receive queue (via [`AskDriver::availabile()`]), and if so, removes
+    /// and returns it. The `rx_header_*` fields are updated from the returned packet.
     ///
     /// # Returns
-    /// - `Some(Vec<u8>)`: The message payload slice
-    /// - `None`: If no valid message has been received or the buffer is incomplete
+    /// - `Ok(Some(AskPacket))`: The headers, payload and reception metadata of the message
+    /// - `Ok(None)`: If no valid message has been received
     ///
-    /// # Message Layout
-    /// The internal buffer (`self.pll.buf`) is assumed to follow the structure:
-    /// `[len, to, from, id, flags, ...payload..., crc_hi, crc_lo]`
+    /// # Errors
+    /// The pin error if switching into receive mode failed.
+    pub fn receive(
+        &mut self,
+    ) -> Result<Option<AskPacket>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if !self.availabile()? {
+            return Ok(None);
+        }
+        let packet = self.rx_queue.pop_front();
+        if let Some(ref packet) = packet {
+            self.rx_header_to = packet.to;
+            self.rx_header_from = packet.from;
+            self.rx_header_id = packet.id;
+            self.rx_header_flags = packet.flags;
+        }
+        Ok(packet)
+    }
+
+    /// Returns the number of `tick()` calls since the driver was created.
     ///
-    /// This function:
-    /// - Reads the total message length from `self.pll.buf_len`
-    /// - Calculates the offset of the payload by skipping the 4-byte header and 2-byte CRC
-    /// - Returns a slice over just the user data portion of the message
+    /// The counter wraps around after `u32::MAX` ticks; received packets are
+    /// stamped with its value in [`AskPacket::received_at_tick`].
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    /// Returns a snapshot of the driver's transmit and receive counters.
     ///
-    /// # Notes
-    /// - This does **not** clear the receive buffer; call `self.set_mode_idle()` or
-    ///   a specific buffer-reset method after processing.
-    /// - The returned slice is valid until the next driver state change that modifies the RX buffer.
+    /// # Example
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+    /// use ask433::config::AskConfig;
+    /// use ask433::driver::AskDriver;
     ///
-    /// # Panics
-    /// May panic if `buf_len` is smaller than `ASK_HEADER_LEN + 1`, but this should never
-    /// happen in a valid message.
-    #[cfg(feature = "std")]
-    pub fn receive(&mut self) -> Option<Vec<u8>> {
-        if !self.availabile() {
-            return None;
-        }
+    /// # let tx = Pin::new(&[PinTransaction::set(PinState::Low)]);
+    /// # let rx = Pin::new(&[]);
+    /// let mut driver: AskDriver<Pin, Pin, Pin> =
+    ///     AskDriver::new(tx, rx, None, AskConfig::default()).unwrap();
+    /// let stats = driver.stats();
+    /// assert_eq!((stats.rx_good, stats.rx_errors()), (0, 0));
+    /// driver.reset_stats();
+    /// # driver.tx.done();
+    /// # driver.rx.done();
+    /// ```
+    pub fn stats(&self) -> AskStats {
+        self.stats
+    }
 
-        let message_len: u8 = self.pll.buf_len - 2;
-        self.rx_buf_valid = false;
-        let message =
-            Vec::from(&self.pll.buf[(ASK_HEADER_LEN + 1) as usize..(message_len as usize)]);
-        Some(message)
+    /// Clears all counters returned by [`stats()`](AskDriver::stats).
+    pub fn reset_stats(&mut self) {
+        self.stats = AskStats::default();
     }
 
     /// Advances the internal transmit/receive state machine by one timing tick.
     ///
-    /// This function must be called at fixed intervals (e.g. every 62.5 µs).
+    /// This function must be called at fixed intervals (e.g. every 62.5 µs).
     /// It handles either advancing transmit state or sampling the input during reception.
     ///
+    /// # Errors
+    /// - [`AskError::Rx`] if the RX pin could not be sampled
+    /// - [`AskError::Tx`] or [`AskError::Ptt`] if the transmitter could not be driven
+    ///
     /// # Timing
     /// Must be called precisely and regularly—ideally via timer interrupt or delay loop.
     ///
+    /// # Sleep
+    /// In [`AskMode::Sleep`] nothing but the tick counter is advanced. After waking up,
+    /// the RX pin is ignored until the configured
+    /// [`rx_warmup_ticks()`](AskConfig::rx_warmup_ticks) have passed.
+    ///
     /// # See also
     /// - [`SoftwarePLL`]
-    pub fn tick(&mut self) {
-        if self.mode == AskMode::Rx {
+    pub fn tick(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        self.ticks = self.ticks.wrapping_add(1);
+        if self.mode == AskMode::Sleep {
+            return Ok(());
+        }
+        let warming_up = self.rx_warmup_remaining > 0;
+        if warming_up {
+            self.rx_warmup_remaining -= 1;
+        }
+        if self.csma_pending && self.mode != AskMode::Cad {
+            // Waiting out a CSMA backoff, then check the channel again
+            if self.csma_backoff > 0 {
+                self.csma_backoff -= 1;
+            } else {
+                self.start_cad()?;
+            }
+        }
+        if matches!(self.mode, AskMode::Rx | AskMode::Cad) {
+            if warming_up {
+                // The receiver output is still settling, don't let it train the PLL
+                return Ok(());
+            }
             // RX always sampled every tick
-            self.pll.update(&mut self.rx);
+            self.pll.update(&mut self.rx).map_err(AskError::Rx)?;
+            if self.rc_switch_rx {
+                self.rc_decoder.update(self.pll.level());
+            }
+            if let Some(drop) = self.pll.take_drop() {
+                self.stats.record_drop(drop);
+            }
+            self.stats.rx_corrected_bits = self
+                .stats
+                .rx_corrected_bits
+                .saturating_add(self.pll.take_corrected().into());
 
-            // Buffer the received byte or flag availability
+            // Move a completed frame into the receive queue
             if self.pll.full && !self.pll.active {
                 self.validate_rx_buf();
             }
+            if self.mode == AskMode::Cad {
+                self.cad_tick()?;
+            }
+        } else if self.mode == AskMode::Tx && self.rc_tx.is_some() {
+            // Remote codes have their own pulse timing
+            self.transmit_rc()?;
         } else if self.mode == AskMode::Tx {
             // TX advances only every `ticks_per_bit` ticks
             self.tick_counter += 1;
             if self.tick_counter >= self.ticks_per_bit {
                 self.tick_counter = 0;
-                self.transmit_bit(); // Move to next TX bit
+                self.transmit_bit()?; // Move to next TX bit
             }
         }
+        Ok(())
     }
 
-    fn wait_packet_sent(&self) -> nb::Result<(), Infallible> {
-        if self.mode == AskMode::Tx {
-            Err(nb::Error::WouldBlock)
-        } else {
-            Ok(())
-        }
-    }
-
-    /// Queues a single byte for transmission over the ASK RF link.
+    /// Queues a message for transmission over the ASK RF link.
+    ///
+    /// The message is framed (length, headers and FCS) and appended to the transmit
+    /// queue. If the transmitter is idle the frame is encoded into 4b6b symbols and
+    /// loaded into the internal transmit buffer straight away; otherwise `tick()`
+    /// starts it as soon as the frames ahead of it have been sent.
+    /// With CSMA enabled, every frame waits for a clear channel check first.
     ///
-    /// This method should encode the byte (e.g. with 4b6b encoding) and load it
-    /// into an internal transmit buffer for use by `tick()`.
+    /// # Errors
+    /// - [`AskError::MessageTooLong`] if `bytes` exceeds [`max_message_len()`](AskDriver::max_message_len)
+    /// - [`AskError::Busy`] if the transmit queue is full
+    /// - [`AskError::InvalidMode`] if the driver is asleep
+    /// - [`AskError::BufferOverflow`] if the encoded frame does not fit the transmit buffer
+    /// - [`AskError::Ptt`] if the PTT pin could not be asserted
     ///
     /// # Note
     /// Actual bit-by-bit transmission is handled incrementally in `tick()`.
+    /// This method never waits for the transmitter; use [`try_send()`](AskDriver::try_send)
+    /// with `nb::block!` if you want to retry until the queue has room.
     #[cfg(feature = "std")]
-    pub fn send(&mut self, bytes: Vec<u8>) -> bool {
-        let mut crc: u16 = 0xffff;
-        let count: u8 = bytes.len() as u8 + 3 + ASK_HEADER_LEN; // Added byte count and FCS and headers to get total number of bytes
-
-        if bytes.len() as u8 > ASK_MAX_MESSAGE_LEN {
-            return false;
-        }
-
-        // Wait for transmitter to become available
-        let _ = block!(self.wait_packet_sent());
-
-        // Encode the message length
-        crc = crc_ccitt_update(crc, &count);
-        let _ = self.tx_buf.extend(encode_4b6b(count));
-
-        // Encode the headers
-        crc = crc_ccitt_update(crc, &self.tx_header_to);
-        let _ = self.tx_buf.extend(encode_4b6b(self.tx_header_to));
-        crc = crc_ccitt_update(crc, &self.tx_header_from);
-        let _ = self.tx_buf.extend(encode_4b6b(self.tx_header_from));
-        crc = crc_ccitt_update(crc, &self.tx_header_id);
-        let _ = self.tx_buf.extend(encode_4b6b(self.tx_header_id));
-        crc = crc_ccitt_update(crc, &self.tx_header_flags);
-        let _ = self.tx_buf.extend(encode_4b6b(self.tx_header_flags));
-
-        // Encode the message into 6 bit symbols. Each byte is converted into
-        // 2 6-bit symbols, high nybble first, low nybble second
-        for b in bytes {
-            crc = crc_ccitt_update(crc, &b);
-            let _ = self.tx_buf.extend(encode_4b6b(b));
-        }
-
-        // Append the fcs, 16 bits before encoding (4 6-bit symbols after encoding)
-        // Caution: VW expects the _ones_complement_ of the CCITT CRC-16 as the FCS
-        // VW sends FCS as low byte then hi byte
-        crc = !crc;
-        let _ = self.tx_buf.push(SYMBOLS[((crc >> 4) & 0xf) as usize]);
-        let _ = self.tx_buf.push(SYMBOLS[(crc & 0xf) as usize]);
-        let _ = self.tx_buf.push(SYMBOLS[((crc >> 12) & 0xf) as usize]);
-        let _ = self.tx_buf.push(SYMBOLS[((crc >> 8) & 0xf) as usize]);
-
-        // Total number of 6-bit symbols to send
-        self.tx_buf_len = self.tx_buf.len() as u8;
-
-        // Start the low level interrupt handler sending symbols
-        self.set_mode_tx();
-
-        return true;
+    pub fn send(&mut self, bytes: Vec<u8>) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        self.send_bytes(&bytes)
     }
 
-    /// Queues a single byte for transmission over the ASK RF link.
+    /// Queues a message for transmission over the ASK RF link.
+    ///
+    /// The message is framed (length, headers and FCS) and appended to the transmit
+    /// queue. If the transmitter is idle the frame is encoded into 4b6b symbols and
+    /// loaded into the internal transmit buffer straight away; otherwise `tick()`
+    /// starts it as soon as the frames ahead of it have been sent.
+    /// With CSMA enabled, every frame waits for a clear channel check first.
     ///
-    /// This method should encode the byte (e.g. with 4b6b encoding) and load it
-    /// into an internal transmit buffer for use by `tick()`.
+    /// # Errors
+    /// - [`AskError::MessageTooLong`] if `bytes` exceeds [`max_message_len()`](AskDriver::max_message_len)
+    /// - [`AskError::Busy`] if the transmit queue is full
+    /// - [`AskError::InvalidMode`] if the driver is asleep
+    /// - [`AskError::BufferOverflow`] if the encoded frame does not fit the transmit buffer
+    /// - [`AskError::Ptt`] if the PTT pin could not be asserted
     ///
     /// # Note
     /// Actual bit-by-bit transmission is handled incrementally in `tick()`.
+    /// This method never waits for the transmitter; use [`try_send()`](AskDriver::try_send)
+    /// with `nb::block!` if you want to retry until the queue has room.
     #[cfg(not(feature = "std"))]
-    pub fn send(&mut self, bytes: Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE>) -> bool {
-        let mut crc: u16 = 0xffff;
-        let count: u8 = bytes.len() as u8 + 3 + ASK_HEADER_LEN; // Added byte count and FCS and headers to get total number of bytes
+    pub fn send(
+        &mut self,
+        bytes: Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE>,
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        self.send_bytes(&bytes)
+    }
 
-        if bytes.len() as u8 > ASK_MAX_MESSAGE_LEN {
-            return false;
+    /// Queues a message for transmission, `nb`-style.
+    ///
+    /// Behaves like [`send()`](AskDriver::send), but takes a slice and reports a full
+    /// transmit queue as [`nb::Error::WouldBlock`] so that the call can be retried
+    /// (e.g. with `nb::block!`) without losing the message.
+    ///
+    /// # Errors
+    /// - [`nb::Error::WouldBlock`] while the transmit queue is full
+    /// - [`nb::Error::Other`] with any other error listed on [`send()`](AskDriver::send)
+    ///
+    /// # Notes
+    /// Never retry this call inside the critical section that guards the driver:
+    /// the queue can only drain while `tick()` is able to run.
+    pub fn try_send(
+        &mut self,
+        bytes: &[u8],
+    ) -> nb::Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        match self.send_bytes(bytes) {
+            Err(AskError::Busy) => Err(nb::Error::WouldBlock),
+            result => result.map_err(nb::Error::Other),
         }
+    }
 
-        // Wait for transmitter to become available
-        let _ = block!(self.wait_packet_sent());
-
-        // Encode the message length
-        crc = crc_ccitt_update(crc, &count);
-        let _ = self.tx_buf.extend(encode_4b6b(count));
-
-        // Encode the headers
-        crc = crc_ccitt_update(crc, &self.tx_header_to);
-        let _ = self.tx_buf.extend(encode_4b6b(self.tx_header_to));
-        crc = crc_ccitt_update(crc, &self.tx_header_from);
-        let _ = self.tx_buf.extend(encode_4b6b(self.tx_header_from));
-        crc = crc_ccitt_update(crc, &self.tx_header_id);
-        let _ = self.tx_buf.extend(encode_4b6b(self.tx_header_id));
-        crc = crc_ccitt_update(crc, &self.tx_header_flags);
-        let _ = self.tx_buf.extend(encode_4b6b(self.tx_header_flags));
-
-        // Encode the message into 6 bit symbols. Each byte is converted into
-        // 2 6-bit symbols, high nybble first, low nybble second
-        for b in bytes {
-            crc = crc_ccitt_update(crc, &b);
-            let _ = self.tx_buf.extend(encode_4b6b(b));
+    /// Sends a code word to a fixed-code receiver, such as a mains outlet, `repeats` times.
+    ///
+    /// The code is sent with its rc-switch protocol timing and pulse length (see
+    /// [`rcswitch`](crate::rcswitch)), through the same TX and PTT pins and `tick()` as
+    /// RadioHead frames. Frames queued meanwhile are sent once the code is done.
+    /// Receivers usually want a few repeats; rc-switch sends
+    /// [`RC_DEFAULT_REPEATS`](crate::rcswitch::RC_DEFAULT_REPEATS).
+    ///
+    /// # Errors
+    /// - [`AskError::InvalidRcCode`] if the code has an unknown protocol, no bits or more than 32 bits
+    /// - [`AskError::Busy`] while frames are being transmitted, queued or waiting for a clear channel
+    /// - [`AskError::InvalidMode`] if the driver is asleep
+    /// - [`AskError::TxEnable`] or [`AskError::Ptt`] if the transmitter could not be keyed up
+    ///
+    /// # Example
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+    /// use ask433::config::AskConfig;
+    /// use ask433::driver::AskDriver;
+    /// use ask433::rcswitch::{RC_DEFAULT_REPEATS, RcCode};
+    ///
+    /// # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
+    /// # let rx_pin = Pin::new(&[]);
+    /// let mut driver: AskDriver<Pin, Pin, Pin> =
+    ///     AskDriver::new(tx_pin, rx_pin, None, AskConfig::default()).unwrap();
+    ///
+    /// // Outlet A on, as printed on the remote's PT2262 DIP switches
+    /// let code = RcCode::from_tri_states(1, "0FFF0FFFFF0F").unwrap();
+    /// driver.send_rc_code(&code, RC_DEFAULT_REPEATS).unwrap();
+    /// assert!(driver.is_tx_busy());
+    /// # driver.tx.done();
+    /// # driver.rx.done();
+    /// ```
+    pub fn send_rc_code(
+        &mut self,
+        code: &RcCode,
+        repeats: u8,
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if self.mode == AskMode::Sleep {
+            return Err(AskError::InvalidMode);
+        }
+        if self.is_tx_busy() || self.csma_pending || self.mode == AskMode::Cad {
+            return Err(AskError::Busy);
         }
+        let transmitter =
+            RcTransmitter::new(code, repeats, self.tick_hz).ok_or(AskError::InvalidRcCode)?;
+        self.set_mode_tx()?;
+        self.rc_tx = Some(transmitter);
+        self.tx_complete = false;
+        Ok(())
+    }
+
+    /// Queues a packet for transmission, using the packet's own header fields.
+    ///
+    /// Unlike [`send()`](AskDriver::send), this does not read the `tx_header_*` fields,
+    /// so every frame can carry its own `to`, `from`, `id` and `flags` without mutating
+    /// driver state. The `received_at_tick` and `quality` fields are ignored.
+    ///
+    /// # Errors
+    /// Same as [`send()`](AskDriver::send).
+    ///
+    /// # Example
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+    /// use ask433::config::AskConfig;
+    /// use ask433::driver::AskDriver;
+    /// use ask433::packet::AskPacket;
+    ///
+    /// # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
+    /// # let rx_pin = Pin::new(&[]);
+    /// let mut driver: AskDriver<Pin, Pin, Pin> =
+    ///     AskDriver::new(tx_pin, rx_pin, None, AskConfig::default()).unwrap();
+    ///
+    /// let mut packet = AskPacket::new(0x10, 0x01);
+    /// packet.id = 1;
+    /// packet.payload.extend(b"21.5C".iter().copied());
+    /// driver.send_packet(&packet).unwrap();
+    /// # driver.tx.done();
+    /// # driver.rx.done();
+    /// ```
+    pub fn send_packet(
+        &mut self,
+        packet: &AskPacket,
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        self.queue_frame(
+            [packet.to, packet.from, packet.id, packet.flags.bits()],
+            &packet.payload,
+        )
+    }
 
-        // Append the fcs, 16 bits before encoding (4 6-bit symbols after encoding)
-        // Caution: VW expects the _ones_complement_ of the CCITT CRC-16 as the FCS
-        // VW sends FCS as low byte then hi byte
-        crc = !crc;
-        let _ = self.tx_buf.push(SYMBOLS[((crc >> 4) & 0xf) as usize]);
-        let _ = self.tx_buf.push(SYMBOLS[(crc & 0xf) as usize]);
-        let _ = self.tx_buf.push(SYMBOLS[((crc >> 12) & 0xf) as usize]);
-        let _ = self.tx_buf.push(SYMBOLS[((crc >> 8) & 0xf) as usize]);
+    fn send_bytes(&mut self, bytes: &[u8]) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let headers = [
+            self.tx_header_to,
+            self.tx_header_from,
+            self.tx_header_id,
+            self.tx_header_flags.bits(),
+        ];
+        self.queue_frame(headers, bytes)
+    }
+
+    /// Frames `bytes` behind `headers` (to, from, id, flags) and appends it to the
+    /// transmit queue, starting the transmitter if it is idle.
+    fn queue_frame(
+        &mut self,
+        headers: [u8; ASK_HEADER_LEN as usize],
+        bytes: &[u8],
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if bytes.len() > self.max_message_len() as usize {
+            return Err(AskError::MessageTooLong);
+        }
+        if self.mode == AskMode::Sleep {
+            return Err(AskError::InvalidMode);
+        }
+        if self.tx_queue.len() >= TXQ {
+            return Err(AskError::Busy);
+        }
+
+        // The FEC flag is the driver's to set, whatever the layers above copied
+        let fec = self.fec_active();
+        let mut headers = headers;
+        headers[3] = (headers[3] & !ASK_FLAGS_FEC) | if fec { ASK_FLAGS_FEC } else { 0 };
+        let headers = FF::encode_header(&headers);
+        let count: u8 = bytes.len() as u8 + 1 + FF::HEADER_LEN + CS::LEN; // Added byte count and FCS and headers to get total number of bytes
+        #[cfg(feature = "std")]
+        let mut frame: Vec<u8> = Vec::with_capacity(count as usize);
+        #[cfg(not(feature = "std"))]
+        let mut frame: Vec<u8, ASK_MAX_PAYLOAD_LEN_USIZE> = Vec::new();
+
+        // Frame layout: message length, headers, message
+        for b in [count].iter().chain(headers.iter()).chain(bytes.iter()) {
+            #[cfg(feature = "std")]
+            frame.push(*b);
+            #[cfg(not(feature = "std"))]
+            frame.push(*b).map_err(|_| AskError::BufferOverflow)?;
+        }
+
+        // Append the fcs, low byte first
+        let fcs = CS::fcs(&frame).to_le_bytes();
+        let fcs = &fcs[..CS::LEN as usize];
+        #[cfg(feature = "std")]
+        frame.extend_from_slice(fcs);
+        #[cfg(not(feature = "std"))]
+        frame
+            .extend_from_slice(fcs)
+            .map_err(|_| AskError::BufferOverflow)?;
+
+        if fec {
+            frame = Self::fec_encode(&frame)?;
+        }
+
+        #[cfg(feature = "std")]
+        self.tx_queue.push_back(frame);
+        #[cfg(not(feature = "std"))]
+        self.tx_queue.push_back(frame).map_err(|_| AskError::Busy)?;
+        self.tx_complete = false;
+
+        if self.mode == AskMode::Tx || self.mode == AskMode::Cad || self.csma_pending {
+            // `tick()` picks the frame up once the transmitter or channel check is done
+            Ok(())
+        } else {
+            self.start_tx()
+        }
+    }
+
+    /// Whether frames are sent with forward error correction: enabled, and the
+    /// frame format has header flags to signal it in.
+    fn fec_active(&self) -> bool {
+        self.fec && FF::FLAGS_OFFSET.is_some()
+    }
+
+    /// Codes the payload and FCS of a frame with forward error correction, behind
+    /// the uncoded count byte and headers.
+    #[cfg(feature = "std")]
+    fn fec_encode(frame: &[u8]) -> Result<Vec<u8>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let (head, body) = frame.split_at(1 + FF::HEADER_LEN as usize);
+        let mut coded = Vec::with_capacity(head.len() + crate::fec::fec_coded_len(body.len()));
+        coded.extend_from_slice(head);
+        for chunk in body.chunks(FEC_DATA_LEN) {
+            // Pad the last block with zeros
+            let mut data = [0; FEC_DATA_LEN];
+            data[..chunk.len()].copy_from_slice(chunk);
+            coded.extend_from_slice(&encode_block(data));
+        }
+        Ok(coded)
+    }
+
+    /// Codes the payload and FCS of a frame with forward error correction, behind
+    /// the uncoded count byte and headers.
+    #[cfg(not(feature = "std"))]
+    fn fec_encode(
+        frame: &[u8],
+    ) -> Result<Vec<u8, ASK_MAX_PAYLOAD_LEN_USIZE>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let (head, body) = frame.split_at(1 + FF::HEADER_LEN as usize);
+        let mut coded = Vec::new();
+        coded
+            .extend_from_slice(head)
+            .map_err(|_| AskError::BufferOverflow)?;
+        for chunk in body.chunks(FEC_DATA_LEN) {
+            // Pad the last block with zeros
+            let mut data = [0; FEC_DATA_LEN];
+            data[..chunk.len()].copy_from_slice(chunk);
+            coded
+                .extend_from_slice(&encode_block(data))
+                .map_err(|_| AskError::BufferOverflow)?;
+        }
+        Ok(coded)
+    }
+
+    /// Pops the next frame off the transmit queue, encodes it into `tx_buf`
+    /// behind a fresh preamble and (re)starts the transmitter.
+    ///
+    /// Returns `Ok(false)` if the queue was empty.
+    fn start_next_frame(&mut self) -> Result<bool, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let Some(frame) = self.tx_queue.pop_front() else {
+            return Ok(false);
+        };
+        self.rc_tx = None;
+        self.tx_buf.clear();
+        for _ in 2..self.preamble_len {
+            self.push_tx_symbols(&[Self::TRAINING_SYMBOL])?;
+        }
+        self.push_tx_symbols(&Self::START_SYMBOLS)?;
+
+        // Encode the frame with the line code. Each byte is converted into
+        // 2 symbols, high nybble first, low nybble second
+        for b in frame.iter() {
+            self.push_tx_symbols(&LC::encode(*b))?;
+        }
 
         // Total number of 6-bit symbols to send
         self.tx_buf_len = self.tx_buf.len() as u8;
+        self.tx_index = 0;
+        self.tx_bit = 0;
+        self.tx_sample = 0;
 
         // Start the low level interrupt handler sending symbols
-        self.set_mode_tx();
+        self.set_mode_tx()?;
+        Ok(true)
+    }
 
-        return true;
+    /// Hands the transmitter over to the next queued frame, or keys it off if
+    /// there is none.
+    fn finish_tx(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if self.csma && !self.tx_queue.is_empty() {
+            // Key off and listen before the next frame
+            self.set_mode_idle()?;
+            self.start_tx()?;
+        } else if !self.start_next_frame()? {
+            // Nothing left to chain into
+            self.tx_complete = true;
+            self.set_mode_idle()?;
+        }
+        Ok(())
+    }
+
+    /// Starts the next pulse part of the remote code word being sent, if one is due.
+    fn transmit_rc(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let Some(transmitter) = self.rc_tx.as_mut() else {
+            return Ok(());
+        };
+        if let Some(level) = transmitter.tick() {
+            self.write_tx(level)
+        } else if transmitter.is_done() {
+            self.rc_tx = None;
+            self.finish_tx()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn push_tx_symbols(
+        &mut self,
+        symbols: &[u8],
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        #[cfg(not(feature = "std"))]
+        self.tx_buf
+            .extend_from_slice(symbols)
+            .map_err(|_| AskError::BufferOverflow)?;
+        #[cfg(feature = "std")]
+        self.tx_buf.extend_from_slice(symbols);
+        Ok(())
     }
 
     /// Advances to the next encoded bit in the transmission sequence.
@@ -710,14 +1744,14 @@ where
     ///
     /// # Note
     /// Not intended for direct user invocation.
-    fn transmit_bit(&mut self) {
+    fn transmit_bit(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
         // Send next bit
         // Symbols are sent LSB first
         // Finished sending the whole message? (after waiting one bit period
         // since the last bit)
         if self.tx_index >= self.tx_buf_len {
-            self.tx_good += 1;
-            self.set_mode_idle();
+            self.stats.tx_good = self.stats.tx_good.saturating_add(1);
+            self.finish_tx()?;
         } else {
             // bit = bit_to_send (Bitwise AND) (1 (Bitwise shift Left) tx_bit)
             // e.g. for bit_to_send = 4 = 00000100
@@ -729,29 +1763,199 @@ where
             // 0001000 & 0001000 = 0001000 (8 = true)
             let bit = self.tx_buf[self.tx_index as usize] & (1 << self.tx_bit);
             self.tx_bit += 1;
-            self.write_tx(bit != 0);
-            if self.tx_bit >= 6 {
+            self.write_tx(bit != 0)?;
+            // The preamble is always made of 6-bit symbols
+            let symbol_bits = if self.tx_index < self.preamble_len {
+                6
+            } else {
+                LC::SYMBOL_BITS
+            };
+            if self.tx_bit >= symbol_bits {
                 self.tx_bit = 0;
                 self.tx_index += 1;
             }
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl<TX, RX, PTT, const TXQ: usize, const RXQ: usize, LC, CS, FF, RXEN, TXEN>
+    AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>
+where
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+{
+    /// Loads `packet` into the PLL buffer as if it had just been demodulated.
+    pub(crate) fn load_rx_packet(&mut self, packet: &AskPacket) {
+        let count = packet.payload.len() as u8 + 1 + FF::HEADER_LEN + CS::LEN;
+        let headers = [packet.to, packet.from, packet.id, packet.flags.bits()];
+        self.pll.buf.clear();
+        for b in [count]
+            .iter()
+            .chain(FF::encode_header(&headers))
+            .chain(packet.payload.iter())
+        {
+            #[cfg(feature = "std")]
+            self.pll.buf.push(*b);
+            #[cfg(not(feature = "std"))]
+            self.pll.buf.push(*b).unwrap();
+        }
+        let fcs = CS::fcs(&self.pll.buf).to_le_bytes();
+        for &b in &fcs[..CS::LEN as usize] {
+            #[cfg(feature = "std")]
+            self.pll.buf.push(b);
+            #[cfg(not(feature = "std"))]
+            self.pll.buf.push(b).unwrap();
+        }
+        self.pll.buf_len = self.pll.buf.len() as u8;
+        self.pll.full = true;
+    }
+
+    /// Returns the most recently queued frame as an [`AskPacket`]: the last one in the
+    /// transmit queue, or the one in the transmit buffer if the queue is empty.
+    pub(crate) fn sent_packet(&self) -> AskPacket {
+        let mut frame = self.tx_queue.back().cloned().unwrap_or_else(|| {
+            self.tx_buf[self.preamble_len as usize..]
+                .chunks(2)
+                .map(|symbols| LC::decode(symbols[0], symbols[1]))
+                .collect::<Option<_>>()
+                .expect("the transmit buffer holds valid symbols")
+        });
+        let payload_start = 1 + FF::HEADER_LEN as usize;
+        let [to, from, id, flags] = FF::decode_header(&frame[1..payload_start]);
+        if flags & ASK_FLAGS_FEC != 0 {
+            let count = frame[0] as usize;
+            frame = frame[..payload_start]
+                .iter()
+                .copied()
+                .chain(
+                    frame[payload_start..]
+                        .chunks(FEC_BLOCK_LEN)
+                        .filter_map(|block| crate::fec::decode_block(block.try_into().ok()?))
+                        .flat_map(|(data, _)| data),
+                )
+                .take(count)
+                .collect();
+        }
+        let mut packet = AskPacket::new(to, from);
+        packet.id = id;
+        packet.flags = HeaderFlags::from_bits_retain(flags);
+        #[cfg(feature = "std")]
+        packet
+            .payload
+            .extend_from_slice(&frame[payload_start..frame.len() - CS::LEN as usize]);
+        #[cfg(not(feature = "std"))]
+        packet
+            .payload
+            .extend_from_slice(&frame[payload_start..frame.len() - CS::LEN as usize])
+            .unwrap();
+        packet
+    }
+}
+
+#[cfg(test)]
+impl<const TXQ: usize, const RXQ: usize, LC, CS, FF>
+    AskDriver<
+        embedded_hal_mock::eh1::digital::Mock,
+        embedded_hal_mock::eh1::digital::Mock,
+        embedded_hal_mock::eh1::digital::Mock,
+        TXQ,
+        RXQ,
+        LC,
+        CS,
+        FF,
+    >
+where
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+{
+    /// Checks that every mock pin of the driver saw all of its expected transactions.
+    pub(crate) fn done(&mut self) {
+        self.tx.done();
+        self.rx.done();
+        for pin in [&mut self.ptt, &mut self.rx_enable, &mut self.tx_enable]
+            .into_iter()
+            .flatten()
+        {
+            pin.done();
+        }
+    }
+}
+
+/// A packet to `to` from `from` with header id `id` and `flags`, its payload made of
+/// `parts` one after the other.
+#[cfg(test)]
+pub(crate) fn test_packet(
+    to: u8,
+    from: u8,
+    id: u8,
+    flags: HeaderFlags,
+    parts: &[&[u8]],
+) -> AskPacket {
+    let mut packet = AskPacket::new(to, from);
+    packet.id = id;
+    packet.flags = flags;
+    packet
+        .payload
+        .extend(parts.iter().flat_map(|part| part.iter().copied()));
+    packet
+}
+
+/// An enable pin of its own type, whose error is not the PTT pin's.
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct EnablePin(pub(crate) bool);
+
+#[cfg(test)]
+impl embedded_hal::digital::ErrorType for EnablePin {
+    type Error = embedded_hal::digital::ErrorKind;
+}
+
+#[cfg(test)]
+impl OutputPin for EnablePin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0 = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0 = true;
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::crc::crc_ccitt_update;
+    use crate::encoding::{ManchesterIeee, encode_4b6b};
     use embedded_hal_mock::eh1::digital::{
         Mock as PinMock, State as PinState, Transaction as PinTransaction,
     };
 
+    fn config(ticks_per_bit: u8) -> AskConfig {
+        AskConfig::builder()
+            .ticks_per_bit(ticks_per_bit)
+            .build()
+            .unwrap()
+    }
+
     #[test]
     fn test_driver_initialization() {
         let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
         let rx = PinMock::new(&[]);
         let ptt = PinMock::new(&[]);
 
-        let mut driver = AskDriver::new(tx, rx, Some(ptt), 8, Some(false), Some(false));
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), AskConfig::default()).unwrap();
 
         assert_eq!(driver.mode, AskMode::Idle);
         driver.tx.done();
@@ -764,7 +1968,8 @@ mod tests {
         let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
         let rx = PinMock::new(&[]);
         let ptt = PinMock::new(&[]);
-        let mut driver = AskDriver::new(tx, rx, Some(ptt), 8, Some(false), Some(false));
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), AskConfig::default()).unwrap();
 
         driver.set_address(0x42);
         assert_eq!(driver.this_address, 0x42);
@@ -784,14 +1989,15 @@ mod tests {
         let rx = PinMock::new(&[]);
         let ptt = PinMock::new(&[PinTransaction::set(PinState::High)]);
 
-        let mut driver = AskDriver::new(tx, rx, Some(ptt), 8, Some(false), Some(false));
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), AskConfig::default()).unwrap();
         let mut message = Vec::new();
         #[cfg(feature = "std")]
         message.extend_from_slice(b"Hi");
         #[cfg(not(feature = "std"))]
         let _ = message.extend_from_slice(b"Hi");
 
-        assert!(driver.send(message));
+        driver.send(message).unwrap();
         assert_eq!(driver.mode, AskMode::Tx);
         assert_eq!(driver.tx_buf.len(), 26); // (2 bytes + 4 headers + 1 count + 2 CRC bytes) * 2 (4b6b encoding) + 8 (preamble)
         driver.tx.done();
@@ -828,10 +2034,11 @@ mod tests {
             PinTransaction::set(PinState::Low),
         ]);
 
-        let mut driver = AskDriver::new(tx, rx, Some(ptt), 2, Some(false), Some(false));
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), config(4)).unwrap();
         let mut message: Vec<u8> = Vec::new();
         message.extend_from_slice(b"AB");
-        assert!(driver.send(message));
+        driver.send(message).unwrap();
 
         assert_eq!(
             driver.tx_buf,
@@ -843,13 +2050,16 @@ mod tests {
 
         assert!(driver.mode == AskMode::Tx);
 
-        for _ in 0..(tx_states.len() * 3) {
-            // 2 ticks per bit
-            driver.tick();
+        for _ in 0..(tx_states.len() * 5) {
+            // 4 ticks per bit
+            driver.tick().unwrap();
         }
 
         assert_eq!(driver.tx_buf_len, 26);
-        assert_eq!(driver.tx_good, 1);
+        assert_eq!(driver.stats().tx_good, 1);
+        assert!(!driver.is_tx_busy());
+        assert!(driver.take_tx_complete());
+        assert!(!driver.take_tx_complete());
         assert_eq!(driver.tx_index, 26);
         driver.tx.done();
         driver.rx.done();
@@ -865,11 +2075,996 @@ mod tests {
         let rx = PinMock::new(&[]);
         let ptt = PinMock::new(&[PinTransaction::set(PinState::Low)]);
 
-        let mut driver = AskDriver::new(tx, rx, Some(ptt), 8, Some(false), Some(false));
-        assert!(!driver.availabile());
-        assert!(driver.receive().is_none());
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), AskConfig::default()).unwrap();
+        assert!(!driver.availabile().unwrap());
+        assert!(driver.receive().unwrap().is_none());
+        driver.tx.done();
+        driver.rx.done();
+        let _ = driver.ptt.as_mut().map(|ptt| ptt.done());
+    }
+
+    #[test]
+    fn test_receive_queue_keeps_frames_in_order_and_counts_overflow() {
+        let tx = PinMock::new(&[
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::Low),
+        ]);
+        let rx = PinMock::new(&[]);
+        let ptt = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock, 1, 2> =
+            AskDriver::new(tx, rx, Some(ptt), AskConfig::default()).unwrap();
+        driver.set_address(0x42);
+
+        driver.load_rx_packet(&test_packet(0x42, 0x01, 1, HeaderFlags::NONE, &[b"one"]));
+        driver.validate_rx_buf();
+        // Not for us: silently dropped
+        driver.load_rx_packet(&test_packet(0x17, 0x01, 2, HeaderFlags::NONE, &[b"other"]));
+        driver.validate_rx_buf();
+        driver.load_rx_packet(&test_packet(
+            BROADCAST_ADDRESS,
+            0x01,
+            3,
+            HeaderFlags::NONE,
+            &[b"two"],
+        ));
+        driver.validate_rx_buf();
+        // Queue is full, so this one is dropped
+        driver.load_rx_packet(&test_packet(0x42, 0x01, 4, HeaderFlags::NONE, &[b"three"]));
+        driver.validate_rx_buf();
+        assert!(driver.pll.buf.is_empty());
+        assert_eq!(driver.rx_queue_len(), 2);
+        assert_eq!(driver.stats().rx_good, 2);
+        assert_eq!(driver.stats().rx_filtered, 1);
+        assert_eq!(driver.stats().rx_queue_overflows, 1);
+
+        let one = driver.receive().unwrap().unwrap();
+        assert_eq!(one.payload.as_slice(), b"one");
+        assert_eq!((one.to, one.from, one.id), (0x42, 0x01, 1));
+        assert_eq!(one.flags, HeaderFlags::NONE);
+        assert_eq!(driver.rx_header_id, 1);
+        let two = driver.receive().unwrap().unwrap();
+        assert_eq!(two.payload.as_slice(), b"two");
+        assert_eq!((two.to, two.id), (BROADCAST_ADDRESS, 3));
+        assert_eq!(driver.rx_header_to, BROADCAST_ADDRESS);
+        assert!(driver.receive().unwrap().is_none());
+
+        driver.tx.done();
+        driver.rx.done();
+        let _ = driver.ptt.as_mut().map(|ptt| ptt.done());
+    }
+
+    #[test]
+    fn test_subscribed_addresses_are_accepted() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let config = AskConfig::builder().address(0x21).build().unwrap();
+        let mut driver: AskDriver<PinMock, PinMock, PinMock, 4, 8> =
+            AskDriver::new(tx, rx, None, config).unwrap();
+        driver.subscribe(0x11, AddressKind::Unicast).unwrap();
+        driver.subscribe(0xe2, AddressKind::Group).unwrap();
+        assert!(driver.is_group_address(0xe2));
+        assert!(!driver.is_group_address(0x11));
+
+        for (to, id) in [(0x21, 1), (0x11, 2), (0xe2, 3), (0xe3, 4)] {
+            driver.load_rx_packet(&test_packet(to, 0x01, id, HeaderFlags::NONE, &[b""]));
+            driver.validate_rx_buf();
+        }
+        assert_eq!(driver.stats().rx_good, 3);
+        assert_eq!(driver.stats().rx_filtered, 1);
+
+        assert!(driver.unsubscribe(0xe2));
+        assert!(!driver.unsubscribe(0xe2));
+        driver.load_rx_packet(&test_packet(0xe2, 0x01, 5, HeaderFlags::NONE, &[b""]));
+        driver.validate_rx_buf();
+        assert_eq!(driver.stats().rx_filtered, 2);
+
+        // Re-subscribing only changes the kind; the table holds ASK_ADDRESS_FILTER_LEN entries
+        driver.subscribe(0x11, AddressKind::Group).unwrap();
+        assert_eq!(driver.subscriptions().count(), 1);
+        for address in 0..ASK_ADDRESS_FILTER_LEN as u8 - 1 {
+            driver.subscribe(address, AddressKind::Group).unwrap();
+        }
+        assert_eq!(
+            driver.subscribe(0x80, AddressKind::Unicast),
+            Err(AskError::BufferOverflow)
+        );
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_duplicate_filter_drops_repeated_ids() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let config = AskConfig::builder()
+            .duplicate_filter(true)
+            .duplicate_expiry_ticks(100)
+            .build()
+            .unwrap();
+        let mut driver: AskDriver<PinMock, PinMock, PinMock, 4, 8> =
+            AskDriver::new(tx, rx, None, config).unwrap();
+        let receive = |driver: &mut AskDriver<PinMock, PinMock, PinMock, 4, 8>,
+                       from: u8,
+                       id: u8,
+                       flags: HeaderFlags| {
+            let mut packet = AskPacket::new(BROADCAST_ADDRESS, from);
+            packet.id = id;
+            packet.flags = flags;
+            driver.load_rx_packet(&packet);
+            driver.validate_rx_buf();
+        };
+
+        receive(&mut driver, 0x01, 1, HeaderFlags::NONE);
+        receive(&mut driver, 0x01, 1, HeaderFlags::NONE);
+        // Another sender may use the same id, and ACKs always repeat one
+        receive(&mut driver, 0x02, 1, HeaderFlags::NONE);
+        receive(&mut driver, 0x02, 1, HeaderFlags::ACK);
+        receive(&mut driver, 0x01, 2, HeaderFlags::NONE);
+        assert_eq!(driver.stats().rx_duplicates, 1);
+        assert_eq!(driver.stats().rx_good, 4);
+
+        // Once expired, the same id is accepted again
+        driver.ticks += 100;
+        receive(&mut driver, 0x01, 2, HeaderFlags::NONE);
+        assert_eq!(driver.stats().rx_good, 5);
+
+        driver.set_duplicate_filter(false);
+        receive(&mut driver, 0x01, 2, HeaderFlags::NONE);
+        assert_eq!(driver.stats().rx_duplicates, 1);
+        assert_eq!(driver.stats().rx_good, 6);
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_crc32_frames_round_trip() {
+        use crate::crc::Crc32;
+        type Crc32Driver = AskDriver<PinMock, PinMock, PinMock, 2, 2, FourBSixB, Crc32>;
+
+        let tx = PinMock::new(&[
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::Low),
+        ]);
+        let rx = PinMock::new(&[]);
+        let mut driver: Crc32Driver = AskDriver::new(tx, rx, None, AskConfig::default()).unwrap();
+        assert_eq!(Crc32Driver::MAX_MESSAGE_LEN, ASK_MAX_MESSAGE_LEN - 2);
+
+        let mut packet = AskPacket::new(BROADCAST_ADDRESS, 0x02);
+        #[cfg(feature = "std")]
+        packet.payload.extend_from_slice(b"long frame");
+        #[cfg(not(feature = "std"))]
+        packet.payload.extend_from_slice(b"long frame").unwrap();
+        driver.load_rx_packet(&packet);
+        assert_eq!(driver.pll.buf.len(), 1 + 4 + 10 + 4);
+        driver.validate_rx_buf();
+        assert_eq!(driver.receive().unwrap().unwrap().payload, packet.payload);
+
+        driver.load_rx_packet(&packet);
+        driver.pll.buf[7] ^= 0x80;
+        driver.validate_rx_buf();
+        assert_eq!(driver.stats().rx_crc_errors, 1);
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_virtualwire_frames_have_no_header() {
+        use crate::format::VirtualWire;
+        type VwDriver =
+            AskDriver<PinMock, PinMock, PinMock, 2, 2, FourBSixB, Crc16Ccitt, VirtualWire>;
+
+        let tx = PinMock::new(&[
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::Low),
+        ]);
+        let rx = PinMock::new(&[]);
+        let config = AskConfig::builder()
+            .address(0x01)
+            .duplicate_filter(true)
+            .build()
+            .unwrap();
+        let mut driver: VwDriver = AskDriver::new(tx, rx, None, config).unwrap();
+        assert_eq!(VwDriver::MAX_MESSAGE_LEN, ASK_MAX_MESSAGE_LEN);
+        // Without flags to signal it, FEC is never used
+        assert_eq!(VwDriver::MAX_FEC_MESSAGE_LEN, ASK_MAX_MESSAGE_LEN);
+
+        // The same headerless frame twice: count, payload and FCS only
+        let mut packet = AskPacket::new(0x01, 0x02);
+        packet.id = 7;
+        #[cfg(feature = "std")]
+        packet.payload.extend_from_slice(b"Hi");
+        #[cfg(not(feature = "std"))]
+        packet.payload.extend_from_slice(b"Hi").unwrap();
+        for _ in 0..2 {
+            driver.load_rx_packet(&packet);
+            assert_eq!(driver.pll.buf[..3], [5, b'H', b'i']);
+            driver.validate_rx_buf();
+        }
+        let received = driver.receive().unwrap().unwrap();
+        assert_eq!(
+            (received.to, received.from, received.id),
+            (BROADCAST_ADDRESS, BROADCAST_ADDRESS, 0)
+        );
+        assert_eq!(received.payload.as_slice(), b"Hi");
+        assert_eq!(driver.stats().rx_good, 2);
+
+        driver.send_bytes(b"Hi").unwrap();
+        assert_eq!(driver.tx_buf[8..10], encode_4b6b(5));
+        assert_eq!(driver.tx_buf[10..12], encode_4b6b(b'H'));
+        assert_eq!(driver.sent_packet().payload.as_slice(), b"Hi");
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_stats_break_down_receive_errors() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, None, AskConfig::default()).unwrap();
+
+        driver.load_rx_packet(&test_packet(
+            BROADCAST_ADDRESS,
+            0x01,
+            1,
+            HeaderFlags::NONE,
+            &[b"ok"],
+        ));
+        driver.validate_rx_buf();
+        driver.load_rx_packet(&test_packet(
+            BROADCAST_ADDRESS,
+            0x01,
+            2,
+            HeaderFlags::NONE,
+            &[b"corrupt"],
+        ));
+        driver.pll.buf[6] ^= 0x01;
+        driver.validate_rx_buf();
+
+        // A CRC-valid frame too short to hold a header
+        let crc = !crc_ccitt_update(0xffff, &0x03);
+        #[cfg(feature = "std")]
+        driver
+            .pll
+            .buf
+            .extend_from_slice(&[0x03, crc as u8, (crc >> 8) as u8]);
+        #[cfg(not(feature = "std"))]
+        driver
+            .pll
+            .buf
+            .extend_from_slice(&[0x03, crc as u8, (crc >> 8) as u8])
+            .unwrap();
+        driver.validate_rx_buf();
+
+        let stats = driver.stats();
+        assert_eq!(stats.rx_good, 1);
+        assert_eq!(stats.rx_crc_errors, 1);
+        assert_eq!(stats.rx_bad_length, 1);
+        assert_eq!(stats.rx_errors(), 2);
+
+        driver.reset_stats();
+        assert_eq!(driver.stats(), AskStats::default());
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_send_packet_uses_packet_headers() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let ptt = PinMock::new(&[PinTransaction::set(PinState::High)]);
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), AskConfig::default()).unwrap();
+        let mut packet = AskPacket::new(0x10, 0x01);
+        packet.id = 0x22;
+        packet.flags = HeaderFlags::from_application(0x03).unwrap();
+        packet.payload.extend(b"Hi".iter().copied());
+        driver.send_packet(&packet).unwrap();
+
+        // Symbols after the preamble: count, to, from, id, flags
+        let mut expected = [0u8; 10];
+        for (i, b) in [9, 0x10, 0x01, 0x22, 0x03].iter().enumerate() {
+            expected[i * 2..i * 2 + 2].copy_from_slice(&encode_4b6b(*b));
+        }
+        assert_eq!(&driver.tx_buf[8..18], &expected);
+        assert_eq!(driver.tx_header_to, BROADCAST_ADDRESS);
+        driver.tx.done();
+        driver.rx.done();
+        let _ = driver.ptt.as_mut().map(|ptt| ptt.done());
+    }
+
+    #[test]
+    fn test_config_sets_address_and_preamble() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let ptt = PinMock::new(&[PinTransaction::set(PinState::High)]);
+
+        let config = AskConfig::builder()
+            .address(0x42)
+            .promiscuous(true)
+            .preamble_len(12)
+            .build()
+            .unwrap();
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), config).unwrap();
+        assert_eq!(driver.this_address, 0x42);
+        assert_eq!(driver.tx_header_from, 0x42);
+        assert!(driver.promiscuous);
+
+        driver.send_bytes(b"Hi").unwrap();
+        assert_eq!(&driver.tx_buf[..10], &[0x2a; 10]);
+        assert_eq!(&driver.tx_buf[10..12], &[0x38, 0x2c]);
+        assert_eq!(driver.tx_buf.len(), 12 + 18);
+        driver.tx.done();
+        driver.rx.done();
+        let _ = driver.ptt.as_mut().map(|ptt| ptt.done());
+    }
+
+    #[test]
+    fn test_set_bitrate_keeps_tick_rate() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let ptt = PinMock::new(&[PinTransaction::set(PinState::High)]);
+
+        let config = AskConfig::builder()
+            .bit_rate(BitRate::Bps2000)
+            .tick_hz(16_000)
+            .build()
+            .unwrap();
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), config).unwrap();
+        assert_eq!(driver.ticks_per_bit(), 8);
+
+        driver.set_bitrate(BitRate::Bps500).unwrap();
+        assert_eq!(driver.bit_rate(), BitRate::Bps500);
+        assert_eq!(driver.ticks_per_bit(), 32);
+        assert_eq!(
+            driver.set_bitrate(BitRate::Bps9600),
+            Err(AskError::Config(ConfigError::InvalidTickRate(16_000)))
+        );
+        assert_eq!(driver.bit_rate(), BitRate::Bps500);
+
+        driver.send_bytes(b"Hi").unwrap();
+        assert_eq!(driver.set_bitrate(BitRate::Bps1000), Err(AskError::Busy));
         driver.tx.done();
         driver.rx.done();
         let _ = driver.ptt.as_mut().map(|ptt| ptt.done());
     }
+
+    #[test]
+    fn test_send_rejects_long_message_and_busy_transmitter() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let ptt = PinMock::new(&[PinTransaction::set(PinState::High)]);
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), AskConfig::default()).unwrap();
+        let too_long = [0u8; ASK_MAX_MESSAGE_LEN as usize + 1];
+        assert_eq!(driver.send_bytes(&too_long), Err(AskError::MessageTooLong));
+
+        // One frame goes on air straight away, the rest wait in the queue
+        for _ in 0..=ASK_TX_QUEUE_LEN {
+            driver.send_bytes(b"Hi").unwrap();
+        }
+        assert_eq!(driver.tx_queue_len(), ASK_TX_QUEUE_LEN);
+        assert_eq!(driver.send_bytes(b"Hi"), Err(AskError::Busy));
+        driver.tx.done();
+        driver.rx.done();
+        let _ = driver.ptt.as_mut().map(|ptt| ptt.done());
+    }
+
+    #[test]
+    fn test_try_send_would_block_while_queue_full() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let ptt = PinMock::new(&[PinTransaction::set(PinState::High)]);
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock, 1> =
+            AskDriver::new(tx, rx, Some(ptt), AskConfig::default()).unwrap();
+        assert!(!driver.is_tx_busy());
+        driver.try_send(b"Hi").unwrap();
+        assert!(driver.is_tx_busy());
+        driver.try_send(b"Hi").unwrap();
+        assert_eq!(driver.try_send(b"Hi"), Err(nb::Error::WouldBlock));
+        assert!(!driver.take_tx_complete());
+        driver.tx.done();
+        driver.rx.done();
+        let _ = driver.ptt.as_mut().map(|ptt| ptt.done());
+    }
+
+    #[test]
+    fn test_back_to_back_frames_each_get_a_preamble() {
+        use heapless::Vec as FixedVec;
+
+        // Each "Hi" frame is 8 preamble symbols + 2 * 9 frame symbols = 26 symbols
+        const FRAME_BITS: usize = 26 * 6;
+        let mut tx_transactions: FixedVec<PinTransaction, { 2 * FRAME_BITS + 2 }> = FixedVec::new();
+        let mut bits: FixedVec<bool, FRAME_BITS> = FixedVec::new();
+        tx_transactions
+            .push(PinTransaction::set(PinState::Low))
+            .unwrap();
+
+        let ptt = PinMock::new(&[
+            PinTransaction::set(PinState::High),
+            PinTransaction::set(PinState::Low),
+        ]);
+        let rx = PinMock::new(&[]);
+
+        // Work out the expected bit stream from a driver that only sends one frame
+        let mut reference: AskDriver<PinMock, PinMock, PinMock> = AskDriver::new(
+            PinMock::new(&[PinTransaction::set(PinState::Low)]),
+            PinMock::new(&[]),
+            None,
+            AskConfig::default(),
+        )
+        .unwrap();
+        reference.send_bytes(b"Hi").unwrap();
+        for symbol in reference.tx_buf.iter() {
+            for bit in 0..6 {
+                bits.push(symbol & (1 << bit) != 0).unwrap();
+            }
+        }
+        assert_eq!(bits.len(), FRAME_BITS);
+        reference.tx.done();
+        reference.rx.done();
+
+        for _ in 0..2 {
+            for bit in bits.iter() {
+                let state = if *bit { PinState::High } else { PinState::Low };
+                tx_transactions.push(PinTransaction::set(state)).unwrap();
+            }
+        }
+        // Carrier off when returning to idle
+        tx_transactions
+            .push(PinTransaction::set(PinState::Low))
+            .unwrap();
+        let tx = PinMock::new(&tx_transactions);
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), config(4)).unwrap();
+        driver.send_bytes(b"Hi").unwrap();
+        driver.send_bytes(b"Hi").unwrap();
+        assert_eq!(driver.tx_queue_len(), 1);
+
+        for _ in 0..(FRAME_BITS + 1) * 4 {
+            driver.tick().unwrap();
+        }
+        // First frame done, second one started without dropping out of TX
+        assert_eq!(driver.stats().tx_good, 1);
+        assert_eq!(driver.mode, AskMode::Tx);
+        assert_eq!(driver.tx_queue_len(), 0);
+        assert_eq!(
+            &driver.tx_buf[..8],
+            &[0x2a, 0x2a, 0x2a, 0x2a, 0x2a, 0x2a, 0x38, 0x2c]
+        );
+        assert!(!driver.take_tx_complete());
+
+        for _ in 0..(FRAME_BITS + 1) * 4 {
+            driver.tick().unwrap();
+        }
+        assert_eq!(driver.stats().tx_good, 2);
+        assert_eq!(driver.mode, AskMode::Idle);
+        assert!(driver.take_tx_complete());
+
+        driver.tx.done();
+        driver.rx.done();
+        let _ = driver.ptt.as_mut().map(|ptt| ptt.done());
+    }
+
+    #[test]
+    fn test_manchester_frame_round_trip() {
+        use heapless::Vec as FixedVec;
+        type Manchester = AskDriver<PinMock, PinMock, PinMock, 2, 2, ManchesterIeee>;
+
+        // 8 preamble symbols of 6 bits + 9 frame bytes of 16 bits
+        const FRAME_BITS: usize = 8 * 6 + 9 * 16;
+        const SAMPLES: usize = 32 + FRAME_BITS * 4 + 16;
+        let mut bits: FixedVec<bool, FRAME_BITS> = FixedVec::new();
+        let mut reference: Manchester = AskDriver::new(
+            PinMock::new(&[PinTransaction::set(PinState::Low)]),
+            PinMock::new(&[]),
+            None,
+            AskConfig::default(),
+        )
+        .unwrap();
+        reference.send_bytes(b"Hi").unwrap();
+        for (i, symbol) in reference.tx_buf.iter().enumerate() {
+            let width = if i < 8 { 6 } else { 8 };
+            for bit in 0..width {
+                bits.push(symbol & (1 << bit) != 0).unwrap();
+            }
+        }
+        assert_eq!(bits.len(), FRAME_BITS);
+        reference.tx.done();
+        reference.rx.done();
+
+        // The transmitter sends the preamble in 6-bit and the frame in 8-bit symbols
+        let mut tx_transactions: FixedVec<PinTransaction, { FRAME_BITS + 2 }> = FixedVec::new();
+        tx_transactions
+            .push(PinTransaction::set(PinState::Low))
+            .unwrap();
+        for bit in bits.iter() {
+            let state = if *bit { PinState::High } else { PinState::Low };
+            tx_transactions.push(PinTransaction::set(state)).unwrap();
+        }
+        tx_transactions
+            .push(PinTransaction::set(PinState::Low))
+            .unwrap();
+        let mut sender: Manchester = AskDriver::new(
+            PinMock::new(&tx_transactions),
+            PinMock::new(&[]),
+            None,
+            config(4),
+        )
+        .unwrap();
+        sender.send_bytes(b"Hi").unwrap();
+        for _ in 0..(FRAME_BITS + 1) * 4 {
+            sender.tick().unwrap();
+        }
+        assert_eq!(sender.stats().tx_good, 1);
+        sender.tx.done();
+        sender.rx.done();
+
+        // Play the same bits back into a receiver, 4 samples per bit
+        let mut samples: FixedVec<PinTransaction, SAMPLES> = FixedVec::new();
+        for i in 0..SAMPLES {
+            let high = (32..32 + FRAME_BITS * 4).contains(&i) && bits[(i - 32) / 4];
+            let state = if high { PinState::High } else { PinState::Low };
+            samples.push(PinTransaction::get(state)).unwrap();
+        }
+        let mut receiver: Manchester = AskDriver::new(
+            PinMock::new(&[
+                PinTransaction::set(PinState::Low),
+                PinTransaction::set(PinState::Low),
+            ]),
+            PinMock::new(&samples),
+            None,
+            config(4),
+        )
+        .unwrap();
+        receiver.set_mode_rx().unwrap();
+        for _ in 0..SAMPLES {
+            receiver.tick().unwrap();
+        }
+        let packet = receiver.receive().unwrap().unwrap();
+        assert_eq!(packet.payload.as_slice(), b"Hi");
+        assert_eq!(receiver.stats().rx_errors(), 0);
+        receiver.tx.done();
+        receiver.rx.done();
+    }
+
+    #[test]
+    fn test_fec_frame_survives_a_bad_symbol() {
+        use heapless::Vec as FixedVec;
+
+        // 8 preamble symbols + 2 symbols for each of the 13 bytes on air
+        const FRAME_BITS: usize = (8 + 2 * 13) * 6;
+        const SAMPLES: usize = 32 + FRAME_BITS * 4 + 16;
+        let mut sender: AskDriver<PinMock, PinMock, PinMock> = AskDriver::new(
+            PinMock::new(&[PinTransaction::set(PinState::Low)]),
+            PinMock::new(&[]),
+            None,
+            AskConfig::builder().fec(true).build().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(sender.max_message_len(), 28);
+        assert_eq!(sender.send_bytes(&[0; 29]), Err(AskError::MessageTooLong));
+        sender.send_bytes(b"Hi").unwrap();
+        assert_eq!(sender.sent_packet().payload.as_slice(), b"Hi");
+        // The count and headers are sent uncoded, FEC signalled in the flags
+        assert_eq!(sender.tx_buf[8..10], encode_4b6b(9));
+        assert_eq!(sender.tx_buf[16..18], encode_4b6b(ASK_FLAGS_FEC));
+        let mut bits: FixedVec<bool, FRAME_BITS> = FixedVec::new();
+        for (i, symbol) in sender.tx_buf.iter().enumerate() {
+            // Wipe out one symbol of the first block
+            let symbol = if i == 19 { 0 } else { *symbol };
+            for bit in 0..6 {
+                bits.push(symbol & (1 << bit) != 0).unwrap();
+            }
+        }
+        sender.tx.done();
+        sender.rx.done();
+
+        let mut samples: FixedVec<PinTransaction, SAMPLES> = FixedVec::new();
+        for i in 0..SAMPLES {
+            let high = (32..32 + FRAME_BITS * 4).contains(&i) && bits[(i - 32) / 4];
+            let state = if high { PinState::High } else { PinState::Low };
+            samples.push(PinTransaction::get(state)).unwrap();
+        }
+        let mut receiver: AskDriver<PinMock, PinMock, PinMock> = AskDriver::new(
+            PinMock::new(&[
+                PinTransaction::set(PinState::Low),
+                PinTransaction::set(PinState::Low),
+            ]),
+            PinMock::new(&samples),
+            None,
+            config(4),
+        )
+        .unwrap();
+        receiver.set_mode_rx().unwrap();
+        for _ in 0..SAMPLES {
+            receiver.tick().unwrap();
+        }
+        let packet = receiver.receive().unwrap().unwrap();
+        assert_eq!(packet.payload.as_slice(), b"Hi");
+        assert_eq!(receiver.stats().rx_errors(), 0);
+        assert!(receiver.stats().rx_corrected_bits > 0);
+        receiver.tx.done();
+        receiver.rx.done();
+    }
+
+    #[test]
+    fn test_rc_switch_codes_decoded_while_receiving() {
+        use crate::rcswitch::RC_PROTOCOLS;
+        use heapless::Vec as FixedVec;
+
+        // Two repeats of a 24-bit EV1527 code with protocol 1 timing, at 16 kHz
+        let protocol = &RC_PROTOCOLS[0];
+        let ticks = |len: u8| usize::from(len) * usize::from(protocol.pulse_len_us) * 16 / 1000;
+        let mut samples: FixedVec<PinTransaction, 1400> = FixedVec::new();
+        for _ in 0..2 {
+            for bit in (0..24).rev() {
+                let pulse = if (0x0005_a5c3 >> bit) & 1 == 1 {
+                    protocol.one
+                } else {
+                    protocol.zero
+                };
+                for (state, len) in [(PinState::High, pulse.high), (PinState::Low, pulse.low)] {
+                    for _ in 0..ticks(len) {
+                        samples.push(PinTransaction::get(state)).unwrap();
+                    }
+                }
+            }
+            for (state, len) in [
+                (PinState::High, protocol.sync.high),
+                (PinState::Low, protocol.sync.low),
+            ] {
+                for _ in 0..ticks(len) {
+                    samples.push(PinTransaction::get(state)).unwrap();
+                }
+            }
+        }
+        samples.push(PinTransaction::get(PinState::High)).unwrap();
+
+        let tx = PinMock::new(&[
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::Low),
+        ]);
+        let config = AskConfig::builder().rc_switch_rx(true).build().unwrap();
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, PinMock::new(&samples), None, config).unwrap();
+        driver.set_mode_rx().unwrap();
+        for _ in 0..samples.len() {
+            driver.tick().unwrap();
+        }
+        let code = driver.receive_rc_code().unwrap();
+        assert_eq!(
+            (code.code, code.bit_len, code.protocol),
+            (0x0005_a5c3, 24, 1)
+        );
+        assert!(code.pulse_len_us.abs_diff(350) < 10);
+        assert_eq!(driver.receive_rc_code(), None);
+        assert_eq!(driver.receive().unwrap(), None);
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_rc_code_shares_the_transmitter_with_frames() {
+        use crate::rcswitch::RcCode;
+
+        // Protocol 1 "10": 3+1, 1+3 and a 1+31 sync, 6 ticks per pulse at 16 kHz
+        let tx = PinMock::new(&[
+            PinTransaction::set(PinState::Low), // new()
+            PinTransaction::set(PinState::High),
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::High),
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::High),
+            PinTransaction::set(PinState::Low),
+        ]);
+        let ptt = PinMock::new(&[PinTransaction::set(PinState::High)]);
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, PinMock::new(&[]), Some(ptt), AskConfig::default()).unwrap();
+
+        let code = RcCode::new(1, 0b10, 2).unwrap();
+        assert_eq!(
+            driver.send_rc_code(
+                &RcCode {
+                    protocol: 7,
+                    ..code
+                },
+                1
+            ),
+            Err(AskError::InvalidRcCode)
+        );
+        driver.send_rc_code(&code, 1).unwrap();
+        assert_eq!(driver.send_rc_code(&code, 1), Err(AskError::Busy));
+
+        // A frame queued meanwhile waits for the code
+        driver.send_bytes(b"Hi").unwrap();
+        for _ in 0..240 {
+            driver.tick().unwrap();
+        }
+        assert_eq!(driver.tx_queue_len(), 1);
+        driver.tick().unwrap();
+        assert_eq!(driver.tx_queue_len(), 0);
+        assert_eq!(driver.mode, AskMode::Tx);
+        assert!(!driver.take_tx_complete());
+        driver.tx.done();
+        driver.rx.done();
+        driver.ptt.as_mut().unwrap().done();
+    }
+
+    #[test]
+    fn test_sleep_powers_down_and_stops_sampling() {
+        let tx = PinMock::new(&[
+            PinTransaction::set(PinState::Low), // new()
+            PinTransaction::set(PinState::Low), // set_mode_sleep()
+            PinTransaction::set(PinState::Low), // set_mode_rx()
+        ]);
+        // Only the sample taken once the warm-up is over reaches the pin
+        let rx = PinMock::new(&[PinTransaction::get(PinState::Low)]);
+        let rx_en = PinMock::new(&[
+            PinTransaction::set(PinState::High),
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::High),
+        ]);
+        let tx_en = PinMock::new(&[
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::Low),
+        ]);
+        let config = AskConfig::builder().rx_warmup_ticks(3).build().unwrap();
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, None, config).unwrap();
+        driver.set_power_pins(Some(rx_en), Some(tx_en)).unwrap();
+
+        driver.set_mode_sleep().unwrap();
+        assert_eq!(driver.mode, AskMode::Sleep);
+        for _ in 0..10 {
+            driver.tick().unwrap();
+        }
+        assert_eq!(driver.ticks(), 10);
+        // Polling for messages must not wake the node
+        assert!(!driver.availabile().unwrap());
+        assert_eq!(driver.mode, AskMode::Sleep);
+        assert_eq!(driver.send_bytes(b"Hi"), Err(AskError::InvalidMode));
+
+        driver.set_mode_rx().unwrap();
+        for _ in 0..4 {
+            driver.tick().unwrap();
+        }
+
+        driver.tx.done();
+        driver.rx.done();
+        let _ = driver.rx_enable.as_mut().map(|pin| pin.done());
+        let _ = driver.tx_enable.as_mut().map(|pin| pin.done());
+    }
+
+    #[test]
+    fn test_sleep_rejected_while_transmitting() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let tx_en = PinMock::new(&[
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::High),
+        ]);
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, None, AskConfig::default()).unwrap();
+        driver.set_power_pins(None, Some(tx_en)).unwrap();
+        driver.send_bytes(b"Hi").unwrap();
+        assert_eq!(driver.set_mode_sleep(), Err(AskError::Busy));
+        assert_eq!(driver.mode, AskMode::Tx);
+
+        driver.tx.done();
+        driver.rx.done();
+        let _ = driver.tx_enable.as_mut().map(|pin| pin.done());
+    }
+
+    #[test]
+    fn test_power_pins_of_their_own_type() {
+        let tx = PinMock::new(&[
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::Low), // set_mode_sleep()
+        ]);
+        let rx = PinMock::new(&[]);
+        let mut driver: AskDriver<
+            PinMock,
+            PinMock,
+            PinMock,
+            2,
+            2,
+            FourBSixB,
+            Crc16Ccitt,
+            RadioHead,
+            EnablePin,
+            EnablePin,
+        > = AskDriver::new(tx, rx, None, AskConfig::default()).unwrap();
+        driver
+            .set_power_pins(Some(EnablePin(false)), Some(EnablePin(true)))
+            .unwrap();
+        assert!(driver.rx_enable.as_ref().is_some_and(|pin| pin.0));
+        assert!(driver.tx_enable.as_ref().is_some_and(|pin| !pin.0));
+
+        driver.set_mode_sleep().unwrap();
+        assert!(driver.rx_enable.as_ref().is_some_and(|pin| !pin.0));
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    /// Runs a CAD window of 8 bits at 8 ticks per bit over `samples` and returns the outcome.
+    fn run_cad(samples: &[bool; 64]) -> (bool, u32, u32) {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx_transactions: [PinTransaction; 64] = core::array::from_fn(|i| {
+            PinTransaction::get(if samples[i] {
+                PinState::High
+            } else {
+                PinState::Low
+            })
+        });
+        let rx = PinMock::new(&rx_transactions);
+        let config = AskConfig::builder().cad_window_bits(8).build().unwrap();
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, None, config).unwrap();
+        driver.start_cad().unwrap();
+        assert_eq!(driver.mode, AskMode::Cad);
+        for _ in 0..63 {
+            driver.tick().unwrap();
+        }
+        assert!(matches!(driver.cad_result(), Err(nb::Error::WouldBlock)));
+        // Polling for messages must not cut the window short
+        assert!(!driver.availabile().unwrap());
+        driver.tick().unwrap();
+        assert_eq!(driver.mode, AskMode::Idle);
+        let busy = driver.cad_result().unwrap();
+
+        driver.tx.done();
+        driver.rx.done();
+        (busy, driver.stats().cad_busy, driver.stats().cad_clear)
+    }
+
+    #[test]
+    fn test_cad_detects_regular_transmission() {
+        // One, two and three bit wide pulses, as in a 4b6b coded frame
+        let widths = [8, 16, 8, 24, 8, 8];
+        let mut samples = [false; 64];
+        let mut level = true;
+        let mut i = 0;
+        for width in widths.iter().cycle() {
+            for _ in 0..*width {
+                if i == samples.len() {
+                    break;
+                }
+                samples[i] = level;
+                i += 1;
+            }
+            if i == samples.len() {
+                break;
+            }
+            level = !level;
+        }
+        assert_eq!(run_cad(&samples), (true, 1, 0));
+    }
+
+    #[test]
+    fn test_cad_ignores_silence_and_noise() {
+        assert_eq!(run_cad(&[false; 64]), (false, 0, 1));
+
+        // Glitches at random, mostly sub-bit intervals
+        let mut samples = [false; 64];
+        for i in [3, 4, 9, 13, 14, 15, 22, 27, 33, 34, 41, 46, 47, 52, 58, 61] {
+            samples[i] = true;
+        }
+        assert_eq!(run_cad(&samples), (false, 0, 1));
+    }
+
+    #[test]
+    fn test_cad_rejected_while_transmitting_or_asleep() {
+        let tx = PinMock::new(&[
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::Low),
+            PinTransaction::set(PinState::Low),
+        ]);
+        let rx = PinMock::new(&[]);
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, None, AskConfig::default()).unwrap();
+        assert!(matches!(
+            driver.cad_result(),
+            Err(nb::Error::Other(AskError::InvalidMode))
+        ));
+        driver.send_bytes(b"Hi").unwrap();
+        assert_eq!(driver.start_cad(), Err(AskError::Busy));
+
+        driver.tx_queue.clear();
+        driver.set_mode_idle().unwrap();
+        driver.set_mode_sleep().unwrap();
+        assert_eq!(driver.start_cad(), Err(AskError::InvalidMode));
+
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_csma_transmits_on_clear_channel() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx_transactions: [PinTransaction; 64] =
+            core::array::from_fn(|_| PinTransaction::get(PinState::Low));
+        let rx = PinMock::new(&rx_transactions);
+        let config = AskConfig::builder()
+            .cad_window_bits(8)
+            .csma(true)
+            .build()
+            .unwrap();
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, None, config).unwrap();
+        driver.send_bytes(b"Hi").unwrap();
+        assert_eq!(driver.mode, AskMode::Cad);
+        assert!(driver.is_tx_busy());
+
+        for _ in 0..64 {
+            driver.tick().unwrap();
+        }
+        assert_eq!(driver.mode, AskMode::Tx);
+        assert_eq!(driver.stats().cad_clear, 1);
+        assert_eq!(driver.stats().csma_backoffs, 0);
+
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_csma_backs_off_and_gives_up_on_busy_channel() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        // A carrier switching every two bits for both channel checks
+        let rx_transactions: [PinTransaction; 128] = core::array::from_fn(|i| {
+            PinTransaction::get(if (i / 16) % 2 == 0 {
+                PinState::High
+            } else {
+                PinState::Low
+            })
+        });
+        let rx = PinMock::new(&rx_transactions);
+        let config = AskConfig::builder()
+            .cad_window_bits(8)
+            .csma(true)
+            .csma_max_attempts(2)
+            .build()
+            .unwrap();
+
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, None, config).unwrap();
+        driver.send_bytes(b"Hi").unwrap();
+
+        for _ in 0..64 {
+            driver.tick().unwrap();
+        }
+        assert_eq!(driver.mode, AskMode::Idle);
+        assert_eq!(driver.stats().csma_backoffs, 1);
+        assert!(driver.is_tx_busy());
+        // The first backoff is at most one CAD window
+        assert!((1..=64).contains(&driver.csma_backoff));
+
+        let mut ticks = 0;
+        while driver.is_tx_busy() {
+            driver.tick().unwrap();
+            ticks += 1;
+            assert!(ticks <= 64 + 64);
+        }
+        assert_eq!(driver.stats().cad_busy, 2);
+        assert_eq!(driver.stats().csma_failures, 1);
+        assert_eq!(driver.stats().tx_good, 0);
+        assert!(driver.take_tx_complete());
+
+        driver.tx.done();
+        driver.rx.done();
+    }
 }
@@ -17,9 +17,31 @@
 //! ## Symbol Table
 //!
 //! The encoder maps each 4-bit nibble to a unique 6-bit balanced symbol,
-//! taken from a fixed lookup table. The decoder uses a reverse-lookup table
-//! to reconstruct the original nibbles. Only 16 of the 64 possible 6-bit
-//! values are valid in this scheme.
+//! taken from a fixed lookup table. The decoder uses a 64-entry reverse-lookup
+//! table ([`SYMBOL_NIBBLES`]) to reconstruct the original nibbles. Only 16 of the
+//! 64 possible 6-bit values are valid in this scheme.
+//!
+//! ## Line codes
+//!
+//! The driver is generic over a [`LineCode`], which maps each nibble of a frame to
+//! an on-air symbol. 4b6b ([`FourBSixB`]) is the default and the only one RadioHead
+//! understands. [`ManchesterIeee`] and [`ManchesterThomas`] trade throughput for a
+//! transition in every bit, and [`Nrz`] sends the bytes as they are for the highest
+//! throughput at the cost of DC balance.
+//!
+//! | Line code            | Bits per byte | DC balanced | Invalid symbols detected |
+//! |----------------------|---------------|-------------|--------------------------|
+//! | [`FourBSixB`]        | 12            | Yes         | Yes                      |
+//! | [`ManchesterIeee`]   | 16            | Yes         | Yes                      |
+//! | [`ManchesterThomas`] | 16            | Yes         | Yes                      |
+//! | [`Nrz`]              | 8             | No          | No                       |
+//!
+//! Every line code uses the same 4b6b preamble and 12-bit start symbol, so the bit
+//! rate configured on the driver is always the on-air bit rate. Only the frame
+//! after the start symbol is sent in the chosen line code. This framing is this
+//! crate's own: the Manchester and NRZ modes only talk to other nodes running this
+//! crate with the same line code, not to Manchester or NRZ devices with framing of
+//! their own. With [`Nrz`], only the preamble is DC balanced.
 //!
 //! ## Functions
 //!
@@ -37,7 +59,8 @@
 //! ## Limitations
 //!
 //! - Only valid encoded 6-bit symbols are supported; decoding invalid values returns `None`
-//! - Input to `decode_buffer` must be even-length; symbol pairs are required
+//! - Input to `decode_buffer` must be even-length; symbol pairs are required, and an odd
+//!   length returns `None`
 
 #[cfg(not(feature = "std"))]
 use crate::consts::{ASK_MAX_BUF_LEN_USIZE, ASK_MAX_PAYLOAD_LEN_USIZE};
@@ -47,10 +70,28 @@ use heapless::Vec;
 use std::vec::Vec;
 
 /// 4b6b encoding symbol table.
-pub static SYMBOLS: [u8; 16] = [
+pub static SYMBOLS: [u8; 16] = SYMBOL_TABLE;
+
+/// [`SYMBOLS`] as a constant, so the reverse table can be built at compile time.
+const SYMBOL_TABLE: [u8; 16] = [
     0xd, 0xe, 0x13, 0x15, 0x16, 0x19, 0x1a, 0x1c, 0x23, 0x25, 0x26, 0x29, 0x2a, 0x2c, 0x32, 0x34,
 ];
 
+/// Marks a 6-bit value that is not a symbol in [`SYMBOL_NIBBLES`].
+pub const INVALID_SYMBOL: u8 = 0xff;
+
+/// Reverse of [`SYMBOLS`]: the nibble encoded by each 6-bit value, or
+/// [`INVALID_SYMBOL`] if the value is not a symbol.
+pub static SYMBOL_NIBBLES: [u8; 64] = {
+    let mut table = [INVALID_SYMBOL; 64];
+    let mut nibble = 0;
+    while nibble < SYMBOL_TABLE.len() {
+        table[SYMBOL_TABLE[nibble] as usize] = nibble as u8;
+        nibble += 1;
+    }
+    table
+};
+
 /// Encodes an 8-bit byte into two 6-bit symbols using 4b6b encoding.
 pub fn encode_4b6b(byte: u8) -> [u8; 2] {
     let high = byte >> 4;
@@ -61,10 +102,19 @@ pub fn encode_4b6b(byte: u8) -> [u8; 2] {
 /// Decodes two 6-bit symbols back into the original byte using the reverse symbol table.
 ///
 /// Returns `None` if either symbol is invalid (not part of the encoding table).
-pub fn decode_6b4b(symbol1: &u8, symbol2: &u8) -> u8 {
-    let b1 = SYMBOLS.iter().position(|s| s == symbol1).unwrap_or(0) as u8;
-    let b2 = SYMBOLS.iter().position(|s| s == symbol2).unwrap_or(0) as u8;
-    (b1 << 4) | b2
+pub fn decode_6b4b(symbol1: &u8, symbol2: &u8) -> Option<u8> {
+    Some((decode_symbol(*symbol1)? << 4) | decode_symbol(*symbol2)?)
+}
+
+/// Decodes a single 6-bit symbol into the nibble it encodes.
+///
+/// Returns `None` if the symbol is invalid (not part of the encoding table).
+/// Bits above the lower six make the symbol invalid.
+pub fn decode_symbol(symbol: u8) -> Option<u8> {
+    match SYMBOL_NIBBLES.get(symbol as usize) {
+        Some(&nibble) if nibble != INVALID_SYMBOL => Some(nibble),
+        _ => None,
+    }
 }
 
 /// Encodes an array of 8-bit bytes into the array `output` as 6-bit symbols using 4b6b encoding.
@@ -108,19 +158,17 @@ pub fn encode_buffer(input: &[u8]) -> Vec<u8, ASK_MAX_BUF_LEN_USIZE> {
 /// - `&mut [u8]` : The output buffer
 ///
 /// # Returns
-/// The optional length of the output buffer.
-/// Returns `None` if the input buffer is an uneven length. (As the 6-bit encoded data comes in
-/// pairs.)
+/// The decoded bytes, or `None` if the input buffer is an uneven length (as the
+/// 6-bit encoded data comes in pairs) or contains an invalid symbol.
 #[cfg(feature = "std")]
-pub fn decode_buffer(input: &[u8]) -> Vec<u8> {
-    if input.len() % 2 != 0 {
-        return Vec::new(); // Invalid input length
+pub fn decode_buffer(input: &[u8]) -> Option<Vec<u8>> {
+    if !input.len().is_multiple_of(2) {
+        return None;
     }
-    let mut output: Vec<u8> = Vec::with_capacity(input.len() / 2);
-    for chunk in input.chunks(2) {
-        output.push(decode_6b4b(&chunk[0], &chunk[1]));
-    }
-    output
+    input
+        .chunks(2)
+        .map(|chunk| decode_6b4b(&chunk[0], &chunk[1]))
+        .collect()
 }
 
 /// Decodes an array of 6-bit symbol pairs back into the original byte using the reverse symbol table.
@@ -130,19 +178,167 @@ pub fn decode_buffer(input: &[u8]) -> Vec<u8> {
 /// - `&mut [u8]` : The output buffer
 ///
 /// # Returns
-/// The optional length of the output buffer.
-/// Returns `None` if the input buffer is an uneven length. (As the 6-bit encoded data comes in
-/// pairs.)
+/// The decoded bytes, or `None` if the input buffer is an uneven length (as the
+/// 6-bit encoded data comes in pairs), contains an invalid symbol or decodes to
+/// more than [`ASK_MAX_PAYLOAD_LEN`](crate::consts::ASK_MAX_PAYLOAD_LEN) bytes.
 #[cfg(not(feature = "std"))]
-pub fn decode_buffer(input: &[u8]) -> Vec<u8, ASK_MAX_PAYLOAD_LEN_USIZE> {
-    if input.len() % 2 != 0 {
-        return Vec::new(); // Invalid input length
+pub fn decode_buffer(input: &[u8]) -> Option<Vec<u8, ASK_MAX_PAYLOAD_LEN_USIZE>> {
+    if !input.len().is_multiple_of(2) {
+        return None;
     }
     let mut output = Vec::new();
     for chunk in input.chunks(2) {
-        let _ = output.push(decode_6b4b(&chunk[0], &chunk[1]));
+        output.push(decode_6b4b(&chunk[0], &chunk[1])?).ok()?;
+    }
+    Some(output)
+}
+
+/// Maps the nibbles of a frame to on-air symbols and back.
+///
+/// Each byte is sent as two symbols, high nibble first, and each symbol is sent
+/// LSB first. Implementations only describe a single nibble; [`encode()`](LineCode::encode)
+/// and [`decode()`](LineCode::decode) handle whole bytes.
+///
+/// The line code only applies to the frame itself. The preamble and start symbol
+/// are always 4b6b, so both ends of a link must run this crate with the same line
+/// code (see the [module docs](self#line-codes)).
+///
+/// # Example
+///
+/// ```rust
+/// use ask433::encoding::{FourBSixB, LineCode, ManchesterIeee};
+///
+/// assert_eq!(FourBSixB::encode(0xab), [0x26, 0x29]);
+/// assert_eq!(ManchesterIeee::decode(0x66, 0xa6), Some(0xab));
+/// assert_eq!(ManchesterIeee::decode(0x66, 0x00), None);
+/// ```
+pub trait LineCode {
+    /// Number of on-air bits each nibble is sent as, at most 8.
+    const SYMBOL_BITS: u8;
+
+    /// Encodes a nibble (`0..=15`) into a symbol.
+    fn encode_nibble(nibble: u8) -> u8;
+
+    /// Decodes a symbol back into its nibble.
+    ///
+    /// Returns `None` if the symbol is not one this line code produces.
+    fn decode_symbol(symbol: u8) -> Option<u8>;
+
+    /// Encodes a byte into two symbols, high nibble first.
+    fn encode(byte: u8) -> [u8; 2] {
+        [
+            Self::encode_nibble(byte >> 4),
+            Self::encode_nibble(byte & 0x0f),
+        ]
+    }
+
+    /// Decodes two symbols, high nibble first, back into a byte.
+    ///
+    /// Returns `None` if either symbol is invalid.
+    fn decode(high: u8, low: u8) -> Option<u8> {
+        Some((Self::decode_symbol(high)? << 4) | Self::decode_symbol(low)?)
+    }
+}
+
+/// RadioHead's DC-balanced 4b6b code, see [`SYMBOLS`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FourBSixB;
+
+impl LineCode for FourBSixB {
+    const SYMBOL_BITS: u8 = 6;
+
+    fn encode_nibble(nibble: u8) -> u8 {
+        SYMBOLS[(nibble & 0x0f) as usize]
+    }
+
+    fn decode_symbol(symbol: u8) -> Option<u8> {
+        decode_symbol(symbol)
+    }
+}
+
+/// Manchester code with the IEEE 802.3 polarity: a 1 is sent as a low-to-high
+/// transition, a 0 as high-to-low.
+///
+/// Bytes are sent MSB first, each bit as two half-bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ManchesterIeee;
+
+impl LineCode for ManchesterIeee {
+    const SYMBOL_BITS: u8 = 8;
+
+    fn encode_nibble(nibble: u8) -> u8 {
+        manchester_encode(nibble, 0b10)
+    }
+
+    fn decode_symbol(symbol: u8) -> Option<u8> {
+        manchester_decode(symbol, 0b10)
     }
-    output
+}
+
+/// Manchester code with the G.E. Thomas polarity: a 1 is sent as a high-to-low
+/// transition, a 0 as low-to-high.
+///
+/// Bytes are sent MSB first, each bit as two half-bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ManchesterThomas;
+
+impl LineCode for ManchesterThomas {
+    const SYMBOL_BITS: u8 = 8;
+
+    fn encode_nibble(nibble: u8) -> u8 {
+        manchester_encode(nibble, 0b01)
+    }
+
+    fn decode_symbol(symbol: u8) -> Option<u8> {
+        manchester_decode(symbol, 0b01)
+    }
+}
+
+/// Raw non-return-to-zero: bytes are sent as they are, MSB first.
+///
+/// # Notes
+/// NRZ is not DC balanced; only the 4b6b preamble in front of the frame is. Long
+/// runs of equal bits give the receiver PLL nothing to lock on to, and the data
+/// slicer of cheap receivers drifts during them, so keep frames short or the
+/// payload varied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Nrz;
+
+impl LineCode for Nrz {
+    const SYMBOL_BITS: u8 = 4;
+
+    fn encode_nibble(nibble: u8) -> u8 {
+        // Symbols go out LSB first
+        nibble.reverse_bits() >> 4
+    }
+
+    fn decode_symbol(symbol: u8) -> Option<u8> {
+        Some(symbol.reverse_bits() >> 4)
+    }
+}
+
+/// Encodes a nibble MSB first, sending `one` (two half-bits, first one in the LSB)
+/// for each 1 and its complement for each 0.
+fn manchester_encode(nibble: u8, one: u8) -> u8 {
+    (0..4).fold(0, |symbol, i| {
+        let bit = (nibble >> (3 - i)) & 1;
+        let half_bits = if bit == 1 { one } else { one ^ 0b11 };
+        symbol | (half_bits << (2 * i))
+    })
+}
+
+/// Reverses [`manchester_encode()`], or `None` if two half-bits are equal.
+fn manchester_decode(symbol: u8, one: u8) -> Option<u8> {
+    (0..4).try_fold(0, |nibble, i| {
+        let half_bits = (symbol >> (2 * i)) & 0b11;
+        if half_bits == one {
+            Some(nibble | (1 << (3 - i)))
+        } else if half_bits == one ^ 0b11 {
+            Some(nibble)
+        } else {
+            None
+        }
+    })
 }
 
 #[cfg(test)]
@@ -159,7 +355,21 @@ mod tests {
     #[test]
     fn test_decode_6b4b_correct_output() {
         let decoded = decode_6b4b(&0b10101, &0b1101);
-        assert_eq!(decoded, 0b110000);
+        assert_eq!(decoded, Some(0b110000));
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_symbols() {
+        for (nibble, symbol) in SYMBOLS.iter().enumerate() {
+            assert_eq!(decode_symbol(*symbol), Some(nibble as u8));
+        }
+        let valid = (0..=u8::MAX)
+            .filter(|s| decode_symbol(*s).is_some())
+            .count();
+        assert_eq!(valid, SYMBOLS.len());
+        assert_eq!(decode_6b4b(&0x26, &0x00), None);
+        assert_eq!(decode_6b4b(&0x3f, &0x29), None);
+        assert_eq!(decode_buffer(&[0x26, 0x29, 0x26, 0x3f]), None);
     }
 
     #[test]
@@ -176,26 +386,40 @@ mod tests {
     #[test]
     fn test_decode_buffer_successful() {
         let input = [0x26, 0x29];
-        let output = decode_buffer(&input);
+        let output = decode_buffer(&input).unwrap();
         assert_eq!(output.len(), 1);
         assert_eq!(output[0], 0xAB);
     }
 
     #[test]
     fn test_decode_buffer_odd_length_fails() {
-        #[cfg(not(feature = "std"))]
-        use heapless::Vec;
-        #[cfg(feature = "std")]
-        use std::vec::Vec;
-
         let input = [0x2a];
-        let result = decode_buffer(&input);
+        assert_eq!(decode_buffer(&input), None);
+    }
 
-        #[cfg(feature = "std")]
-        let expected: Vec<u8> = Vec::new();
-        #[cfg(not(feature = "std"))]
-        let expected: Vec<u8, ASK_MAX_PAYLOAD_LEN_USIZE> = Vec::new();
+    fn round_trip<LC: LineCode>() {
+        for byte in 0..=u8::MAX {
+            let [high, low] = LC::encode(byte);
+            assert!(u32::from(high) < 1 << LC::SYMBOL_BITS);
+            assert_eq!(LC::decode(high, low), Some(byte));
+        }
+    }
 
-        assert_eq!(result, expected);
+    #[test]
+    fn test_line_codes_round_trip() {
+        round_trip::<FourBSixB>();
+        round_trip::<ManchesterIeee>();
+        round_trip::<ManchesterThomas>();
+        round_trip::<Nrz>();
+        assert_eq!(FourBSixB::encode(0xab), encode_4b6b(0xab));
+    }
+
+    #[test]
+    fn test_manchester_polarities() {
+        // 0b1010, MSB first, half-bits LSB first
+        assert_eq!(ManchesterIeee::encode_nibble(0x0a), 0b0110_0110);
+        assert_eq!(ManchesterThomas::encode_nibble(0x0a), 0b1001_1001);
+        assert_eq!(ManchesterIeee::decode_symbol(0b0110_0111), None);
+        assert_eq!(Nrz::encode_nibble(0b0001), 0b1000);
     }
 }
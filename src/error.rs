@@ -0,0 +1,197 @@
+//! Error types for the ASK driver.
+//!
+//! Every fallible operation on [`AskDriver`](crate::driver::AskDriver) reports
+//! an [`AskError`]. The error wraps the underlying `embedded-hal` pin errors
+//! (so a failing GPIO never has to be unwrapped inside an ISR) together with
+//! the protocol-level failure cases of the modem itself.
+//!
+//! For the common case of naming the error type of a concrete driver, use the
+//! [`AskDriverError`] alias, which derives the pin error types from the pin types.
+
+use core::fmt;
+use embedded_hal::digital::ErrorType;
+
+/// Errors that can be returned by the ASK driver and its helpers.
+///
+/// # Type Parameters
+/// - `TXE`: Error type of the TX pin
+/// - `RXE`: Error type of the RX pin
+/// - `PTTE`: Error type of the PTT pin
+/// - `RXENE`, `TXENE`: Error types of the optional receiver and transmitter enable
+///   pins (default to `PTTE`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AskError<TXE, RXE, PTTE, RXENE = PTTE, TXENE = PTTE> {
+    /// Driving the TX pin failed.
+    Tx(TXE),
+    /// Sampling the RX pin failed.
+    Rx(RXE),
+    /// Driving the Push To Talk (PTT) pin failed.
+    Ptt(PTTE),
+    /// Driving the receiver enable pin failed.
+    RxEnable(RXENE),
+    /// Driving the transmitter enable pin failed.
+    TxEnable(TXENE),
+    /// The message is longer than the driver's
+    /// [`max_message_len()`](crate::driver::AskDriver::max_message_len).
+    MessageTooLong,
+    /// An internal buffer ran out of space.
+    BufferOverflow,
+    /// The transmitter is still busy sending a previous frame.
+    Busy,
+    /// The requested operation is not allowed in the driver's current [`AskMode`](crate::driver::AskMode).
+    InvalidMode,
+    /// A global driver has not been set up yet.
+    Uninitialized,
+    /// A fixed-code remote code has an unknown protocol, no bits or more than 32 bits.
+    InvalidRcCode,
+    /// A runtime configuration change was rejected.
+    Config(ConfigError),
+}
+
+/// The [`AskError`] type produced by an `AskDriver<TX, RX, PTT>`, or by one with
+/// enable pins of their own types `RXEN` and `TXEN`.
+pub type AskDriverError<TX, RX, PTT, RXEN = PTT, TXEN = PTT> = AskError<
+    <TX as ErrorType>::Error,
+    <RX as ErrorType>::Error,
+    <PTT as ErrorType>::Error,
+    <RXEN as ErrorType>::Error,
+    <TXEN as ErrorType>::Error,
+>;
+
+impl<TXE, RXE, PTTE, RXENE, TXENE> fmt::Display for AskError<TXE, RXE, PTTE, RXENE, TXENE>
+where
+    TXE: fmt::Debug,
+    RXE: fmt::Debug,
+    PTTE: fmt::Debug,
+    RXENE: fmt::Debug,
+    TXENE: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AskError::Tx(e) => write!(f, "TX pin error: {e:?}"),
+            AskError::Rx(e) => write!(f, "RX pin error: {e:?}"),
+            AskError::Ptt(e) => write!(f, "PTT pin error: {e:?}"),
+            AskError::RxEnable(e) => write!(f, "RX enable pin error: {e:?}"),
+            AskError::TxEnable(e) => write!(f, "TX enable pin error: {e:?}"),
+            AskError::MessageTooLong => write!(f, "message too long"),
+            AskError::BufferOverflow => write!(f, "buffer overflow"),
+            AskError::Busy => write!(f, "transmitter busy"),
+            AskError::InvalidMode => write!(f, "invalid mode for this operation"),
+            AskError::Uninitialized => write!(f, "driver not initialized"),
+            AskError::InvalidRcCode => write!(f, "invalid remote code"),
+            AskError::Config(e) => write!(f, "invalid configuration: {e}"),
+        }
+    }
+}
+
+impl<TXE, RXE, PTTE, RXENE, TXENE> core::error::Error for AskError<TXE, RXE, PTTE, RXENE, TXENE>
+where
+    TXE: fmt::Debug,
+    RXE: fmt::Debug,
+    PTTE: fmt::Debug,
+    RXENE: fmt::Debug,
+    TXENE: fmt::Debug,
+{
+}
+
+/// Errors returned when building an [`AskConfig`](crate::config::AskConfig).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The ticks per bit are below [`ASK_MIN_TICKS_PER_BIT`](crate::consts::ASK_MIN_TICKS_PER_BIT).
+    TicksPerBitTooLow(u8),
+    /// The tick rate (in Hz) gives too few or too many ticks per bit at the chosen bit rate.
+    InvalidTickRate(u32),
+    /// The preamble length is outside
+    /// [`ASK_MIN_PREAMBLE_LEN`](crate::consts::ASK_MIN_PREAMBLE_LEN)..=[`ASK_MAX_PREAMBLE_LEN`](crate::consts::ASK_MAX_PREAMBLE_LEN).
+    InvalidPreambleLen(u8),
+    /// The channel activity detection window (in bits) is shorter than
+    /// [`ASK_MIN_CAD_WINDOW_BITS`](crate::consts::ASK_MIN_CAD_WINDOW_BITS).
+    InvalidCadWindow(u8),
+    /// CSMA was given zero attempts to find a clear channel.
+    InvalidCsmaAttempts(u8),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::TicksPerBitTooLow(n) => write!(f, "{n} ticks per bit is too few"),
+            ConfigError::InvalidTickRate(hz) => {
+                write!(f, "a {hz} Hz tick does not fit the bit rate")
+            }
+            ConfigError::InvalidPreambleLen(n) => write!(f, "invalid preamble length: {n}"),
+            ConfigError::InvalidCadWindow(n) => write!(f, "CAD window of {n} bits is too short"),
+            ConfigError::InvalidCsmaAttempts(n) => write!(f, "invalid CSMA attempts: {n}"),
+        }
+    }
+}
+
+impl core::error::Error for ConfigError {}
+
+/// Error returned when application code tries to set protocol-reserved header flags.
+///
+/// Holds the rejected flags byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReservedFlagsError(pub u8);
+
+impl fmt::Display for ReservedFlagsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "flags {:#04x} use reserved bits", self.0)
+    }
+}
+
+impl core::error::Error for ReservedFlagsError {}
+
+/// Errors returned when splitting, sending or reassembling a fragmented message.
+///
+/// `E` is the driver error a fragment could not be sent with, see
+/// [`FragmentSender::send_fragment()`](crate::fragment::FragmentSender::send_fragment).
+/// Nothing else involves a driver, hence the [`Infallible`](core::convert::Infallible) default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FragmentError<E = core::convert::Infallible> {
+    /// The message needs more than [`MAX_FRAGMENTS`](crate::fragment::MAX_FRAGMENTS) fragments.
+    MessageTooLong,
+    /// The packet does not have the [`ASK_FLAGS_FRAGMENT`](crate::consts::ASK_FLAGS_FRAGMENT) flag set.
+    NotAFragment,
+    /// The fragment header is inconsistent, or a fragment other than the last is short.
+    Malformed,
+    /// The reassembly buffer cannot hold the message.
+    BufferTooSmall,
+    /// The message has no fragment at this index.
+    InvalidIndex(u8),
+    /// The driver could not send the fragment.
+    Driver(E),
+}
+
+impl<E: fmt::Display> fmt::Display for FragmentError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FragmentError::MessageTooLong => write!(f, "message too long to fragment"),
+            FragmentError::NotAFragment => write!(f, "not a fragment"),
+            FragmentError::Malformed => write!(f, "malformed fragment"),
+            FragmentError::BufferTooSmall => write!(f, "reassembly buffer too small"),
+            FragmentError::InvalidIndex(index) => write!(f, "no fragment at index {index}"),
+            FragmentError::Driver(e) => write!(f, "fragment not sent: {e}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> core::error::Error for FragmentError<E> {}
+
+/// The [`FragmentError`] type produced when sending a fragment with an
+/// `AskDriver<TX, RX, PTT>`, or with one with enable pins of their own types.
+pub type FragmentDriverError<TX, RX, PTT, RXEN = PTT, TXEN = PTT> =
+    FragmentError<AskDriverError<TX, RX, PTT, RXEN, TXEN>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_display_wraps_pin_errors() {
+        let err: AskError<u8, u8, u8> = AskError::Tx(3);
+        let mut out: heapless::String<32> = heapless::String::new();
+        write!(out, "{err}").unwrap();
+        assert_eq!(out.as_str(), "TX pin error: 3");
+    }
+}
@@ -0,0 +1,188 @@
+//! Forward error correction.
+//!
+//! Frames sent with FEC enabled carry their payload and FCS as an
+//! extended Hamming(8,4) code: a Hamming(7,4) codeword plus an overall parity bit.
+//! Each codeword corrects one flipped bit and detects two.
+//!
+//! A single corrupt line-code symbol garbles four data bits, more than one
+//! codeword can correct. The codewords are therefore interleaved in blocks of
+//! four ([`encode_block()`]), so each symbol on air carries one bit of each of the
+//! four codewords. One bad symbol in every [`FEC_BLOCK_LEN`] bytes on air is
+//! corrected, even with a line code that detects invalid symbols.
+//!
+//! ## Frame layout
+//!
+//! ```text
+//! [count, to, from, id, flags | ASK_FLAGS_FEC, FEC(...payload..., ...fcs..., padding)]
+//! ```
+//!
+//! FEC is negotiated through the [`ASK_FLAGS_FEC`](crate::consts::ASK_FLAGS_FEC)
+//! header flag. The count byte and the headers stay uncoded: the receiver checks
+//! the count for sense as soon as it arrives, and has to read the flags before
+//! the coded part starts. The count and the FCS are the same as for the uncoded
+//! frame, so the count also tells where the zero padding of the last block
+//! starts. Receivers handle both kinds of frames, so only senders need to enable
+//! FEC (see [`AskConfigBuilder::fec()`](crate::config::AskConfigBuilder::fec)).
+//! Receivers without FEC support drop the frames because their FCS does not match.
+//!
+//! A flipped bit in the count byte or the headers is not corrected. Frames
+//! without headers, such as [`VirtualWire`](crate::format::VirtualWire) frames,
+//! have no flags to signal FEC in and are always sent uncoded.
+//!
+//! FEC halves the longest message that fits a frame, see
+//! [`AskDriver::max_message_len()`](crate::driver::AskDriver::max_message_len).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ask433::fec::{decode_block, encode_block};
+//!
+//! let mut block = encode_block([0x12, 0x34]);
+//! // Garble a whole nibble on air
+//! block[1] ^= 0xf0;
+//! assert_eq!(decode_block(block), Some(([0x12, 0x34], 4)));
+//! ```
+
+/// Number of data bytes in an interleaved block.
+pub const FEC_DATA_LEN: usize = 2;
+
+/// Number of bytes an interleaved block takes on air.
+pub const FEC_BLOCK_LEN: usize = 2 * FEC_DATA_LEN;
+
+/// Extended Hamming(8,4) codeword of each nibble.
+///
+/// Bit 0 is the overall parity; bits 1 to 7 are the Hamming(7,4) positions
+/// `p1 p2 d1 p3 d2 d3 d4`.
+static CODEWORDS: [u8; 16] = {
+    let mut table = [0; 16];
+    let mut nibble: u8 = 0;
+    while nibble < 16 {
+        let d1 = (nibble >> 3) & 1;
+        let d2 = (nibble >> 2) & 1;
+        let d3 = (nibble >> 1) & 1;
+        let d4 = nibble & 1;
+        let p1 = d1 ^ d2 ^ d4;
+        let p2 = d1 ^ d3 ^ d4;
+        let p3 = d2 ^ d3 ^ d4;
+        let code =
+            (p1 << 1) | (p2 << 2) | (d1 << 3) | (p3 << 4) | (d2 << 5) | (d3 << 6) | (d4 << 7);
+        table[nibble as usize] = code | (code.count_ones() as u8 & 1);
+        nibble += 1;
+    }
+    table
+};
+
+/// Encodes a nibble (`0..=15`) as an extended Hamming(8,4) codeword.
+pub fn hamming_encode(nibble: u8) -> u8 {
+    CODEWORDS[(nibble & 0x0f) as usize]
+}
+
+/// Decodes an extended Hamming(8,4) codeword.
+///
+/// # Returns
+/// - `Some((nibble, corrected))`: the nibble, and whether a flipped bit was corrected
+/// - `None`: two or more bits were flipped
+pub fn hamming_decode(code: u8) -> Option<(u8, bool)> {
+    // Codewords are at least 4 bits apart, so at most one is within 1 bit
+    CODEWORDS
+        .iter()
+        .zip(0..)
+        .find_map(|(codeword, nibble)| match (codeword ^ code).count_ones() {
+            0 => Some((nibble, false)),
+            1 => Some((nibble, true)),
+            _ => None,
+        })
+}
+
+/// Encodes two data bytes as four interleaved codewords.
+///
+/// Bit `k` of codeword `i` (the nibbles of `data`, high nibble first) is sent as
+/// bit `i` of nibble `k` on air.
+pub fn encode_block(data: [u8; FEC_DATA_LEN]) -> [u8; FEC_BLOCK_LEN] {
+    let codewords = [
+        hamming_encode(data[0] >> 4),
+        hamming_encode(data[0]),
+        hamming_encode(data[1] >> 4),
+        hamming_encode(data[1]),
+    ];
+    let mut block = [0; FEC_BLOCK_LEN];
+    for (k, byte) in (0..8).map(|k| (k, k / 2)) {
+        let nibble = codewords
+            .iter()
+            .enumerate()
+            .fold(0, |nibble, (i, codeword)| {
+                nibble | (((codeword >> k) & 1) << i)
+            });
+        block[byte] |= if k % 2 == 0 { nibble << 4 } else { nibble };
+    }
+    block
+}
+
+/// Reverses [`encode_block()`], correcting one flipped bit per codeword.
+///
+/// # Returns
+/// - `Some((data, corrected))`: the two data bytes and the number of corrected bits
+/// - `None`: a codeword had two or more flipped bits
+pub fn decode_block(block: [u8; FEC_BLOCK_LEN]) -> Option<([u8; FEC_DATA_LEN], u8)> {
+    let mut codewords = [0u8; 4];
+    for k in 0..8 {
+        let byte = block[k / 2];
+        let nibble = if k % 2 == 0 { byte >> 4 } else { byte & 0x0f };
+        for (i, codeword) in codewords.iter_mut().enumerate() {
+            *codeword |= ((nibble >> i) & 1) << k;
+        }
+    }
+    let mut nibbles = [0u8; 4];
+    let mut corrected = 0;
+    for (nibble, codeword) in nibbles.iter_mut().zip(codewords) {
+        let (decoded, fixed) = hamming_decode(codeword)?;
+        *nibble = decoded;
+        corrected += u8::from(fixed);
+    }
+    Some((
+        [
+            (nibbles[0] << 4) | nibbles[1],
+            (nibbles[2] << 4) | nibbles[3],
+        ],
+        corrected,
+    ))
+}
+
+/// Number of bytes `len` bytes take on air once coded, padding included.
+pub const fn fec_coded_len(len: usize) -> usize {
+    len.div_ceil(FEC_DATA_LEN) * FEC_BLOCK_LEN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hamming_corrects_one_and_detects_two_errors() {
+        for nibble in 0..16 {
+            let code = hamming_encode(nibble);
+            assert_eq!(hamming_decode(code), Some((nibble, false)));
+            for bit in 0..8 {
+                assert_eq!(hamming_decode(code ^ (1 << bit)), Some((nibble, true)));
+                for other in (bit + 1)..8 {
+                    assert_eq!(hamming_decode(code ^ (1 << bit) ^ (1 << other)), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_block_survives_any_garbled_nibble() {
+        let data = [0xa5, 0x3c];
+        assert_eq!(decode_block(encode_block(data)), Some((data, 0)));
+        for k in 0..8 {
+            let mut block = encode_block(data);
+            block[k / 2] ^= if k % 2 == 0 { 0xf0 } else { 0x0f };
+            assert_eq!(decode_block(block), Some((data, 4)));
+        }
+        let mut block = encode_block(data);
+        block[0] ^= 0x11;
+        assert_eq!(decode_block(block), None);
+        assert_eq!(fec_coded_len(7), 16);
+    }
+}
@@ -0,0 +1,157 @@
+//! Typed header flags.
+//!
+//! Every frame carries a flags byte in its header. Like RadioHead, the byte is
+//! split in two halves:
+//!
+//! | Bits   | Owner       | Flags |
+//! |--------|-------------|-------|
+//! | 7..=4  | Protocol    | [`ASK_FLAGS_ACK`], [`ASK_FLAGS_RETRY`], [`ASK_FLAGS_FRAGMENT`], [`ASK_FLAGS_FEC`] |
+//! | 3..=0  | Application | Free for application use |
+//!
+//! [`HeaderFlags`] keeps the two apart: application code can only set the lower
+//! four bits, while the protocol bits are set by the layers of this crate that
+//! implement them (e.g. [`ReliableDatagram`](crate::reliable::ReliableDatagram) for
+//! ACKs). Both halves can be read.
+//!
+//! RadioHead itself only uses the ACK and retry bits, so frames from Arduino nodes
+//! never carry the other protocol bits.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ask433::flags::HeaderFlags;
+//!
+//! let flags = HeaderFlags::from_application(0x05).unwrap();
+//! assert_eq!(flags.application(), 0x05);
+//! assert!(!flags.is_ack());
+//!
+//! // The upper four bits belong to the protocol
+//! assert!(HeaderFlags::from_application(0x85).is_err());
+//! ```
+
+use crate::consts::{
+    ASK_FLAGS_ACK, ASK_FLAGS_APPLICATION_SPECIFIC, ASK_FLAGS_FEC, ASK_FLAGS_FRAGMENT,
+    ASK_FLAGS_NONE, ASK_FLAGS_RESERVED, ASK_FLAGS_RETRY,
+};
+use crate::error::ReservedFlagsError;
+
+/// The flags byte of a frame header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct HeaderFlags(u8);
+
+impl HeaderFlags {
+    /// No flags set.
+    pub const NONE: Self = Self(ASK_FLAGS_NONE);
+
+    /// The frame is an acknowledgement.
+    pub(crate) const ACK: Self = Self(ASK_FLAGS_ACK);
+
+    /// The frame is a retransmission.
+    pub(crate) const RETRY: Self = Self(ASK_FLAGS_RETRY);
+
+    /// The frame is one fragment of a longer message.
+    pub(crate) const FRAGMENT: Self = Self(ASK_FLAGS_FRAGMENT);
+
+    /// Application flags, without any protocol bits.
+    ///
+    /// # Errors
+    /// [`ReservedFlagsError`] if `bits` has any of the [`ASK_FLAGS_RESERVED`] bits set.
+    pub const fn from_application(bits: u8) -> Result<Self, ReservedFlagsError> {
+        if bits & ASK_FLAGS_RESERVED != 0 {
+            return Err(ReservedFlagsError(bits));
+        }
+        Ok(Self(bits))
+    }
+
+    /// Flags as received on air, protocol bits included.
+    pub(crate) const fn from_bits_retain(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// Replaces the application bits, keeping the protocol bits.
+    ///
+    /// # Errors
+    /// [`ReservedFlagsError`] if `bits` has any of the [`ASK_FLAGS_RESERVED`] bits set.
+    pub fn set_application(&mut self, bits: u8) -> Result<(), ReservedFlagsError> {
+        let application = Self::from_application(bits)?;
+        self.0 = (self.0 & ASK_FLAGS_RESERVED) | application.0;
+        Ok(())
+    }
+
+    /// Sets the protocol bits of `other` as well.
+    pub(crate) fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// The whole flags byte.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// The application bits.
+    pub const fn application(self) -> u8 {
+        self.0 & ASK_FLAGS_APPLICATION_SPECIFIC
+    }
+
+    /// The protocol bits.
+    pub const fn protocol(self) -> u8 {
+        self.0 & ASK_FLAGS_RESERVED
+    }
+
+    /// Whether the frame is an acknowledgement ([`ASK_FLAGS_ACK`]).
+    pub const fn is_ack(self) -> bool {
+        self.0 & ASK_FLAGS_ACK != 0
+    }
+
+    /// Whether the frame is a retransmission ([`ASK_FLAGS_RETRY`]).
+    pub const fn is_retry(self) -> bool {
+        self.0 & ASK_FLAGS_RETRY != 0
+    }
+
+    /// Whether the frame is one fragment of a longer message ([`ASK_FLAGS_FRAGMENT`]).
+    pub const fn is_fragment(self) -> bool {
+        self.0 & ASK_FLAGS_FRAGMENT != 0
+    }
+
+    /// Whether the frame was sent with forward error correction ([`ASK_FLAGS_FEC`]).
+    pub const fn is_fec(self) -> bool {
+        self.0 & ASK_FLAGS_FEC != 0
+    }
+}
+
+impl From<HeaderFlags> for u8 {
+    fn from(flags: HeaderFlags) -> u8 {
+        flags.0
+    }
+}
+
+impl TryFrom<u8> for HeaderFlags {
+    type Error = ReservedFlagsError;
+
+    /// Same as [`HeaderFlags::from_application()`].
+    fn try_from(bits: u8) -> Result<Self, Self::Error> {
+        Self::from_application(bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_application_bits_keep_protocol_bits() {
+        let mut flags = HeaderFlags::ACK;
+        flags.insert(HeaderFlags::RETRY);
+        flags.set_application(0x03).unwrap();
+        assert_eq!(flags.bits(), 0xc3);
+        assert!(flags.is_ack() && flags.is_retry());
+        assert!(!flags.is_fragment() && !flags.is_fec());
+        assert_eq!((flags.protocol(), flags.application()), (0xc0, 0x03));
+
+        assert_eq!(flags.set_application(0x13), Err(ReservedFlagsError(0x13)));
+        assert_eq!(flags.bits(), 0xc3);
+        assert_eq!(HeaderFlags::try_from(0x20), Err(ReservedFlagsError(0x20)));
+        assert_eq!(HeaderFlags::try_from(0x10), Err(ReservedFlagsError(0x10)));
+        assert_eq!(u8::from(HeaderFlags::try_from(0x0f).unwrap()), 0x0f);
+    }
+}
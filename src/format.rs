@@ -0,0 +1,87 @@
+//! Frame formats.
+//!
+//! The driver is generic over a [`FrameFormat`] that decides which headers sit
+//! between the count byte and the payload:
+//!
+//! | Format           | Frame layout                                       |
+//! |------------------|----------------------------------------------------|
+//! | [`RadioHead`]    | `[count, to, from, id, flags, ...payload..., ...fcs...]` (default) |
+//! | [`VirtualWire`]  | `[count, ...payload..., ...fcs...]`                |
+//!
+//! Frames received without headers are reported as broadcasts from
+//! [`BROADCAST_ADDRESS`] with id 0 and no flags. They carry nothing to tell
+//! repeats apart, so the duplicate filter lets them all through.
+//!
+//! The reliable datagram, routing, mesh and fragmentation layers need the headers
+//! and only work with [`RadioHead`] frames.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ask433::format::{FrameFormat, RadioHead, VirtualWire};
+//!
+//! let headers = [0x01, 0x02, 0x03, 0x00];
+//! assert_eq!(RadioHead::encode_header(&headers), &headers);
+//! assert!(VirtualWire::encode_header(&headers).is_empty());
+//! assert_eq!(VirtualWire::decode_header(&[]), [0xff, 0xff, 0, 0]);
+//! ```
+
+use crate::consts::{ASK_HEADER_LEN, BROADCAST_ADDRESS};
+
+/// The headers of a frame, as `[to, from, id, flags]`.
+pub type Headers = [u8; ASK_HEADER_LEN as usize];
+
+/// Lays out the headers between the count byte and the payload of a frame.
+pub trait FrameFormat {
+    /// Number of header bytes sent in each frame.
+    const HEADER_LEN: u8;
+
+    /// Position of the flags byte in the header bytes, `None` if none is sent.
+    ///
+    /// Forward error correction is signalled in the flags, so frames without
+    /// them are always sent uncoded.
+    const FLAGS_OFFSET: Option<u8>;
+
+    /// Returns the header bytes sent for `headers`, [`HEADER_LEN`](FrameFormat::HEADER_LEN) of them.
+    fn encode_header(headers: &Headers) -> &[u8];
+
+    /// Recovers the headers from the [`HEADER_LEN`](FrameFormat::HEADER_LEN) header
+    /// bytes of a received frame.
+    fn decode_header(header: &[u8]) -> Headers;
+}
+
+/// RadioHead frames with the 4 byte `to`, `from`, `id` and `flags` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RadioHead;
+
+impl FrameFormat for RadioHead {
+    const HEADER_LEN: u8 = ASK_HEADER_LEN;
+    const FLAGS_OFFSET: Option<u8> = Some(ASK_HEADER_LEN - 1);
+
+    fn encode_header(headers: &Headers) -> &[u8] {
+        headers
+    }
+
+    fn decode_header(header: &[u8]) -> Headers {
+        let mut headers = [0; ASK_HEADER_LEN as usize];
+        headers.copy_from_slice(header);
+        headers
+    }
+}
+
+/// Headerless frames, as sent by the original VirtualWire library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VirtualWire;
+
+impl FrameFormat for VirtualWire {
+    const HEADER_LEN: u8 = 0;
+    const FLAGS_OFFSET: Option<u8> = None;
+
+    fn encode_header(_headers: &Headers) -> &[u8] {
+        &[]
+    }
+
+    fn decode_header(_header: &[u8]) -> Headers {
+        [BROADCAST_ADDRESS, BROADCAST_ADDRESS, 0, 0]
+    }
+}
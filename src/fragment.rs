@@ -0,0 +1,569 @@
+//! Fragmentation and reassembly of messages longer than one frame.
+//!
+//! A single frame carries at most
+//! [`AskDriver::max_message_len()`](crate::driver::AskDriver::max_message_len) bytes of
+//! user data, which depends on the checksum and on forward error correction.
+//! [`FragmentSender`] splits a longer message into up to [`MAX_FRAGMENTS`] frames,
+//! each flagged with [`ASK_FLAGS_FRAGMENT`](crate::consts::ASK_FLAGS_FRAGMENT) and starting with a
+//! [`FRAGMENT_HEADER_LEN`] byte sub-header:
+//!
+//! | Byte | Field        | Meaning |
+//! |------|--------------|---------|
+//! | 0    | `message_id` | Same for every fragment of a message |
+//! | 1    | `index`      | Position of the fragment, from `0` |
+//! | 2    | `count`      | Number of fragments in the message |
+//! | 3    | `stride`     | Data bytes in every fragment but the last |
+//!
+//! The sender picks the stride to fill its frames, and the receiver places each
+//! fragment at `index * stride`, so both ends need not agree on a frame size.
+//! [`Reassembler`] puts received fragments back together in a caller-provided buffer,
+//! in any order. It tells which fragments are still [`missing`](Reassembler::missing)
+//! and drops incomplete messages after a timeout.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+//! use ask433::config::AskConfig;
+//! use ask433::driver::AskDriver;
+//! use ask433::fragment::{FragmentSender, Reassembler};
+//!
+//! # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
+//! # let rx_pin = Pin::new(&[]);
+//! let mut driver: AskDriver<Pin, Pin, Pin> =
+//!     AskDriver::new(tx_pin, rx_pin, None, AskConfig::default()).unwrap();
+//!
+//! // Sending: queue fragments as the transmit queue drains
+//! let blob = [0x5a; 150];
+//! let mut sender = FragmentSender::new(0x02, 1, &blob, driver.max_message_len()).unwrap();
+//! assert_eq!(sender.fragment_count(), 3);
+//! nb::block!(sender.poll(&mut driver)).unwrap();
+//!
+//! // Receiving: feed every received packet to the reassembler
+//! let mut buf = [0u8; 512];
+//! let mut reassembler = Reassembler::new(&mut buf, 16_000);
+//! while let Ok(Some(packet)) = driver.receive() {
+//!     if let Ok(Some(message)) = reassembler.push(&packet) {
+//!         // message holds the whole blob
+//!     }
+//! }
+//! # driver.tx.done();
+//! # driver.rx.done();
+//! ```
+
+use crate::consts::ASK_MAX_MESSAGE_LEN;
+use crate::crc::Checksum;
+use crate::driver::AskDriver;
+use crate::encoding::LineCode;
+use crate::error::{AskDriverError, AskError, FragmentDriverError, FragmentError};
+use crate::flags::HeaderFlags;
+use crate::format::FrameFormat;
+use crate::packet::AskPacket;
+use embedded_hal::digital::{InputPin, OutputPin};
+
+/// Length (in bytes) of the sub-header at the start of every fragment.
+pub const FRAGMENT_HEADER_LEN: usize = 4;
+
+/// Maximum number of fragments in a message.
+///
+/// [`Reassembler`] keeps track of the received fragments in a 32-bit mask.
+pub const MAX_FRAGMENTS: u8 = 32;
+
+/// Maximum length (in bytes) of a fragmented message, sent in frames of
+/// [`ASK_MAX_MESSAGE_LEN`] bytes. Smaller frames carry less.
+pub const MAX_FRAGMENTED_LEN: usize =
+    MAX_FRAGMENTS as usize * (ASK_MAX_MESSAGE_LEN as usize - FRAGMENT_HEADER_LEN);
+
+/// Splits a message into fragments and queues them on an [`AskDriver`].
+#[derive(Debug, Clone)]
+pub struct FragmentSender<'a> {
+    to: u8,
+    message_id: u8,
+    data: &'a [u8],
+    stride: u8,
+    count: u8,
+    next: u8,
+}
+
+impl<'a> FragmentSender<'a> {
+    /// Prepares `data` to be sent to `to` as message `message_id`, in frames of up to
+    /// `max_message_len` bytes.
+    ///
+    /// Pass the [`max_message_len()`](AskDriver::max_message_len) of the driver the
+    /// fragments are sent with; anything above [`ASK_MAX_MESSAGE_LEN`] is capped to
+    /// it. The receiver tells messages apart by sender and
+    /// `message_id`, so use a new id for every message.
+    ///
+    /// # Errors
+    /// [`FragmentError::MessageTooLong`] if `data` does not fit in [`MAX_FRAGMENTS`]
+    /// frames of `max_message_len` bytes.
+    pub fn new(
+        to: u8,
+        message_id: u8,
+        data: &'a [u8],
+        max_message_len: u8,
+    ) -> Result<Self, FragmentError> {
+        let stride = max_message_len
+            .min(ASK_MAX_MESSAGE_LEN)
+            .saturating_sub(FRAGMENT_HEADER_LEN as u8);
+        if stride == 0 || data.len() > MAX_FRAGMENTS as usize * usize::from(stride) {
+            return Err(FragmentError::MessageTooLong);
+        }
+        // An empty message is still one (empty) fragment
+        let count = data.len().div_ceil(usize::from(stride)).max(1) as u8;
+        Ok(Self {
+            to,
+            message_id,
+            data,
+            stride,
+            count,
+            next: 0,
+        })
+    }
+
+    /// Number of fragments the message is split into.
+    pub fn fragment_count(&self) -> u8 {
+        self.count
+    }
+
+    /// Returns `true` once every fragment has been queued.
+    pub fn is_done(&self) -> bool {
+        self.next >= self.count
+    }
+
+    /// Queues as many of the remaining fragments as the transmit queue has room for.
+    ///
+    /// Each fragment gets the next value of the driver's
+    /// [`tx_header_id`](AskDriver::tx_header_id) as its header id.
+    ///
+    /// # Errors
+    /// - [`nb::Error::WouldBlock`] while fragments are left; call again once the
+    ///   transmit queue has drained
+    /// - [`nb::Error::Other`] with any other error returned by [`AskDriver::send_packet()`]
+    pub fn poll<
+        TX,
+        RX,
+        PTT,
+        const TXQ: usize,
+        const RXQ: usize,
+        LC: LineCode,
+        CS: Checksum,
+        FF: FrameFormat,
+        RXEN: OutputPin,
+        TXEN: OutputPin,
+    >(
+        &mut self,
+        driver: &mut AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+    ) -> nb::Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>>
+    where
+        TX: OutputPin,
+        RX: InputPin,
+        PTT: OutputPin,
+    {
+        while self.next < self.count {
+            match self.queue_fragment(driver, self.next) {
+                Ok(()) => self.next += 1,
+                Err(AskError::Busy) => return Err(nb::Error::WouldBlock),
+                Err(e) => return Err(nb::Error::Other(e)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Queues the fragment at `index` again, e.g. one the receiver reported missing.
+    ///
+    /// # Errors
+    /// - [`FragmentError::InvalidIndex`] if `index` is not below [`fragment_count()`](FragmentSender::fragment_count)
+    /// - [`FragmentError::Driver`] with any error returned by [`AskDriver::send_packet()`]
+    pub fn send_fragment<
+        TX,
+        RX,
+        PTT,
+        const TXQ: usize,
+        const RXQ: usize,
+        LC: LineCode,
+        CS: Checksum,
+        FF: FrameFormat,
+        RXEN: OutputPin,
+        TXEN: OutputPin,
+    >(
+        &self,
+        driver: &mut AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+        index: u8,
+    ) -> Result<(), FragmentDriverError<TX, RX, PTT, RXEN, TXEN>>
+    where
+        TX: OutputPin,
+        RX: InputPin,
+        PTT: OutputPin,
+    {
+        if index >= self.count {
+            return Err(FragmentError::InvalidIndex(index));
+        }
+        self.queue_fragment(driver, index)
+            .map_err(FragmentError::Driver)
+    }
+
+    /// Queues the fragment at `index`, which must be below the fragment count.
+    fn queue_fragment<
+        TX,
+        RX,
+        PTT,
+        const TXQ: usize,
+        const RXQ: usize,
+        LC: LineCode,
+        CS: Checksum,
+        FF: FrameFormat,
+        RXEN: OutputPin,
+        TXEN: OutputPin,
+    >(
+        &self,
+        driver: &mut AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+        index: u8,
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>>
+    where
+        TX: OutputPin,
+        RX: InputPin,
+        PTT: OutputPin,
+    {
+        let packet = self
+            .fragment(index, driver.address(), driver.tx_header_id.wrapping_add(1))
+            .ok_or(AskError::MessageTooLong)?;
+        driver.send_packet(&packet)?;
+        driver.tx_header_id = packet.id;
+        Ok(())
+    }
+
+    /// Builds the fragment at `index`, sent by `from` with header id `id`.
+    ///
+    /// Returns `None` if the fragment does not fit in a packet.
+    fn fragment(&self, index: u8, from: u8, id: u8) -> Option<AskPacket> {
+        let mut packet = AskPacket::new(self.to, from);
+        packet.id = id;
+        packet.flags = HeaderFlags::FRAGMENT;
+        let start = usize::from(index) * usize::from(self.stride);
+        let end = (start + usize::from(self.stride)).min(self.data.len());
+        let header = [self.message_id, index, self.count, self.stride];
+        #[cfg(feature = "std")]
+        {
+            packet.payload.extend_from_slice(&header);
+            packet.payload.extend_from_slice(&self.data[start..end]);
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            packet.payload.extend_from_slice(&header).ok()?;
+            packet
+                .payload
+                .extend_from_slice(&self.data[start..end])
+                .ok()?;
+        }
+        Some(packet)
+    }
+}
+
+/// The message a [`Reassembler`] is putting together.
+#[derive(Debug, Clone, Copy)]
+struct Assembly {
+    from: u8,
+    message_id: u8,
+    count: u8,
+    stride: u8,
+    /// Bit `n` is set once fragment `n` has arrived.
+    received: u32,
+    len: usize,
+    started_at: u32,
+}
+
+/// Puts fragments received from an [`AskDriver`] back together.
+///
+/// One message is reassembled at a time. A fragment of another message (a different
+/// sender or message id) drops the incomplete one and starts over.
+#[derive(Debug)]
+pub struct Reassembler<'a> {
+    buf: &'a mut [u8],
+    timeout: u32,
+    current: Option<Assembly>,
+
+    /// Counter of incomplete messages dropped because they timed out or another
+    /// message started.
+    pub dropped: u16,
+}
+
+impl<'a> Reassembler<'a> {
+    /// Reassembles into `buf`, dropping messages that are not complete `timeout`
+    /// `tick()` calls after their first fragment arrived.
+    pub fn new(buf: &'a mut [u8], timeout: u32) -> Self {
+        Self {
+            buf,
+            timeout,
+            current: None,
+            dropped: 0,
+        }
+    }
+
+    /// Adds a received fragment.
+    ///
+    /// # Returns
+    /// The whole message once its last missing fragment has arrived, `None` before.
+    ///
+    /// # Errors
+    /// - [`FragmentError::NotAFragment`] if `packet` lacks the [`ASK_FLAGS_FRAGMENT`](crate::consts::ASK_FLAGS_FRAGMENT) flag
+    /// - [`FragmentError::Malformed`] if the sub-header or data length are inconsistent
+    /// - [`FragmentError::BufferTooSmall`] if the fragment lies beyond the end of the buffer
+    pub fn push(&mut self, packet: &AskPacket) -> Result<Option<&[u8]>, FragmentError> {
+        if !packet.flags.is_fragment() {
+            return Err(FragmentError::NotAFragment);
+        }
+        let [message_id, index, count, stride, ref data @ ..] = *packet.payload.as_slice() else {
+            return Err(FragmentError::Malformed);
+        };
+        let last = index + 1 == count;
+        if count > MAX_FRAGMENTS
+            || index >= count
+            || (!last && data.len() != usize::from(stride))
+            || data.len() > usize::from(stride)
+        {
+            return Err(FragmentError::Malformed);
+        }
+        let start = usize::from(index) * usize::from(stride);
+        let end = start + data.len();
+        if end > self.buf.len() {
+            return Err(FragmentError::BufferTooSmall);
+        }
+
+        let _ = self.check_timeout(packet.received_at_tick);
+        let mut assembly = match self.current {
+            Some(a) if a.from == packet.from && a.message_id == message_id => {
+                if a.count != count || a.stride != stride {
+                    return Err(FragmentError::Malformed);
+                }
+                a
+            }
+            other => {
+                if other.is_some() {
+                    self.dropped = self.dropped.wrapping_add(1);
+                }
+                Assembly {
+                    from: packet.from,
+                    message_id,
+                    count,
+                    stride,
+                    received: 0,
+                    len: 0,
+                    started_at: packet.received_at_tick,
+                }
+            }
+        };
+        self.buf[start..end].copy_from_slice(data);
+        assembly.received |= 1 << index;
+        if last {
+            assembly.len = end;
+        }
+
+        if assembly.received == Self::all_received(count) {
+            self.current = None;
+            return Ok(Some(&self.buf[..assembly.len]));
+        }
+        self.current = Some(assembly);
+        Ok(None)
+    }
+
+    /// Drops the incomplete message if its timeout has passed at tick `now`.
+    ///
+    /// Returns `true` if a message was dropped.
+    pub fn check_timeout(&mut self, now: u32) -> bool {
+        match self.current {
+            Some(a) if now.wrapping_sub(a.started_at) >= self.timeout => {
+                self.current = None;
+                self.dropped = self.dropped.wrapping_add(1);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` while a message is partly received.
+    pub fn is_assembling(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Iterates over the indices of the fragments of the current message that have
+    /// not arrived yet. Empty when no message is being reassembled.
+    pub fn missing(&self) -> impl Iterator<Item = u8> + '_ {
+        let (count, received) = self.current.map_or((0, 0), |a| (a.count, a.received));
+        (0..count).filter(move |index| received & (1 << index) == 0)
+    }
+
+    /// Mask with the lowest `count` bits set.
+    fn all_received(count: u8) -> u32 {
+        u32::MAX >> (32 - u32::from(count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AskConfig;
+    use crate::crc::Crc32;
+    use crate::encoding::FourBSixB;
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+
+    /// The fragments `sender` would queue, built without a driver.
+    fn fragments<'a>(from: u8, sender: &'a FragmentSender) -> impl Iterator<Item = AskPacket> + 'a {
+        (0..sender.count).map(move |index| sender.fragment(index, from, index).unwrap())
+    }
+
+    fn blob(len: usize) -> [u8; 300] {
+        core::array::from_fn(|i| if i < len { i as u8 } else { 0 })
+    }
+
+    #[test]
+    fn test_sender_queues_fragments_on_driver() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, None, AskConfig::default()).unwrap();
+        let data = blob(150);
+        let mut sender =
+            FragmentSender::new(0x02, 7, &data[..150], driver.max_message_len()).unwrap();
+        assert_eq!(sender.fragment_count(), 3);
+        assert_eq!(sender.poll(&mut driver), Ok(()));
+        assert!(sender.is_done());
+        assert_eq!(driver.tx_header_id, 3);
+
+        let last = driver.sent_packet();
+        assert_eq!(
+            (last.to, last.id, last.flags),
+            (0x02, 3, HeaderFlags::FRAGMENT)
+        );
+        assert_eq!(&last.payload[..FRAGMENT_HEADER_LEN], &[7, 2, 3, 56]);
+        assert_eq!(&last.payload[FRAGMENT_HEADER_LEN..], &data[2 * 56..150]);
+        assert_eq!(
+            sender.send_fragment(&mut driver, 3),
+            Err(FragmentError::InvalidIndex(3))
+        );
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_fragments_fit_a_crc32_driver() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let mut driver: AskDriver<PinMock, PinMock, PinMock, 8, 8, FourBSixB, Crc32> =
+            AskDriver::new(tx, rx, None, AskConfig::default()).unwrap();
+        let data = blob(120);
+        let mut sender =
+            FragmentSender::new(0x02, 7, &data[..120], driver.max_message_len()).unwrap();
+        assert_eq!(sender.fragment_count(), 3);
+        assert_eq!(sender.poll(&mut driver), Ok(()));
+        assert_eq!(
+            &driver.sent_packet().payload[..FRAGMENT_HEADER_LEN],
+            &[7, 2, 3, 54]
+        );
+
+        // The receiver places fragments by the stride they carry
+        let mut buf = [0u8; 128];
+        let mut reassembler = Reassembler::new(&mut buf, 1000);
+        let mut complete = None;
+        for index in 0..3 {
+            let packet = sender.fragment(index, 0x01, index).unwrap();
+            if let Some(message) = reassembler.push(&packet).unwrap() {
+                assert_eq!(message, &data[..120]);
+                complete = Some(index);
+            }
+        }
+        assert_eq!(complete, Some(2));
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_sender_caps_oversized_max_message_len() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, None, AskConfig::default()).unwrap();
+        let data = blob(200);
+        let mut sender = FragmentSender::new(0x02, 1, &data[..200], 200).unwrap();
+        assert_eq!(sender.fragment_count(), 4);
+        assert_eq!(sender.poll(&mut driver), Ok(()));
+        assert_eq!(
+            &driver.sent_packet().payload[..FRAGMENT_HEADER_LEN],
+            &[1, 3, 4, 56]
+        );
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_reassembles_out_of_order_and_reports_missing() {
+        let data = blob(200);
+        let sender = FragmentSender::new(0x02, 1, &data[..200], ASK_MAX_MESSAGE_LEN).unwrap();
+        let packets: [AskPacket; 4] = {
+            let mut iter = fragments(0x01, &sender);
+            core::array::from_fn(|_| iter.next().unwrap())
+        };
+        let mut buf = [0u8; 256];
+        let mut reassembler = Reassembler::new(&mut buf, 1000);
+
+        assert_eq!(reassembler.push(&packets[3]), Ok(None));
+        assert_eq!(reassembler.push(&packets[1]), Ok(None));
+        assert!(reassembler.is_assembling());
+        assert!(reassembler.missing().eq([0, 2]));
+        assert_eq!(reassembler.push(&packets[0]), Ok(None));
+        assert_eq!(reassembler.push(&packets[2]), Ok(Some(&data[..200])));
+        assert!(!reassembler.is_assembling());
+        assert_eq!(reassembler.missing().count(), 0);
+    }
+
+    #[test]
+    fn test_reassembler_rejects_bad_fragments() {
+        let data = blob(300);
+        let mut buf = [0u8; 100];
+        let mut reassembler = Reassembler::new(&mut buf, 1000);
+
+        assert_eq!(
+            reassembler.push(&AskPacket::new(0x02, 0x01)),
+            Err(FragmentError::NotAFragment)
+        );
+        let sender = FragmentSender::new(0x02, 1, &data, ASK_MAX_MESSAGE_LEN).unwrap();
+        let mut packets = fragments(0x01, &sender);
+        let mut first = packets.next().unwrap();
+        // Fragment 2 lies beyond the 100 byte buffer
+        assert_eq!(
+            reassembler.push(&packets.nth(1).unwrap()),
+            Err(FragmentError::BufferTooSmall)
+        );
+        let _ = first.payload.pop();
+        assert_eq!(reassembler.push(&first), Err(FragmentError::Malformed));
+
+        assert_eq!(
+            FragmentSender::new(0x02, 1, &[0; MAX_FRAGMENTED_LEN + 1], ASK_MAX_MESSAGE_LEN).err(),
+            Some(FragmentError::MessageTooLong)
+        );
+    }
+
+    #[test]
+    fn test_incomplete_message_times_out() {
+        let data = blob(100);
+        let sender = FragmentSender::new(0x02, 1, &data[..100], ASK_MAX_MESSAGE_LEN).unwrap();
+        let mut packets = fragments(0x01, &sender);
+        let mut buf = [0u8; 128];
+        let mut reassembler = Reassembler::new(&mut buf, 50);
+
+        let mut first = packets.next().unwrap();
+        first.received_at_tick = 10;
+        assert_eq!(reassembler.push(&first), Ok(None));
+        assert!(!reassembler.check_timeout(59));
+        assert!(reassembler.check_timeout(60));
+        assert_eq!(reassembler.dropped, 1);
+
+        // The second fragment alone starts a new, incomplete message
+        let mut second = packets.next().unwrap();
+        second.received_at_tick = 70;
+        assert_eq!(reassembler.push(&second), Ok(None));
+        assert!(reassembler.missing().eq([0]));
+    }
+}
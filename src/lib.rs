@@ -30,6 +30,7 @@
 //! ## Usage
 //!
 //! ```rust
+//! use ask433::config::AskConfig;
 //! use ask433::driver::AskDriver;
 //! # use embedded_hal_mock::eh1::digital::{Mock as Pin, Transaction as PinTransaction, State as PinState};
 //! # use embedded_hal::digital::OutputPin;
@@ -37,10 +38,10 @@
 //!     // ...
 //!     # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
 //!     # let rx_pin = Pin::new(&[]);
-//!     let mut driver: AskDriver<Pin, Pin, Pin> = AskDriver::new(tx_pin, rx_pin, None, 8, None, None);
-//!                                                        // ^ this is the number of interrupts per bit
+//!     let mut driver: AskDriver<Pin, Pin, Pin> =
+//!         AskDriver::new(tx_pin, rx_pin, None, AskConfig::default()).unwrap();
 //!     loop {
-//!         driver.tick(); // Call at ~62.5 µs intervals
+//!         driver.tick().unwrap(); // Call at ~62.5 µs intervals
 //!         # break; // For testing purposes
 //!     }
 //!     # driver.tx.done(); // Mark the transmission complete
@@ -51,6 +52,7 @@
 //! Or, use `run_tick_loop()` with a `DelayUs` implementation:
 //!
 //! ```rust
+//! use ask433::config::AskConfig;
 //! use ask433::driver::AskDriver;
 //! #[cfg(feature = "delay-loop")]
 //! use ask433::timer::run_ask_tick_loop;
@@ -62,11 +64,12 @@
 //!     // ...
 //!     # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
 //!     # let rx_pin = Pin::new(&[]);
-//!     let mut driver: AskDriver<Pin, Pin, Pin> = AskDriver::new(tx_pin, rx_pin, None, 8, None, None);
-//! # #[cfg(feature = "delay-loop")]                                                      // ^ this is the number of interrupts per bit
+//!     let mut driver: AskDriver<Pin, Pin, Pin> =
+//!         AskDriver::new(tx_pin, rx_pin, None, AskConfig::default()).unwrap();
+//! # #[cfg(feature = "delay-loop")]
 //!     # let mut delay = Delay::new();
 //! # #[cfg(feature = "delay-loop")]
-//!     run_ask_tick_loop(&mut driver, &mut delay, 63);
+//!     run_ask_tick_loop(&mut driver, &mut delay, 63).unwrap();
 //!     # driver.tx.done();
 //!     # driver.rx.done();
 //! }
@@ -79,7 +82,8 @@
 //!
 //! ## Integration Notes
 //!
-//! - Transmit and receive timing are based on ~2 kbps bit rate (~62.5 µs per tick)
+//! - Transmit and receive timing default to a 2 kbps bit rate (~62.5 µs per tick); 500, 1000,
+//!   4000 and 9600 bps are available through [`config::BitRate`]
 //! - Timing precision is critical; hardware timer configuration is recommended for reliability
 //! - Only one driver instance should be active at a time in interrupt-driven mode
 //!
@@ -119,11 +123,25 @@ pub use critical_section;
 #[cfg(all(feature = "timer-isr", not(feature = "std")))]
 pub use heapless;
 
+pub use nb;
+
+pub mod config;
 pub mod consts;
-pub(crate) mod crc;
+pub mod crc;
 pub mod driver;
 pub mod encoding;
+pub mod error;
+pub mod fec;
+pub mod flags;
+pub mod format;
+pub mod fragment;
+pub mod mesh;
+pub mod packet;
 pub mod pll;
+pub mod rcswitch;
+pub mod reliable;
+pub mod router;
+pub mod stats;
 pub mod timer;
 
 #[cfg(test)]
@@ -131,6 +149,7 @@ mod tests {
 
     #[cfg(all(test, feature = "std"))]
     mod lib {
+        use crate::config::AskConfig;
         use crate::driver::AskDriver;
         use core::fmt;
         use critical_section::RawRestoreState;
@@ -246,50 +265,50 @@ mod tests {
         fn test_simulated_radio_send_and_receive() {
             let pin = Pin::new();
             let mut driver: AskDriver<&Pin, &Pin, &Pin> =
-                AskDriver::new(&pin, &pin, None, 8, None, None);
+                AskDriver::new(&pin, &pin, None, AskConfig::default()).unwrap();
             let mut message = Vec::new();
             message.extend_from_slice(b"Hello, world!");
-            let okay = driver.send(message.clone());
-            assert!(okay, "Failed to send data");
+            driver.send(message.clone()).expect("Failed to send data");
 
             // 6 bits per byte * 8 ticks per bit + 1 bit just for good measure
             let ticks = (driver.tx_buf.len() * 48) + 8;
 
             // Simulate reception by pushing the sent bits into the pin state
             for _ in 0..ticks {
-                driver.tick();
+                driver.tick().unwrap();
                 std::thread::sleep(std::time::Duration::from_micros(63));
             }
 
             assert_eq!(
-                driver.tx_good, 1,
+                driver.stats().tx_good,
+                1,
                 "Transmit buffer should be empty after sending"
             );
 
             // Put the driver in a state to receive
-            driver.set_mode_rx();
+            driver.set_mode_rx().unwrap();
 
             // Simulate halft the reception of the sent bits
             for _ in 0..(ticks / 2) {
-                driver.tick();
+                driver.tick().unwrap();
                 std::thread::sleep(std::time::Duration::from_micros(63));
             }
 
             assert!(driver.pll.active, "PLL should be active during sending");
 
             for _ in 0..ticks {
-                driver.tick();
+                driver.tick().unwrap();
                 std::thread::sleep(std::time::Duration::from_micros(63));
             }
 
-            let _ = driver.availabile();
+            let _ = driver.availabile().unwrap();
 
             // Check if the driver received the message correctly
-            let received = driver.receive();
+            let received = driver.receive().unwrap();
             assert!(received.is_some(), "No data received");
             let received_data = received.unwrap();
             assert_eq!(
-                received_data, message,
+                received_data.payload, message,
                 "Received data does not match sent data"
             );
         }
@@ -297,6 +316,7 @@ mod tests {
 
     #[cfg(all(test, feature = "timer-isr"))]
     mod macros {
+        use crate::config::AskConfig;
         use embedded_hal_mock::eh1::digital::{
             Mock as PinMock, State as PinState, Transaction as PinTransaction,
         };
@@ -309,7 +329,7 @@ mod tests {
             let rx = PinMock::new(&[]);
             let ptt = PinMock::new(&[]);
 
-            setup_ask_driver!(tx, rx, Some(ptt), 8, None, None);
+            setup_ask_driver!(tx, rx, Some(ptt)).unwrap();
 
             critical_section::with(|cs| {
                 assert!(ASK_DRIVER.borrow(cs).borrow().is_some());
@@ -331,14 +351,14 @@ mod tests {
             let rx = PinMock::new(&[PinTransaction::get(PinState::High)]);
             let ptt = PinMock::new(&[PinTransaction::set(PinState::Low)]);
 
-            setup_ask_driver!(tx, rx, Some(ptt), 8, None, None);
+            setup_ask_driver!(tx, rx, Some(ptt)).unwrap();
 
-            tick_ask_timer!();
+            tick_ask_timer!().unwrap();
 
-            let message = receive_from_ask!();
+            let message = receive_from_ask!().unwrap();
             assert!(message.is_none());
 
-            tick_ask_timer!();
+            tick_ask_timer!().unwrap();
 
             critical_section::with(|cs| {
                 let mut driver = ASK_DRIVER.take(cs).unwrap();
@@ -351,17 +371,24 @@ mod tests {
         #[test]
         fn test_send_macro_formats_payload() {
             use crate::{init_ask_driver, send_from_ask, setup_ask_driver, tick_ask_timer};
-            init_ask_driver!(PinMock, PinMock, PinMock);
+            init_ask_driver!(PinMock, PinMock, PinMock, 1);
             let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
             let rx = PinMock::new(&[]);
             let ptt = PinMock::new(&[PinTransaction::set(PinState::High)]);
 
-            setup_ask_driver!(tx, rx, Some(ptt), 8, Some(false), Some(false));
+            setup_ask_driver!(
+                tx,
+                rx,
+                Some(ptt),
+                AskConfig::builder().ticks_per_bit(8).build().unwrap()
+            )
+            .unwrap();
 
-            let sent = send_from_ask![0x42, 0x43];
-            assert!(sent);
+            send_from_ask![0x42, 0x43].unwrap();
+            send_from_ask!("Hi").unwrap();
+            assert_eq!(send_from_ask![0x42, 0x43], Err(nb::Error::WouldBlock));
 
-            tick_ask_timer!();
+            tick_ask_timer!().unwrap();
 
             critical_section::with(|cs| {
                 let mut driver = ASK_DRIVER.take(cs).unwrap();
@@ -382,9 +409,15 @@ mod tests {
             let rx = PinMock::new(&[]);
             let ptt = PinMock::new(&[PinTransaction::set(PinState::Low)]);
 
-            setup_ask_driver!(tx, rx, Some(ptt), 8, Some(false), Some(false));
+            setup_ask_driver!(
+                tx,
+                rx,
+                Some(ptt),
+                AskConfig::builder().ticks_per_bit(8).build().unwrap()
+            )
+            .unwrap();
 
-            let result = receive_from_ask!();
+            let result = receive_from_ask!().unwrap();
             assert!(result.is_none());
 
             critical_section::with(|cs| {
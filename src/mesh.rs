@@ -0,0 +1,734 @@
+//! On-demand route discovery on top of [`Router`].
+//!
+//! [`Mesh`] implements the protocol of RadioHead's `RHMesh`, so it shares a network
+//! with Arduino nodes using that class. Instead of a static routing table, routes are
+//! found when they are needed:
+//!
+//! - Sending to a node without a known route broadcasts a *route discovery request*.
+//!   Every node that hears it notes the route back to the requester and re-broadcasts
+//!   it with its own address appended.
+//! - The node being looked for unicasts a *route discovery response*, carrying the
+//!   list of nodes the request passed through, back to the requester. Nodes relaying
+//!   the response learn the routes to the nodes behind them.
+//! - When a node can't reach the next hop, it deletes the route and sends a
+//!   *route failure* to the original sender, which flushes its stale route as well.
+//!
+//! The first byte after the routing header tells the message types apart:
+//!
+//! | Type | Constant | Rest of the payload |
+//! |------|----------|---------------------|
+//! | 0 | [`MESH_MESSAGE_TYPE_APPLICATION`] | User data |
+//! | 1 | [`MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_REQUEST`] | `1`, wanted address, nodes passed |
+//! | 2 | [`MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_RESPONSE`] | Same as the request |
+//! | 3 | [`MESH_MESSAGE_TYPE_ROUTE_FAILURE`] | Unreachable address |
+//!
+//! ## Example
+//!
+//! ```rust
+//! # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+//! use ask433::config::AskConfig;
+//! use ask433::driver::AskDriver;
+//! use ask433::mesh::{Mesh, MeshEvent};
+//!
+//! # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
+//! # let rx_pin = Pin::new(&[]);
+//! let config = AskConfig::builder().address(0x01).build().unwrap();
+//! let driver: AskDriver<Pin, Pin, Pin> = AskDriver::new(tx_pin, rx_pin, None, config).unwrap();
+//! let mut mesh = Mesh::new(driver);
+//!
+//! // No route to 0x03 yet: this broadcasts a route discovery request first
+//! mesh.send_to(0x03, b"21.5C", 0).unwrap();
+//!
+//! match mesh.poll() {
+//!     Ok(Some(MeshEvent::Received(message))) => { /* message.source sent message.payload */ }
+//!     Ok(Some(MeshEvent::NoRoute { dest })) => { /* nobody answered for dest */ }
+//!     _ => {}
+//! }
+//! # let mut driver = mesh.into_inner().into_inner().into_inner();
+//! # driver.tx.done();
+//! # driver.rx.done();
+//! ```
+
+#[cfg(not(feature = "std"))]
+use crate::consts::ASK_MAX_MESSAGE_LEN_USIZE;
+use crate::consts::{ASK_MAX_MESSAGE_LEN, ASK_RX_QUEUE_LEN, ASK_TX_QUEUE_LEN, BROADCAST_ADDRESS};
+use crate::crc::{Checksum, Crc16Ccitt};
+use crate::driver::AskDriver;
+use crate::encoding::{FourBSixB, LineCode};
+use crate::error::{AskDriverError, AskError};
+use crate::format::{FrameFormat, RadioHead};
+use crate::router::{
+    ROUTER_HEADER_LEN, RouteState, RoutedMessage, Router, RouterDriverError, RouterError,
+    RouterEvent, RoutingTable,
+};
+use embedded_hal::digital::{InputPin, OutputPin};
+
+#[cfg(not(feature = "std"))]
+use heapless::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// Message type of user data.
+pub const MESH_MESSAGE_TYPE_APPLICATION: u8 = 0;
+
+/// Message type of a broadcast looking for a route.
+pub const MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_REQUEST: u8 = 1;
+
+/// Message type of the answer to a route discovery request.
+pub const MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_RESPONSE: u8 = 2;
+
+/// Message type telling the sender that a route broke.
+pub const MESH_MESSAGE_TYPE_ROUTE_FAILURE: u8 = 3;
+
+/// Maximum length of the user data in a mesh message sent in frames of
+/// [`ASK_MAX_MESSAGE_LEN`] bytes; see [`Mesh::max_message_len()`] for a given driver.
+pub const MESH_MAX_MESSAGE_LEN: usize = ASK_MAX_MESSAGE_LEN as usize - ROUTER_HEADER_LEN - 1;
+
+/// Default time to wait for a route discovery response, in milliseconds
+/// (RadioHead's `RH_MESH_ARP_TIMEOUT`).
+pub const DEFAULT_ARP_TIMEOUT_MS: u32 = 4000;
+
+/// Something that happened on the mesh, returned by [`Mesh::poll()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MeshEvent {
+    /// User data addressed to this node (or broadcast) arrived.
+    ///
+    /// The payload holds the user data only, without the message type.
+    Received(RoutedMessage),
+    /// The next hop acknowledged a message sent with [`Mesh::send_to()`].
+    Delivered {
+        /// Final destination of the message.
+        dest: u8,
+    },
+    /// The next hop did not acknowledge a message sent with [`Mesh::send_to()`].
+    ///
+    /// The route has been deleted; the next message to `dest` looks for a new one.
+    Failed {
+        /// Final destination of the message.
+        dest: u8,
+    },
+    /// No node answered the route discovery for a message sent with
+    /// [`Mesh::send_to()`]; the message was dropped.
+    NoRoute {
+        /// Final destination of the message.
+        dest: u8,
+    },
+}
+
+/// A message waiting for its route to be discovered.
+#[derive(Debug)]
+struct Discovery {
+    dest: u8,
+    flags: u8,
+    #[cfg(not(feature = "std"))]
+    payload: Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE>,
+    #[cfg(feature = "std")]
+    payload: Vec<u8>,
+    started_at: u32,
+}
+
+/// RadioHead `RHMesh` compatible route discovery layer around a [`Router`].
+///
+/// Call [`tick()`](Mesh::tick) at the driver's tick rate and [`poll()`](Mesh::poll)
+/// from the main loop; route discovery, like forwarding, only makes progress while
+/// `poll()` runs.
+///
+/// One message can wait for its route at a time.
+#[derive(Debug)]
+pub struct Mesh<
+    TX,
+    RX,
+    PTT,
+    const TXQ: usize = ASK_TX_QUEUE_LEN,
+    const RXQ: usize = ASK_RX_QUEUE_LEN,
+    LC = FourBSixB,
+    CS = Crc16Ccitt,
+    FF = RadioHead,
+    RXEN = PTT,
+    TXEN = PTT,
+> where
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+{
+    router: Router<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+    arp_timeout: u32,
+    discovery: Option<Discovery>,
+    /// Destination and end-to-end id of the user message waiting for a hop-level ACK.
+    in_flight: Option<(u8, u8)>,
+}
+
+impl<TX, RX, PTT, const TXQ: usize, const RXQ: usize, LC, CS, FF, RXEN, TXEN>
+    Mesh<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>
+where
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+{
+    /// Wraps `driver` in a [`Router`] with an empty routing table and a route discovery
+    /// timeout of [`DEFAULT_ARP_TIMEOUT_MS`].
+    pub fn new(driver: AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>) -> Self {
+        let arp_timeout = driver.tick_hz() / 1000 * DEFAULT_ARP_TIMEOUT_MS;
+        Self {
+            router: Router::new(driver),
+            arp_timeout,
+            discovery: None,
+            in_flight: None,
+        }
+    }
+
+    /// Sets how long to wait for a route discovery response, in `tick()` calls.
+    pub fn set_arp_timeout(&mut self, ticks: u32) {
+        self.arp_timeout = ticks;
+    }
+
+    /// Returns `true` while a message is waiting for its route to be discovered.
+    pub fn is_discovering(&self) -> bool {
+        self.discovery.is_some()
+    }
+
+    /// Returns the routing table.
+    pub fn routing_table(&self) -> &RoutingTable {
+        self.router.routing_table()
+    }
+
+    /// Returns the routing table mutably, e.g. to add static routes.
+    pub fn routing_table_mut(&mut self) -> &mut RoutingTable {
+        self.router.routing_table_mut()
+    }
+
+    /// Returns the wrapped [`Router`], e.g. to change its maximum hop count.
+    pub fn router_mut(&mut self) -> &mut Router<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        &mut self.router
+    }
+
+    /// Returns the driver.
+    pub fn driver(&self) -> &AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        self.router.driver()
+    }
+
+    /// Returns the driver mutably.
+    pub fn driver_mut(&mut self) -> &mut AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        self.router.driver_mut()
+    }
+
+    /// Maximum length of the user data in a mesh message with the driver's current
+    /// [`max_message_len()`](AskDriver::max_message_len).
+    pub fn max_message_len(&self) -> usize {
+        usize::from(self.driver().max_message_len()).saturating_sub(ROUTER_HEADER_LEN + 1)
+    }
+
+    /// Unwraps the [`Router`].
+    pub fn into_inner(self) -> Router<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        self.router
+    }
+
+    /// Advances the driver by one tick; see [`AskDriver::tick()`].
+    ///
+    /// # Errors
+    /// Any error returned by [`AskDriver::tick()`].
+    pub fn tick(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        self.router.tick()
+    }
+
+    /// Sends `payload` to `dest`, looking for a route first if none is known.
+    ///
+    /// Messages to [`BROADCAST_ADDRESS`] go to all neighbours and are not forwarded.
+    /// The outcome of unicast messages is reported by [`poll()`](Mesh::poll) as
+    /// [`MeshEvent::Delivered`], [`MeshEvent::Failed`] or [`MeshEvent::NoRoute`].
+    ///
+    /// # Errors
+    /// - [`RouterError::Driver`] with [`AskError::MessageTooLong`] if `payload` is
+    ///   longer than [`max_message_len()`](Mesh::max_message_len)
+    /// - [`RouterError::Driver`] with [`AskError::Busy`] while a previous message is
+    ///   waiting for its route
+    /// - Any error returned by [`Router::send_to()`]
+    pub fn send_to(
+        &mut self,
+        dest: u8,
+        payload: &[u8],
+        flags: u8,
+    ) -> Result<(), RouterDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if payload.len() > self.max_message_len() {
+            return Err(AskError::MessageTooLong.into());
+        }
+        if self.discovery.is_some() {
+            return Err(AskError::Busy.into());
+        }
+        if dest == BROADCAST_ADDRESS || self.has_route_to(dest) {
+            return self.send_application(dest, payload, flags);
+        }
+
+        let request = [MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_REQUEST, 1, dest];
+        let _ = self.router.send_to(BROADCAST_ADDRESS, &request, 0)?;
+        self.discovery = Some(Discovery {
+            dest,
+            flags,
+            #[cfg(feature = "std")]
+            payload: Vec::from(payload),
+            #[cfg(not(feature = "std"))]
+            payload: Vec::from_slice(payload).map_err(|_| AskError::MessageTooLong)?,
+            started_at: self.driver().ticks(),
+        });
+        Ok(())
+    }
+
+    /// Receives, forwards and answers messages, discovers routes and reports the outcome
+    /// of sent messages.
+    ///
+    /// Call this regularly from the main loop. Each call returns at most one event;
+    /// keep calling while it returns `Some`.
+    ///
+    /// # Errors
+    /// Any pin error of the driver.
+    pub fn poll(&mut self) -> Result<Option<MeshEvent>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let this_address = self.driver().address();
+        while let Some(event) = self
+            .router
+            .poll_with(|message, table| peek_at_message(this_address, message, table))?
+        {
+            let event = match event {
+                RouterEvent::Received(message) => self.handle_received(message)?,
+                RouterEvent::Delivered { dest, id } => self
+                    .take_in_flight(dest, id)
+                    .then_some(MeshEvent::Delivered { dest }),
+                RouterEvent::Failed { dest, id } => {
+                    let _ = self.routing_table_mut().delete_route_to(dest);
+                    self.take_in_flight(dest, id)
+                        .then_some(MeshEvent::Failed { dest })
+                }
+                RouterEvent::ForwardFailed {
+                    dest,
+                    source,
+                    last_hop,
+                } => {
+                    self.report_route_failure(dest, source, last_hop)?;
+                    None
+                }
+            };
+            if event.is_some() {
+                return Ok(event);
+            }
+        }
+        self.poll_discovery()
+    }
+
+    /// Handles a message addressed to this node or broadcast.
+    fn handle_received(
+        &mut self,
+        mut message: RoutedMessage,
+    ) -> Result<Option<MeshEvent>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let this_address = self.driver().address();
+        match *message.payload.as_slice() {
+            [MESH_MESSAGE_TYPE_APPLICATION, ..] => {
+                let _ = message.payload.remove(0);
+                return Ok(Some(MeshEvent::Received(message)));
+            }
+            [
+                MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_REQUEST,
+                1,
+                dest,
+                ref route @ ..,
+            ] if message.dest == BROADCAST_ADDRESS => {
+                // Ignore our own requests and those that already passed through us
+                if message.source == this_address || route.contains(&this_address) {
+                    return Ok(None);
+                }
+                let table = self.router.routing_table_mut();
+                table.add_route_to(message.source, message.last_hop, RouteState::Valid);
+                for &node in route {
+                    table.add_route_to(node, message.last_hop, RouteState::Valid);
+                }
+
+                let mut buf = [0; ASK_MAX_MESSAGE_LEN as usize];
+                let len = message.payload.len();
+                buf[..len].copy_from_slice(&message.payload);
+                if dest == this_address {
+                    // Unicast the whole route back, along the route we just learned
+                    buf[0] = MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_RESPONSE;
+                    self.send_control(message.source, this_address, &buf[..len])?;
+                } else if route.len() < usize::from(self.router.max_hops()) && len < buf.len() {
+                    // Pass the request on with ourselves added, on behalf of the requester
+                    buf[len] = this_address;
+                    self.send_control(BROADCAST_ADDRESS, message.source, &buf[..=len])?;
+                }
+            }
+            // Responses and failures were already taken care of while peeking
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    /// Tells the sender of a message that could not be passed on that its route broke.
+    fn report_route_failure(
+        &mut self,
+        dest: u8,
+        source: u8,
+        last_hop: u8,
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let this_address = self.driver().address();
+        let table = self.router.routing_table_mut();
+        let _ = table.delete_route_to(dest);
+        if source == this_address {
+            return Ok(());
+        }
+        table.add_route_to(source, last_hop, RouteState::Valid);
+        self.send_control(
+            source,
+            this_address,
+            &[MESH_MESSAGE_TYPE_ROUTE_FAILURE, dest],
+        )
+    }
+
+    /// Sends the waiting message once its route is known, or gives up after the timeout.
+    fn poll_discovery(
+        &mut self,
+    ) -> Result<Option<MeshEvent>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let Some(discovery) = self.discovery.take() else {
+            return Ok(None);
+        };
+        if self.has_route_to(discovery.dest) {
+            match self.send_application(discovery.dest, &discovery.payload, discovery.flags) {
+                Ok(()) => {}
+                // The link is busy, try again on the next poll
+                Err(RouterError::Driver(AskError::Busy)) => self.discovery = Some(discovery),
+                // The route was dropped in the meantime
+                Err(RouterError::NoRoute) => {
+                    return Ok(Some(MeshEvent::NoRoute {
+                        dest: discovery.dest,
+                    }));
+                }
+                Err(RouterError::Driver(e)) => return Err(e),
+            }
+            return Ok(None);
+        }
+        if self.driver().ticks().wrapping_sub(discovery.started_at) >= self.arp_timeout {
+            return Ok(Some(MeshEvent::NoRoute {
+                dest: discovery.dest,
+            }));
+        }
+        self.discovery = Some(discovery);
+        Ok(None)
+    }
+
+    fn send_application(
+        &mut self,
+        dest: u8,
+        payload: &[u8],
+        flags: u8,
+    ) -> Result<(), RouterDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let mut buf = [0; ASK_MAX_MESSAGE_LEN as usize];
+        buf[0] = MESH_MESSAGE_TYPE_APPLICATION;
+        buf[1..=payload.len()].copy_from_slice(payload);
+        let id = self.router.send_to(dest, &buf[..=payload.len()], flags)?;
+        if dest != BROADCAST_ADDRESS {
+            self.in_flight = Some((dest, id));
+        }
+        Ok(())
+    }
+
+    /// Sends a route discovery or failure message.
+    ///
+    /// Like RadioHead, a control message that cannot be sent right now is dropped;
+    /// the requester's timeout takes care of it.
+    fn send_control(
+        &mut self,
+        dest: u8,
+        source: u8,
+        payload: &[u8],
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        match self.router.send_from_source(dest, source, payload, 0) {
+            Ok(_)
+            | Err(
+                RouterError::NoRoute
+                | RouterError::Driver(AskError::Busy | AskError::MessageTooLong),
+            ) => Ok(()),
+            Err(RouterError::Driver(e)) => Err(e),
+        }
+    }
+
+    /// Clears the user message waiting for a hop-level ACK if it is `dest`/`id`.
+    fn take_in_flight(&mut self, dest: u8, id: u8) -> bool {
+        let matches = self.in_flight == Some((dest, id));
+        if matches {
+            self.in_flight = None;
+        }
+        matches
+    }
+
+    fn has_route_to(&self, dest: u8) -> bool {
+        self.routing_table()
+            .get_route_to(dest)
+            .is_some_and(|route| route.state == RouteState::Valid)
+    }
+}
+
+/// Learns from route discovery responses and failures, including those passing through.
+fn peek_at_message(this_address: u8, message: &RoutedMessage, table: &mut RoutingTable) {
+    match *message.payload.as_slice() {
+        [
+            MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_RESPONSE,
+            1,
+            dest,
+            ref route @ ..,
+        ] => {
+            // The response came from the direction of the destination and of every
+            // node after us in the list
+            table.add_route_to(dest, message.last_hop, RouteState::Valid);
+            if let Some(index) = route.iter().position(|&node| node == this_address) {
+                for &node in &route[index + 1..] {
+                    table.add_route_to(node, message.last_hop, RouteState::Valid);
+                }
+            }
+        }
+        [MESH_MESSAGE_TYPE_ROUTE_FAILURE, dest, ..] => {
+            let _ = table.delete_route_to(dest);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AskConfig;
+    use crate::driver::test_packet;
+    use crate::flags::HeaderFlags;
+    use crate::packet::AskPacket;
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+
+    type TestMesh = Mesh<PinMock, PinMock, PinMock, 4, 4>;
+
+    fn mesh(address: u8, tx_lows: usize, rx_lows: usize) -> TestMesh {
+        let tx: [PinTransaction; 8] = core::array::from_fn(|_| PinTransaction::set(PinState::Low));
+        let rx: [PinTransaction; 8] = core::array::from_fn(|_| PinTransaction::get(PinState::Low));
+        let config = AskConfig::builder().address(address).build().unwrap();
+        let driver = AskDriver::new(
+            PinMock::new(&tx[..tx_lows]),
+            PinMock::new(&rx[..rx_lows]),
+            None,
+            config,
+        )
+        .unwrap();
+        Mesh::new(driver)
+    }
+
+    fn next_hop(mesh: &TestMesh, dest: u8) -> Option<u8> {
+        mesh.routing_table()
+            .get_route_to(dest)
+            .map(|route| route.next_hop)
+    }
+
+    #[test]
+    fn test_request_is_rebroadcast_once() {
+        let mut mesh = mesh(0x02, 4, 0);
+        mesh.driver_mut().load_rx_packet(&test_packet(
+            BROADCAST_ADDRESS,
+            0x01,
+            9,
+            HeaderFlags::NONE,
+            &[
+                &[BROADCAST_ADDRESS, 0x01, 0, 3, 0],
+                &[MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_REQUEST, 1, 0x05],
+            ],
+        ));
+        assert_eq!(mesh.poll(), Ok(None));
+        assert_eq!(next_hop(&mesh, 0x01), Some(0x01));
+        let sent = mesh.driver().sent_packet();
+        assert_eq!((sent.to, sent.from), (BROADCAST_ADDRESS, 0x02));
+        assert_eq!(
+            sent.payload.as_slice(),
+            &[BROADCAST_ADDRESS, 0x01, 0, 1, 0, 1, 1, 0x05, 0x02]
+        );
+
+        // A neighbour's re-broadcast of the same request has us in its list already
+        mesh.driver_mut().set_mode_idle().unwrap();
+        mesh.driver_mut().load_rx_packet(&test_packet(
+            BROADCAST_ADDRESS,
+            0x03,
+            9,
+            HeaderFlags::NONE,
+            &[
+                &[BROADCAST_ADDRESS, 0x01, 0, 4, 0],
+                &[MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_REQUEST, 1, 0x05, 0x02],
+            ],
+        ));
+        assert_eq!(mesh.poll(), Ok(None));
+        assert_eq!(next_hop(&mesh, 0x03), None);
+        mesh.driver_mut().done();
+    }
+
+    #[test]
+    fn test_request_for_us_is_answered() {
+        let mut mesh = mesh(0x05, 2, 0);
+        mesh.driver_mut().load_rx_packet(&test_packet(
+            BROADCAST_ADDRESS,
+            0x02,
+            9,
+            HeaderFlags::NONE,
+            &[
+                &[BROADCAST_ADDRESS, 0x01, 0, 1, 0],
+                &[MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_REQUEST, 1, 0x05, 0x02],
+            ],
+        ));
+        assert_eq!(mesh.poll(), Ok(None));
+        assert_eq!(next_hop(&mesh, 0x01), Some(0x02));
+        assert_eq!(next_hop(&mesh, 0x02), Some(0x02));
+        let sent = mesh.driver().sent_packet();
+        assert_eq!((sent.to, sent.from), (0x02, 0x05));
+        assert_eq!(
+            sent.payload.as_slice(),
+            &[0x01, 0x05, 0, 1, 0, 2, 1, 0x05, 0x02]
+        );
+        mesh.driver_mut().done();
+    }
+
+    #[test]
+    fn test_send_discovers_route_then_delivers() {
+        let mut mesh = mesh(0x01, 5, 0);
+        mesh.send_to(0x05, b"Hi", 0).unwrap();
+        assert!(mesh.is_discovering());
+        assert_eq!(
+            mesh.send_to(0x05, b"Hi", 0),
+            Err(RouterError::Driver(AskError::Busy))
+        );
+        let sent = mesh.driver().sent_packet();
+        assert_eq!(sent.to, BROADCAST_ADDRESS);
+        assert_eq!(
+            sent.payload.as_slice(),
+            &[BROADCAST_ADDRESS, 0x01, 0, 1, 0, 1, 1, 0x05]
+        );
+
+        // 0x05 answers through 0x02
+        mesh.driver_mut().set_mode_idle().unwrap();
+        mesh.driver_mut().load_rx_packet(&test_packet(
+            0x01,
+            0x02,
+            9,
+            HeaderFlags::NONE,
+            &[
+                &[0x01, 0x05, 1, 1, 0],
+                &[MESH_MESSAGE_TYPE_ROUTE_DISCOVERY_RESPONSE, 1, 0x05, 0x02],
+            ],
+        ));
+        assert_eq!(mesh.poll(), Ok(None));
+        assert!(!mesh.is_discovering());
+        assert_eq!(next_hop(&mesh, 0x05), Some(0x02));
+        let sent = mesh.driver().sent_packet();
+        assert_eq!(sent.to, 0x02);
+        assert_eq!(
+            sent.payload.as_slice(),
+            &[0x05, 0x01, 0, 2, 0, 0, b'H', b'i']
+        );
+
+        mesh.driver_mut().set_mode_idle().unwrap();
+        let mut ack = AskPacket::new(0x01, 0x02);
+        ack.id = sent.id;
+        ack.flags = HeaderFlags::ACK;
+        mesh.driver_mut().load_rx_packet(&ack);
+        assert_eq!(mesh.poll(), Ok(Some(MeshEvent::Delivered { dest: 0x05 })));
+        mesh.driver_mut().done();
+    }
+
+    #[test]
+    fn test_message_length_follows_driver() {
+        let mut mesh = mesh(0x01, 1, 0);
+        assert_eq!(mesh.max_message_len(), MESH_MAX_MESSAGE_LEN);
+        mesh.driver_mut().set_fec(true);
+        assert_eq!(mesh.max_message_len(), 22);
+        assert_eq!(
+            mesh.send_to(0x05, &[0; 23], 0),
+            Err(RouterError::Driver(AskError::MessageTooLong))
+        );
+        assert!(!mesh.is_discovering());
+        mesh.driver_mut().done();
+    }
+
+    #[test]
+    fn test_discovery_times_out() {
+        let mut mesh = mesh(0x01, 3, 5);
+        mesh.set_arp_timeout(5);
+        mesh.send_to(0x05, b"Hi", 0).unwrap();
+        mesh.driver_mut().set_mode_idle().unwrap();
+        assert_eq!(mesh.poll(), Ok(None));
+        for _ in 0..5 {
+            mesh.tick().unwrap();
+        }
+        assert_eq!(mesh.poll(), Ok(Some(MeshEvent::NoRoute { dest: 0x05 })));
+        assert!(!mesh.is_discovering());
+        mesh.driver_mut().done();
+    }
+
+    #[test]
+    fn test_broken_forward_reports_route_failure() {
+        let mut mesh = mesh(0x02, 2, 0);
+        mesh.driver_mut().load_rx_packet(&test_packet(
+            0x02,
+            0x01,
+            9,
+            HeaderFlags::NONE,
+            &[
+                &[0x05, 0x01, 0, 8, 0],
+                &[MESH_MESSAGE_TYPE_APPLICATION, b'x'],
+            ],
+        ));
+        assert_eq!(mesh.poll(), Ok(None));
+        assert_eq!(next_hop(&mesh, 0x01), Some(0x01));
+        let sent = mesh.driver().sent_packet();
+        assert_eq!(sent.to, 0x01);
+        assert_eq!(
+            sent.payload.as_slice(),
+            &[0x01, 0x02, 0, 1, 0, MESH_MESSAGE_TYPE_ROUTE_FAILURE, 0x05]
+        );
+        mesh.driver_mut().done();
+    }
+
+    #[test]
+    fn test_route_failure_flushes_route() {
+        let mut mesh = mesh(0x01, 4, 0);
+        mesh.routing_table_mut()
+            .add_route_to(0x05, 0x02, RouteState::Valid);
+        mesh.driver_mut().load_rx_packet(&test_packet(
+            0x01,
+            0x02,
+            9,
+            HeaderFlags::NONE,
+            &[
+                &[0x01, 0x02, 0, 1, 0],
+                &[MESH_MESSAGE_TYPE_ROUTE_FAILURE, 0x05],
+            ],
+        ));
+        assert_eq!(mesh.poll(), Ok(None));
+        assert_eq!(next_hop(&mesh, 0x05), None);
+
+        // User data arrives without its message type
+        mesh.driver_mut().set_mode_idle().unwrap();
+        mesh.driver_mut().load_rx_packet(&test_packet(
+            0x01,
+            0x02,
+            9,
+            HeaderFlags::NONE,
+            &[
+                &[0x01, 0x02, 0, 2, 0],
+                &[MESH_MESSAGE_TYPE_APPLICATION, b'o', b'k'],
+            ],
+        ));
+        let Ok(Some(MeshEvent::Received(message))) = mesh.poll() else {
+            panic!("message not delivered");
+        };
+        assert_eq!(message.payload.as_slice(), b"ok");
+        mesh.driver_mut().done();
+    }
+}
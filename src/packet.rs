@@ -0,0 +1,81 @@
+//! Structured ASK packets.
+//!
+//! An [`AskPacket`] bundles the RadioHead-style header (`to`, `from`, `id`, `flags`)
+//! with the payload of a single frame. Received packets additionally carry some
+//! metadata about when and how cleanly they were demodulated.
+//!
+//! Packets are returned by [`AskDriver::receive()`](crate::driver::AskDriver::receive)
+//! and accepted by [`AskDriver::send_packet()`](crate::driver::AskDriver::send_packet).
+
+#[cfg(not(feature = "std"))]
+use crate::consts::ASK_MAX_MESSAGE_LEN_USIZE;
+use crate::consts::BROADCAST_ADDRESS;
+use crate::flags::HeaderFlags;
+
+#[cfg(not(feature = "std"))]
+use heapless::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// A single ASK frame: header fields, payload and reception metadata.
+///
+/// # Example
+/// ```rust
+/// use ask433::packet::AskPacket;
+///
+/// let mut packet = AskPacket::new(0x42, 0x01);
+/// packet.id = 7;
+/// packet.payload.extend(b"21.5C".iter().copied());
+/// assert_eq!(packet.payload.as_slice(), b"21.5C");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AskPacket {
+    /// Destination address ([`BROADCAST_ADDRESS`] for everyone).
+    pub to: u8,
+    /// Source address.
+    pub from: u8,
+    /// Packet ID, typically incremented per message to detect duplicates.
+    pub id: u8,
+    /// Header flags. Application code can only set the lower four
+    /// [`ASK_FLAGS_APPLICATION_SPECIFIC`](crate::consts::ASK_FLAGS_APPLICATION_SPECIFIC)
+    /// bits; the upper four are set by the protocol layers and the driver (e.g.
+    /// [`ASK_FLAGS_FEC`](crate::consts::ASK_FLAGS_FEC)), see [`HeaderFlags`].
+    pub flags: HeaderFlags,
+    /// The user data carried by the frame.
+    #[cfg(not(feature = "std"))]
+    pub payload: Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE>,
+    /// The user data carried by the frame.
+    #[cfg(feature = "std")]
+    pub payload: Vec<u8>,
+    /// Value of the driver's tick counter when the frame was validated.
+    ///
+    /// Ignored when sending.
+    pub received_at_tick: u32,
+    /// Demodulation quality in percent: the share of bits in the frame for which
+    /// every RX sample agreed. `100` is a perfectly clean signal.
+    ///
+    /// Ignored when sending.
+    pub quality: u8,
+}
+
+impl AskPacket {
+    /// Creates an empty packet addressed from `from` to `to`, with zeroed `id` and `flags`.
+    pub fn new(to: u8, from: u8) -> Self {
+        Self {
+            to,
+            from,
+            id: 0,
+            flags: HeaderFlags::NONE,
+            payload: Vec::new(),
+            received_at_tick: 0,
+            quality: 0,
+        }
+    }
+}
+
+impl Default for AskPacket {
+    /// An empty broadcast packet.
+    fn default() -> Self {
+        Self::new(BROADCAST_ADDRESS, BROADCAST_ADDRESS)
+    }
+}
@@ -5,6 +5,7 @@
 //! receiver output at high frequency, aligning to bit transitions, and
 //! recovering bits using a majority-vote integrator.
 
+use core::marker::PhantomData;
 use embedded_hal::digital::InputPin;
 
 #[cfg(not(feature = "std"))]
@@ -14,19 +15,35 @@ use std::vec::Vec;
 
 #[cfg(not(feature = "std"))]
 use crate::consts::ASK_MAX_BUF_LEN_USIZE;
-use crate::consts::{ASK_MAX_PAYLOAD_LEN, ASK_START_SYMBOL};
-use crate::encoding::decode_6b4b;
+use crate::consts::{ASK_FLAGS_FEC, ASK_HEADER_LEN, ASK_MAX_PAYLOAD_LEN, ASK_START_SYMBOL};
+use crate::encoding::{FourBSixB, LineCode};
+use crate::fec::{FEC_BLOCK_LEN, decode_block, fec_coded_len};
+
+/// Why the PLL aborted a frame before it was complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameDrop {
+    /// The length byte was shorter than a header or longer than a frame.
+    BadLength,
+    /// A received 6-bit value was not a valid 4b6b symbol.
+    BadSymbol,
+    /// The frame did not fit the receive buffer.
+    Overrun,
+    /// A forward error correction codeword had more flipped bits than it can correct.
+    Uncorrectable,
+}
 
 #[derive(Debug)]
 /// A simple digital phase-locked loop for demodulating ASK signals.
 ///
 /// This PLL samples the input pin at a fixed frequency (e.g., 8x per bit),
 /// detects transitions to adjust phase, and integrates high samples to decide bits.
+/// The bits of a frame are decoded into bytes with the line code `LC`.
 ///
 // - `ramp`: tracks phase position in the current bit interval
 // - `integrator`: counts HIGH samples
-pub struct SoftwarePLL {
-    /// Last 12 bits received, so we can look for the start symbol
+pub struct SoftwarePLL<LC: LineCode = FourBSixB> {
+    /// Last 16 bits received, newest in the MSB, so we can look for the start
+    /// symbol and decode a byte of any line code
     bits: u16,
 
     /// Phase accumulator used to track position within a single bit interval.
@@ -37,10 +54,41 @@ pub struct SoftwarePLL {
 
     /// Counts how many of the samples in the current bit interval were high (signal present).
     ///
-    /// Reset to 0 at the beginning of each bit period. If the count exceeds
-    /// a threshold (e.g., 5 of 8), the current bit is decoded as a 1.
+    /// Reset to 0 at the beginning of each bit period. If the count reaches
+    /// `threshold` (e.g., 5 of 8), the current bit is decoded as a 1.
     integrator: u8,
 
+    /// Number of high samples in a bit interval needed to decode a 1.
+    ///
+    /// Five eighths of the samples per bit, rounded up.
+    threshold: u8,
+
+    /// Counts how many samples have been taken in the current bit interval.
+    ///
+    /// Compared against `integrator` to tell whether all samples of a bit agreed.
+    samples: u8,
+
+    /// Number of bits demodulated since the start symbol of the current frame.
+    frame_bits: u16,
+
+    /// Number of bits since the start symbol for which every sample agreed.
+    clean_bits: u16,
+
+    /// Number of samples taken since the last edge, saturating.
+    ///
+    /// `u16::MAX` until the first edge has been seen.
+    since_edge: u16,
+
+    /// Number of edges seen since [`clear_activity()`](SoftwarePLL::clear_activity).
+    edges: u16,
+
+    /// Number of edges since [`clear_activity()`](SoftwarePLL::clear_activity) that
+    /// followed the previous one by (close to) a whole number of bit periods.
+    regular_edges: u16,
+
+    /// Number of samples per bit, used to judge the spacing of edges.
+    ticks_per_bit: u8,
+
     /// Tracks the previous sample value to detect rising or falling edges.
     ///
     /// Transitions are used to adjust ramp incrementing and keep the PLL in sync.
@@ -48,8 +96,8 @@ pub struct SoftwarePLL {
 
     /// Tracks how many bits have been shifted into `bitstream`.
     ///
-    /// Once this reaches 8, a complete byte is available and can be
-    /// consumed by higher-level protocol code.
+    /// Once this reaches two symbols of the line code, a complete byte is
+    /// available and can be consumed by higher-level protocol code.
     pub bit_count: u8,
 
     /// The number of phase units per bit.
@@ -88,8 +136,28 @@ pub struct SoftwarePLL {
     /// as sent in the message header.
     count: u8,
 
-    /// The number of bad messages received
-    pub bad: u16,
+    /// The smallest sensible byte count: the count byte itself, the headers and the FCS.
+    min_count: u8,
+
+    /// Why the last frame was aborted, until taken with
+    /// [`take_drop()`](SoftwarePLL::take_drop).
+    drop: Option<FrameDrop>,
+
+    /// Position in the frame of the header flags byte that signals forward error
+    /// correction, `None` if frames carry no flags.
+    flags_index: Option<u8>,
+
+    /// Whether the rest of the current frame is sent with forward error correction.
+    fec: bool,
+
+    /// Number of coded bytes of the current frame received on air.
+    fec_received: u8,
+
+    /// The FEC block being received.
+    fec_block: [u8; FEC_BLOCK_LEN],
+
+    /// Bits corrected by FEC, until taken with [`take_corrected()`](SoftwarePLL::take_corrected).
+    corrected: u16,
 
     /// Tracks whether the bitstream has accumulated a full byte.
     /// This is the same as when the `bit_count == 6`
@@ -106,26 +174,52 @@ pub struct SoftwarePLL {
     /// This is built bit-by-bit using majority vote sampling of each full bit period.
     #[cfg(feature = "std")]
     pub buf: Vec<u8>,
+
+    line_code: PhantomData<LC>,
 }
-impl SoftwarePLL {
+impl<LC: LineCode> SoftwarePLL<LC> {
     /// Creates a new, zeroed software PLL instance.
     ///
     /// Resets ramp, integrator, and bit reconstruction state.
+    ///
+    /// # Notes
+    /// `ticks_per_bit` must be at least [`ASK_MIN_TICKS_PER_BIT`](crate::consts::ASK_MIN_TICKS_PER_BIT);
+    /// [`AskConfig`](crate::config::AskConfig) guarantees this for drivers.
     pub fn new(ticks_per_bit: u8, inverted: bool) -> Self {
+        Self::with_min_count(ticks_per_bit, inverted, 1 + ASK_HEADER_LEN + 2)
+    }
+
+    /// Creates a new software PLL that drops frames whose byte count is below
+    /// `min_count`, e.g. because the frame check sequence is not 2 bytes long or
+    /// the frames carry no headers.
+    ///
+    /// # Notes
+    /// `ticks_per_bit` must be at least [`ASK_MIN_TICKS_PER_BIT`](crate::consts::ASK_MIN_TICKS_PER_BIT).
+    pub fn with_min_count(ticks_per_bit: u8, inverted: bool, min_count: u8) -> Self {
         let ramp_len = (ticks_per_bit as u16) * 20;
         let ramp_inc = ramp_len / ticks_per_bit as u16;
         let ramp_adjust = 9;
         let ramp_retard = ramp_inc - ramp_adjust;
         let ramp_advance = ramp_inc + ramp_adjust;
+        let threshold = ((ticks_per_bit as u16 * 5).div_ceil(8)) as u8;
         Self {
             ramp: 0,
             integrator: 0,
+            threshold,
+            samples: 0,
+            frame_bits: 0,
+            clean_bits: 0,
+            since_edge: u16::MAX,
+            edges: 0,
+            regular_edges: 0,
+            ticks_per_bit,
             last_sample: false,
             bit_count: 0,
             active: false,
             bits: 0,
             buf_len: 0,
             count: 0,
+            min_count,
             full: false,
             buf: Vec::new(),
             ramp_len,
@@ -133,10 +227,34 @@ impl SoftwarePLL {
             ramp_retard,
             ramp_advance,
             inverted,
-            bad: 0,
+            drop: None,
+            flags_index: Some(ASK_HEADER_LEN),
+            fec: false,
+            fec_received: 0,
+            fec_block: [0; FEC_BLOCK_LEN],
+            corrected: 0,
+            line_code: PhantomData,
         }
     }
 
+    /// Re-times the PLL for a new number of ticks per bit, e.g. after a bit rate change.
+    ///
+    /// Any frame in progress is dropped; the RX inversion setting and the minimum
+    /// byte count are kept.
+    pub fn set_ticks_per_bit(&mut self, ticks_per_bit: u8) {
+        *self = Self::with_min_count(ticks_per_bit, self.inverted, self.min_count)
+            .with_flags_index(self.flags_index);
+    }
+
+    /// Reads the [`ASK_FLAGS_FEC`] flag of each frame from the byte at `flags_index`
+    /// (the count byte being at `0`), or never expects coded frames if `None`.
+    ///
+    /// Defaults to the flags byte of RadioHead headers.
+    pub fn with_flags_index(mut self, flags_index: Option<u8>) -> Self {
+        self.flags_index = flags_index;
+        self
+    }
+
     /// Updates the PLL state using the current RX input sample.
     ///
     /// Should be called once per timing tick. This reads the RX pin, counts high
@@ -144,20 +262,23 @@ impl SoftwarePLL {
     ///
     /// # Arguments
     /// - `rx`: Input pin to sample
-    pub fn update<RX: InputPin>(&mut self, rx: &mut RX) {
+    ///
+    /// # Errors
+    /// Returns the pin's error if the RX pin could not be read. The PLL state is
+    /// left untouched in that case.
+    pub fn update<RX: InputPin>(&mut self, rx: &mut RX) -> Result<(), RX::Error> {
         // Poll the current state
-        let sample = if self.inverted {
-            !rx.is_high().unwrap_or(false)
-        } else {
-            rx.is_high().unwrap_or(false)
-        };
+        let high = rx.is_high()?;
+        let sample = if self.inverted { !high } else { high };
         // Ensure we're integrating each sample
+        self.samples = self.samples.saturating_add(1);
         if sample {
-            self.integrator += 1;
+            self.integrator = self.integrator.saturating_add(1);
         }
 
         // If the current state is different than previous
         if sample != self.last_sample {
+            self.count_edge();
             // Transition, advance if ramp > 80, retard if < 80
             self.ramp += if self.ramp < self.ramp_len / 2 {
                 self.ramp_retard
@@ -170,6 +291,7 @@ impl SoftwarePLL {
             // No transition
             // Advance ramp by standard 20 (== 160/8 samples)
             self.ramp += self.ramp_inc;
+            self.since_edge = self.since_edge.saturating_add(1);
         }
 
         if self.ramp >= self.ramp_len {
@@ -179,57 +301,216 @@ impl SoftwarePLL {
 
             // Check the integrator to see how many samples in this cycle were high.
             // If < 5 out of 8, then its declared a 0 bit, else a 1;
-            if self.integrator >= 5 {
-                self.bits |= 0x800;
+            if self.integrator >= self.threshold {
+                self.bits |= 0x8000;
+            }
+
+            if self.active {
+                // Keep track of how cleanly the bits of this frame were received
+                self.frame_bits = self.frame_bits.saturating_add(1);
+                if self.integrator == 0 || self.integrator == self.samples {
+                    self.clean_bits = self.clean_bits.saturating_add(1);
+                }
             }
 
             self.ramp -= self.ramp_len;
             self.integrator = 0;
+            self.samples = 0;
 
             if self.active {
                 // We have the start symbol and now we are collecting message bits,
-                // 6 per symbol, each which has to be decoded to 4 bits
+                // one symbol per nibble
                 self.bit_count += 1;
-                if self.bit_count >= 12 {
-                    // Have 12 bits of encoded message == 1 byte encoded
-                    // Decode as 2 lots of 6 bits into 2 lots of 4 bits
-                    // The 6 lsbits are the high nybble
-                    let this_byte =
-                        decode_6b4b(&((self.bits & 0x3f) as u8), &((self.bits >> 6) as u8));
-                    // The first decoded byte is the byte count of the following message
-                    // the count includes the byte count and the 2 trailing FCS bytes
-                    // REVISIT: may also include the ACK flag at 0x40
-                    if self.buf_len == 0 {
-                        // The first byte is the byte count
-                        // Check it for sensibility. It cant be less than 7, since it
-                        // includes the byte count itself, the 4 byte header and the 2 byte FCS
-                        self.count = this_byte;
-                        if self.count < 7 || self.count > ASK_MAX_PAYLOAD_LEN {
-                            // Stupid message length, drop the whole thing
-                            self.active = false;
-                            self.bad += 1;
-                            return;
-                        }
-                    }
-                    #[cfg(not(feature = "std"))]
-                    let _ = self.buf.push(this_byte);
-                    #[cfg(feature = "std")]
-                    self.buf.push(this_byte);
-                    self.buf_len += 1;
-
-                    if self.buf_len >= self.count {
-                        // Got all the bytes now
-                        self.active = false;
-                        self.full = true;
-                    }
+                if self.bit_count >= 2 * LC::SYMBOL_BITS {
+                    // Have 1 byte encoded, decode it as 2 symbols into 2 nibbles
+                    // The lsbits are the high nybble
+                    let byte_bits = self.bits >> (16 - 2 * LC::SYMBOL_BITS);
+                    let mask = (1 << LC::SYMBOL_BITS) - 1;
                     self.bit_count = 0;
+                    self.receive_symbols(
+                        (byte_bits & mask) as u8,
+                        ((byte_bits >> LC::SYMBOL_BITS) & mask) as u8,
+                    );
                 }
-            } else if self.bits == ASK_START_SYMBOL {
+            } else if self.bits >> 4 == ASK_START_SYMBOL {
+                // A new frame always starts from an empty buffer; a previous frame
+                // that was not picked up yet is dropped
                 self.active = true;
+                self.full = false;
                 self.bit_count = 0;
                 self.buf_len = 0;
+                self.buf.clear();
+                self.fec = false;
+                self.fec_received = 0;
+                self.frame_bits = 0;
+                self.clean_bits = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Decodes the two symbols of a byte and adds it to the frame.
+    fn receive_symbols(&mut self, high: u8, low: u8) {
+        if self.fec {
+            // Bad symbols are left to the FEC to correct
+            let byte =
+                (LC::decode_symbol(high).unwrap_or(0) << 4) | LC::decode_symbol(low).unwrap_or(0);
+            self.receive_fec_byte(byte);
+            return;
+        }
+        let Some(this_byte) = LC::decode(high, low) else {
+            // Noise or a collision, the rest of the frame can't be trusted
+            self.abort(FrameDrop::BadSymbol);
+            return;
+        };
+        // The first decoded byte is the byte count of the following message
+        // the count includes the byte count and the trailing FCS bytes.
+        // ACK and retry flags live in the header flags byte, see `crate::reliable`
+        if self.buf_len == 0 {
+            // The first byte is the byte count
+            // Check it for sensibility. It cant be less than `min_count`, since it
+            // includes the byte count itself, the headers and the FCS
+            self.count = this_byte;
+            if self.count < self.min_count || self.count > ASK_MAX_PAYLOAD_LEN {
+                // Stupid message length, drop the whole thing
+                self.abort(FrameDrop::BadLength);
+                return;
+            }
+        }
+        if !self.push_byte(this_byte) {
+            // No room left for the frame, drop the whole thing
+            self.abort(FrameDrop::Overrun);
+            return;
+        }
+        if Some(self.buf_len - 1) == self.flags_index && this_byte & ASK_FLAGS_FEC != 0 {
+            // The payload and FCS that follow are coded, see `crate::fec`
+            let coded_len = fec_coded_len((self.count - self.buf_len) as usize);
+            if self.buf_len as usize + coded_len > ASK_MAX_PAYLOAD_LEN as usize {
+                self.abort(FrameDrop::BadLength);
+                return;
+            }
+            self.fec = true;
+            self.fec_received = 0;
+        }
+        if self.buf_len >= self.count {
+            // Got all the bytes now
+            self.active = false;
+            self.full = true;
+        }
+    }
+
+    /// Collects the bytes of an FEC frame and decodes each block once it is complete.
+    fn receive_fec_byte(&mut self, byte: u8) {
+        self.fec_block[self.fec_received as usize % FEC_BLOCK_LEN] = byte;
+        self.fec_received += 1;
+        if !(self.fec_received as usize).is_multiple_of(FEC_BLOCK_LEN) {
+            return;
+        }
+        let Some((data, corrected)) = decode_block(self.fec_block) else {
+            self.abort(FrameDrop::Uncorrectable);
+            return;
+        };
+        self.corrected = self.corrected.saturating_add(corrected.into());
+        for byte in data {
+            // The count tells where the padding of the last block starts
+            if self.buf_len < self.count && !self.push_byte(byte) {
+                self.abort(FrameDrop::Overrun);
+                return;
             }
         }
+        if self.buf_len >= self.count {
+            self.active = false;
+            self.full = true;
+        }
+    }
+
+    /// Appends a decoded byte to the frame, or returns `false` if there is no room left.
+    fn push_byte(&mut self, byte: u8) -> bool {
+        #[cfg(not(feature = "std"))]
+        if self.buf.push(byte).is_err() {
+            return false;
+        }
+        #[cfg(feature = "std")]
+        self.buf.push(byte);
+        self.buf_len += 1;
+        true
+    }
+
+    /// Drops the frame being received.
+    fn abort(&mut self, reason: FrameDrop) {
+        self.active = false;
+        self.drop = Some(reason);
+    }
+
+    /// Takes the number of bits corrected by forward error correction since the last call.
+    ///
+    /// The driver calls this after every [`update()`](SoftwarePLL::update) to keep
+    /// its [`AskStats`](crate::stats::AskStats) current.
+    pub fn take_corrected(&mut self) -> u16 {
+        core::mem::take(&mut self.corrected)
+    }
+
+    /// Records an edge and whether it came a whole number of bits after the previous one.
+    fn count_edge(&mut self) {
+        let tpb = self.ticks_per_bit as u16;
+        let tolerance = tpb / 4;
+        let interval = self.since_edge.saturating_add(1);
+        let offset = interval % tpb;
+        // 4b6b coded data has short runs of equal bits, anything longer than six is not ours
+        if interval != u16::MAX
+            && (tpb - tolerance..=6 * tpb + tolerance).contains(&interval)
+            && (offset <= tolerance || offset >= tpb - tolerance)
+        {
+            self.regular_edges = self.regular_edges.saturating_add(1);
+        }
+        self.edges = self.edges.saturating_add(1);
+        self.since_edge = 0;
+    }
+
+    /// Takes the reason the last frame was aborted, if one was since the last call.
+    ///
+    /// The driver calls this after every [`update()`](SoftwarePLL::update) to keep
+    /// its [`AskStats`](crate::stats::AskStats) current.
+    pub fn take_drop(&mut self) -> Option<FrameDrop> {
+        self.drop.take()
+    }
+
+    /// Resets the edge counters used for channel activity detection.
+    pub fn clear_activity(&mut self) {
+        self.since_edge = u16::MAX;
+        self.edges = 0;
+        self.regular_edges = 0;
+    }
+
+    /// Number of edges on the RX pin since the last
+    /// [`clear_activity()`](SoftwarePLL::clear_activity).
+    pub fn edges(&self) -> u16 {
+        self.edges
+    }
+
+    /// Number of edges since the last [`clear_activity()`](SoftwarePLL::clear_activity)
+    /// that came a whole number of bit periods (give or take a quarter bit) after the
+    /// previous edge, as they do in a real ASK transmission. Edges caused by receiver
+    /// noise are mostly irregular.
+    pub fn regular_edges(&self) -> u16 {
+        self.regular_edges
+    }
+
+    /// Returns the last sampled RX level, with the configured polarity applied.
+    pub fn level(&self) -> bool {
+        self.last_sample
+    }
+
+    /// Returns the demodulation quality of the current (or last) frame in percent.
+    ///
+    /// This is the share of bits since the start symbol for which every RX sample
+    /// in the bit interval agreed, so `100` means no sample was ever on the wrong
+    /// side of a bit. Returns `0` before any frame bits have been received.
+    pub fn quality(&self) -> u8 {
+        if self.frame_bits == 0 {
+            return 0;
+        }
+        (u32::from(self.clean_bits) * 100 / u32::from(self.frame_bits)) as u8
     }
 }
 
@@ -242,12 +523,12 @@ mod tests {
 
     #[test]
     fn test_pll_initialization_defaults() {
-        let pll = SoftwarePLL::new(8, false);
+        let pll = SoftwarePLL::<FourBSixB>::new(8, false);
         assert_eq!(pll.ramp, 0);
         assert_eq!(pll.integrator, 0);
         assert_eq!(pll.bit_count, 0);
-        assert_eq!(pll.active, false);
-        assert_eq!(pll.full, false);
+        assert!(!pll.active);
+        assert!(!pll.full);
         assert_eq!(pll.buf_len, 0);
     }
 
@@ -256,8 +537,8 @@ mod tests {
         let expectations = [PinTransaction::get(PinState::High)];
         let mut rx = PinMock::new(&expectations);
 
-        let mut pll = SoftwarePLL::new(8, false);
-        pll.update(&mut rx);
+        let mut pll = SoftwarePLL::<FourBSixB>::new(8, false);
+        pll.update(&mut rx).unwrap();
 
         // Should increment integrator if sample was high
         assert!(pll.integrator > 0);
@@ -266,16 +547,107 @@ mod tests {
 
     #[test]
     fn test_pll_detects_start_symbol() {
-        let mut pll = SoftwarePLL::new(8, false);
-        pll.bits = ASK_START_SYMBOL << 1; // Shifted to simulate previous bits
+        let mut pll = SoftwarePLL::<FourBSixB>::new(8, false);
+        // All but the newest bit of the start symbol, which is sampled below
+        pll.bits = ASK_START_SYMBOL << 5;
+        pll.integrator = pll.threshold - 1;
         pll.ramp = pll.ramp_len;
 
         // Should detect start symbol and set active
         let expectations = [PinTransaction::get(PinState::High)];
         let mut rx = PinMock::new(&expectations);
-        pll.update(&mut rx);
+        #[cfg(feature = "std")]
+        pll.buf.extend_from_slice(&[0x11, 0x22]);
+        #[cfg(not(feature = "std"))]
+        pll.buf.extend_from_slice(&[0x11, 0x22]).unwrap();
+        pll.buf_len = 2;
+        pll.full = true;
+        pll.update(&mut rx).unwrap();
 
         assert!(pll.active);
+        // Leftovers from the previous frame are discarded
+        assert!(!pll.full);
+        assert!(pll.buf.is_empty());
+        assert_eq!(pll.buf_len, 0);
+        rx.done();
+    }
+
+    #[test]
+    fn test_pll_drops_frame_with_bad_length() {
+        let mut pll = SoftwarePLL::<FourBSixB>::new(8, false);
+        pll.active = true;
+        pll.bit_count = 11;
+        // 0x0d 0x0d encodes to a length byte of 0x00
+        pll.bits = ((0x0d << 6) | 0x0d) << 5;
+        pll.ramp = pll.ramp_len;
+
+        let mut rx = PinMock::new(&[PinTransaction::get(PinState::Low)]);
+        pll.update(&mut rx).unwrap();
+
+        assert!(!pll.active);
+        assert_eq!(pll.take_drop(), Some(FrameDrop::BadLength));
+        assert_eq!(pll.take_drop(), None);
+        rx.done();
+    }
+
+    #[test]
+    fn test_pll_reads_application_flags_as_uncoded() {
+        use crate::encoding::encode_4b6b;
+
+        let mut pll = SoftwarePLL::<FourBSixB>::new(8, false);
+        pll.active = true;
+        // RadioHead nodes may set any of the four application bits
+        let frame = [9, 0x01, 0x02, 0x03, 0x0f, b'H', b'i', 0xaa, 0xbb];
+        for byte in frame {
+            let [high, low] = encode_4b6b(byte);
+            pll.receive_symbols(high, low);
+        }
+
+        assert!(pll.full);
+        assert!(!pll.fec);
+        assert_eq!(pll.buf.as_slice(), frame);
+    }
+
+    #[test]
+    fn test_pll_aborts_frame_on_invalid_symbol() {
+        let mut pll = SoftwarePLL::<FourBSixB>::new(8, false);
+        pll.active = true;
+        pll.bit_count = 11;
+        // 0x0d is a valid symbol, 0x3f is not
+        pll.bits = ((0x3f << 6) | 0x0d) << 5;
+        pll.ramp = pll.ramp_len;
+
+        let mut rx = PinMock::new(&[PinTransaction::get(PinState::Low)]);
+        pll.update(&mut rx).unwrap();
+
+        assert!(!pll.active);
+        assert!(pll.buf.is_empty());
+        assert_eq!(pll.take_drop(), Some(FrameDrop::BadSymbol));
+        rx.done();
+    }
+
+    #[test]
+    fn test_pll_quality_counts_unanimous_bits() {
+        let mut pll = SoftwarePLL::<FourBSixB>::new(8, false);
+        assert_eq!(pll.quality(), 0);
+        pll.active = true;
+
+        // One clean bit (9 high samples), then a noisy one (4 high, 3 low samples)
+        let states: [PinTransaction; 16] = core::array::from_fn(|i| {
+            PinTransaction::get(if i < 13 {
+                PinState::High
+            } else {
+                PinState::Low
+            })
+        });
+        let mut rx = PinMock::new(&states);
+        for _ in 0..16 {
+            pll.update(&mut rx).unwrap();
+        }
+
+        assert_eq!(pll.frame_bits, 2);
+        assert_eq!(pll.clean_bits, 1);
+        assert_eq!(pll.quality(), 50);
         rx.done();
     }
 
@@ -284,11 +656,36 @@ mod tests {
         let expectations = [PinTransaction::get(PinState::High)];
         let mut rx = PinMock::new(&expectations);
 
-        let mut pll = SoftwarePLL::new(8, true);
-        pll.update(&mut rx);
+        let mut pll = SoftwarePLL::<FourBSixB>::new(8, true);
+        pll.update(&mut rx).unwrap();
 
         // Inverted high is low: integrator should remain zero
         assert_eq!(pll.integrator, 0);
         rx.done();
     }
+
+    #[test]
+    fn test_pll_counts_regular_edges() {
+        let mut pll = SoftwarePLL::<FourBSixB>::new(8, false);
+
+        // Edges at samples 0, 8, 16, 24 (whole bits apart) and 27 (a glitch)
+        let states: [PinTransaction; 32] = core::array::from_fn(|i| {
+            PinTransaction::get(if i < 8 || (16..24).contains(&i) || i >= 27 {
+                PinState::High
+            } else {
+                PinState::Low
+            })
+        });
+        let mut rx = PinMock::new(&states);
+        for _ in 0..32 {
+            pll.update(&mut rx).unwrap();
+        }
+        assert_eq!(pll.edges(), 5);
+        assert_eq!(pll.regular_edges(), 3);
+
+        pll.clear_activity();
+        assert_eq!(pll.edges(), 0);
+        assert_eq!(pll.regular_edges(), 0);
+        rx.done();
+    }
 }
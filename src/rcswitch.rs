@@ -0,0 +1,586 @@
+//! Fixed-code remotes (EV1527, PT2262 and friends).
+//!
+//! Cheap remotes, doorbells and PIR sensors do not send RadioHead frames but
+//! pulse-width OOK code words: a sync pulse followed by one high/low pulse pair
+//! per bit, repeated for as long as a button is held. [`RcSwitchDecoder`] decodes
+//! them the way the Arduino rc-switch library does:
+//!
+//! - It measures the time between edges of the sampled RX signal.
+//! - A gap longer than [`RC_SEPARATION_LIMIT_US`] ends a code word. Once two gaps
+//!   of about the same length have been seen, the edges between them are matched
+//!   against every protocol in [`RC_PROTOCOLS`], scaled by the gap length.
+//! - Of the protocols whose pulse widths all fit within [`RC_RECEIVE_TOLERANCE`]
+//!   percent, the closest fit wins. Unlike rc-switch, which takes the first fit,
+//!   this tells apart protocols that only differ in their pulse length.
+//!
+//! As with rc-switch, protocol 4 can be sent but not received: its sync gap is
+//! shorter than the separation limit.
+//!
+//! EV1527 remotes send a 24-bit code (20-bit address and 4 data bits) with
+//! protocol 1 timing. PT2262 (Princeton) remotes send 12 tri-state symbols as
+//! 24 bits, see [`RcCode::tri_states()`].
+//!
+//! An [`AskDriver`](crate::driver::AskDriver) runs a decoder next to its PLL
+//! when [`AskConfigBuilder::rc_switch_rx()`](crate::config::AskConfigBuilder::rc_switch_rx)
+//! is set. Codes are read with
+//! [`AskDriver::receive_rc_code()`](crate::driver::AskDriver::receive_rc_code).
+//!
+//! The driver also sends codes, e.g. to switch mains outlets, with
+//! [`AskDriver::send_rc_code()`](crate::driver::AskDriver::send_rc_code). Build them
+//! with [`RcCode::new()`] or, for PT2262 receivers, [`RcCode::from_tri_states()`].
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ask433::rcswitch::RcSwitchDecoder;
+//!
+//! // Sample the RX pin at 16 kHz
+//! let mut decoder = RcSwitchDecoder::new(16_000);
+//! # for _ in 0..3 {
+//! #     for (high, ticks) in [(true, 6), (false, 174)] {
+//! #         for _ in 0..ticks { decoder.update(high); }
+//! #     }
+//! #     for bit in 0..24 {
+//! #         let (h, l) = if (0x0a_5a_5a >> (23 - bit)) & 1 == 1 { (17, 5) } else { (5, 17) };
+//! #         for (high, ticks) in [(true, h), (false, l)] {
+//! #             for _ in 0..ticks { decoder.update(high); }
+//! #         }
+//! #     }
+//! # }
+//! # decoder.update(true);
+//! let code = decoder.take_code().unwrap();
+//! assert_eq!((code.code, code.bit_len, code.protocol), (0x0a_5a_5a, 24, 1));
+//! ```
+
+/// Most edges recorded between two gaps: a sync pulse pair and 32 bits.
+pub const RC_MAX_CHANGES: usize = 67;
+
+/// Shortest gap, in microseconds, that separates two code words.
+pub const RC_SEPARATION_LIMIT_US: u16 = 4300;
+
+/// How far, in percent of the pulse length, a received pulse may be off.
+pub const RC_RECEIVE_TOLERANCE: u32 = 60;
+
+/// Fewest edges a code word needs to be reported; shorter bursts are noise.
+const RC_MIN_CHANGES: usize = 8;
+
+/// Number of times rc-switch sends a code word by default.
+pub const RC_DEFAULT_REPEATS: u8 = 10;
+
+/// The lengths of a high and a low pulse, in multiples of a protocol's pulse length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RcPulse {
+    /// Length of the high part.
+    pub high: u8,
+    /// Length of the low part.
+    pub low: u8,
+}
+
+/// Timing of an rc-switch protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RcProtocol {
+    /// Base pulse length in microseconds.
+    pub pulse_len_us: u16,
+    /// The sync pulse sent before every code word.
+    pub sync: RcPulse,
+    /// The pulse pair of a `0` bit.
+    pub zero: RcPulse,
+    /// The pulse pair of a `1` bit.
+    pub one: RcPulse,
+    /// Whether pulses are sent with inverted levels: low for the `high` length,
+    /// then high for the `low` length.
+    pub inverted: bool,
+}
+
+/// The rc-switch protocols 1 to 6; protocol `n` is at index `n - 1`.
+pub static RC_PROTOCOLS: [RcProtocol; 6] = [
+    // Protocol 1: EV1527, PT2262 and most cheap remotes
+    RcProtocol {
+        pulse_len_us: 350,
+        sync: RcPulse { high: 1, low: 31 },
+        zero: RcPulse { high: 1, low: 3 },
+        one: RcPulse { high: 3, low: 1 },
+        inverted: false,
+    },
+    // Protocol 2
+    RcProtocol {
+        pulse_len_us: 650,
+        sync: RcPulse { high: 1, low: 10 },
+        zero: RcPulse { high: 1, low: 2 },
+        one: RcPulse { high: 2, low: 1 },
+        inverted: false,
+    },
+    // Protocol 3
+    RcProtocol {
+        pulse_len_us: 100,
+        sync: RcPulse { high: 30, low: 71 },
+        zero: RcPulse { high: 4, low: 11 },
+        one: RcPulse { high: 9, low: 6 },
+        inverted: false,
+    },
+    // Protocol 4
+    RcProtocol {
+        pulse_len_us: 380,
+        sync: RcPulse { high: 1, low: 6 },
+        zero: RcPulse { high: 1, low: 3 },
+        one: RcPulse { high: 3, low: 1 },
+        inverted: false,
+    },
+    // Protocol 5
+    RcProtocol {
+        pulse_len_us: 500,
+        sync: RcPulse { high: 6, low: 14 },
+        zero: RcPulse { high: 1, low: 2 },
+        one: RcPulse { high: 2, low: 1 },
+        inverted: false,
+    },
+    // Protocol 6: HT6P20B
+    RcProtocol {
+        pulse_len_us: 450,
+        sync: RcPulse { high: 23, low: 1 },
+        zero: RcPulse { high: 1, low: 2 },
+        one: RcPulse { high: 2, low: 1 },
+        inverted: true,
+    },
+];
+
+/// A PT2262 tri-state symbol, sent as two bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriState {
+    /// Pin tied low, sent as `00`.
+    Zero,
+    /// Pin tied high, sent as `11`.
+    One,
+    /// Pin left open, sent as `01`.
+    Floating,
+}
+
+impl TriState {
+    /// Parses a symbol written the rc-switch way: `'0'`, `'1'` or `'F'`.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '0' => Some(TriState::Zero),
+            '1' => Some(TriState::One),
+            'F' | 'f' => Some(TriState::Floating),
+            _ => None,
+        }
+    }
+
+    /// Returns the two bits this symbol is sent as.
+    pub fn bits(self) -> u32 {
+        match self {
+            TriState::Zero => 0b00,
+            TriState::One => 0b11,
+            TriState::Floating => 0b01,
+        }
+    }
+}
+
+/// A code word received from a fixed-code remote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RcCode {
+    /// The received bits, first bit in the most significant used position.
+    pub code: u32,
+    /// Number of bits received.
+    pub bit_len: u8,
+    /// The measured base pulse length in microseconds.
+    pub pulse_len_us: u16,
+    /// The rc-switch protocol number, `1..=6`.
+    pub protocol: u8,
+}
+
+impl RcCode {
+    /// Creates a code to send with the protocol's own pulse length.
+    ///
+    /// # Returns
+    /// `None` if `protocol` is not `1..=6` or `bit_len` is not `1..=32`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use ask433::rcswitch::RcCode;
+    ///
+    /// let code = RcCode::new(1, 0x0005_5551, 24).unwrap().with_pulse_len(320);
+    /// assert_eq!(code.pulse_len_us, 320);
+    /// assert!(RcCode::new(7, 0x0005_5551, 24).is_none());
+    /// ```
+    pub fn new(protocol: u8, code: u32, bit_len: u8) -> Option<Self> {
+        let timing = RC_PROTOCOLS.get(usize::from(protocol).checked_sub(1)?)?;
+        if !(1..=32).contains(&bit_len) {
+            return None;
+        }
+        Some(Self {
+            code,
+            bit_len,
+            pulse_len_us: timing.pulse_len_us,
+            protocol,
+        })
+    }
+
+    /// Creates a code from PT2262 tri-state symbols written the rc-switch way,
+    /// e.g. `"0FF0F0FFFF0F"`.
+    ///
+    /// # Returns
+    /// `None` if `protocol` is not `1..=6`, a symbol is not `0`, `1` or `F`, or
+    /// there are no or more than 16 symbols.
+    pub fn from_tri_states(protocol: u8, symbols: &str) -> Option<Self> {
+        let mut code = 0;
+        let mut len = 0u8;
+        for c in symbols.chars() {
+            let symbol = TriState::from_char(c)?;
+            code = (code << 2) | symbol.bits();
+            len = len.checked_add(2)?;
+        }
+        Self::new(protocol, code, len)
+    }
+
+    /// Returns the code with the base pulse length set to `pulse_len_us` microseconds.
+    pub fn with_pulse_len(mut self, pulse_len_us: u16) -> Self {
+        self.pulse_len_us = pulse_len_us;
+        self
+    }
+
+    /// Returns the timing of the code's protocol, or `None` for an unknown protocol.
+    pub fn timing(&self) -> Option<&'static RcProtocol> {
+        RC_PROTOCOLS.get(usize::from(self.protocol).checked_sub(1)?)
+    }
+
+    /// Reads the code as PT2262 tri-state symbols, first symbol first.
+    ///
+    /// # Returns
+    /// - `Some(symbols)` if every bit pair is a valid symbol
+    /// - `None` if the bit length is odd or a pair reads `10`
+    pub fn tri_states(&self) -> Option<impl Iterator<Item = TriState>> {
+        if !self.bit_len.is_multiple_of(2) {
+            return None;
+        }
+        let code = self.code;
+        let pairs = (0..self.bit_len / 2)
+            .rev()
+            .map(move |i| (code >> (2 * i)) & 0b11);
+        if pairs.clone().any(|pair| pair == 0b10) {
+            return None;
+        }
+        Some(pairs.map(|pair| match pair {
+            0b00 => TriState::Zero,
+            0b11 => TriState::One,
+            _ => TriState::Floating,
+        }))
+    }
+}
+
+/// Decodes fixed-code remotes from RX samples taken at a fixed rate.
+///
+/// Feed it one sample per tick with [`update()`](RcSwitchDecoder::update) and read
+/// decoded code words with [`take_code()`](RcSwitchDecoder::take_code).
+#[derive(Debug)]
+pub struct RcSwitchDecoder {
+    /// Rate at which samples are fed, in Hz.
+    tick_hz: u32,
+
+    /// The last sampled level.
+    level: bool,
+
+    /// Number of samples the current level has lasted.
+    run: u32,
+
+    /// Lengths in microseconds of the levels since the last gap, the gap first.
+    timings: [u16; RC_MAX_CHANGES],
+
+    /// Number of valid entries in `timings`.
+    change_count: usize,
+
+    /// Number of gaps of about the same length seen in a row.
+    repeat_count: u8,
+
+    /// The last decoded code word, until taken.
+    code: Option<RcCode>,
+}
+
+impl RcSwitchDecoder {
+    /// Creates a decoder for samples taken `tick_hz` times per second.
+    pub fn new(tick_hz: u32) -> Self {
+        Self {
+            tick_hz,
+            level: false,
+            run: 0,
+            timings: [0; RC_MAX_CHANGES],
+            change_count: 0,
+            repeat_count: 0,
+            code: None,
+        }
+    }
+
+    /// Feeds the next RX sample.
+    ///
+    /// Edges are timed by counting samples, so this must be called at the rate
+    /// given to [`new()`](RcSwitchDecoder::new).
+    pub fn update(&mut self, level: bool) {
+        if level == self.level {
+            self.run = self.run.saturating_add(1);
+            return;
+        }
+        let duration = u64::from(self.run) * 1_000_000 / u64::from(self.tick_hz.max(1));
+        self.level = level;
+        self.run = 1;
+        self.edge(duration.min(u64::from(u16::MAX)) as u16);
+    }
+
+    /// Takes the last decoded code word, if one arrived since the last call.
+    pub fn take_code(&mut self) -> Option<RcCode> {
+        self.code.take()
+    }
+
+    /// Records a level that lasted `duration` microseconds.
+    fn edge(&mut self, duration: u16) {
+        if duration > RC_SEPARATION_LIMIT_US {
+            // A long stretch without an edge: likely the gap between two repeats of
+            // a code word. Senders repeat with the same gap, so only decode once the
+            // gap before the recorded edges matched this one.
+            if self.repeat_count == 0 || self.timings[0].abs_diff(duration) < 200 {
+                self.repeat_count += 1;
+                if self.repeat_count == 2 {
+                    // Several protocols share their bit shapes, report the closest fit
+                    self.code = (1..)
+                        .zip(RC_PROTOCOLS.iter())
+                        .filter_map(|(id, protocol)| self.decode(id, protocol))
+                        .min_by_key(|(_, deviation)| *deviation)
+                        .map(|(code, _)| code)
+                        .or(self.code);
+                    self.repeat_count = 0;
+                }
+            }
+            self.change_count = 0;
+        }
+        if self.change_count >= RC_MAX_CHANGES {
+            // Too many edges for a code word, start over
+            self.change_count = 0;
+            self.repeat_count = 0;
+        }
+        self.timings[self.change_count] = duration;
+        self.change_count += 1;
+    }
+
+    /// Matches the recorded edges against `protocol`.
+    ///
+    /// Returns the code and how far the edges were off in total, in microseconds.
+    fn decode(&self, id: u8, protocol: &RcProtocol) -> Option<(RcCode, u32)> {
+        if self.change_count < RC_MIN_CHANGES {
+            return None;
+        }
+        // The gap is the long part of the sync pulse, which gives the pulse length
+        let sync_len = protocol.sync.high.max(protocol.sync.low);
+        let delay = u32::from(self.timings[0]) / u32::from(sync_len);
+        let tolerance = delay * RC_RECEIVE_TOLERANCE / 100;
+        let off = |timing: u16, len: u8| {
+            let off = u32::from(timing).abs_diff(delay * u32::from(len));
+            (off < tolerance).then_some(off)
+        };
+        let fits = |[high, low]: [u16; 2], pulse: RcPulse| {
+            Some(off(high, pulse.high)? + off(low, pulse.low)?)
+        };
+
+        // The short part of an inverted sync pulse comes after the gap
+        let first = if protocol.inverted { 2 } else { 1 };
+        let mut code = 0u32;
+        let mut deviation = 0u32;
+        for pair in self.timings[first..self.change_count].chunks_exact(2) {
+            let pair = [pair[0], pair[1]];
+            code <<= 1;
+            let off = match fits(pair, protocol.zero) {
+                Some(off) => off,
+                None => {
+                    // Not a zero bit, so it has to be a one
+                    code |= 1;
+                    fits(pair, protocol.one)?
+                }
+            };
+            deviation += off;
+        }
+        let code = RcCode {
+            code,
+            bit_len: ((self.change_count - 1) / 2) as u8,
+            pulse_len_us: delay as u16,
+            protocol: id,
+        };
+        Some((code, deviation))
+    }
+}
+
+/// Sends a code word a number of times, one pulse part per call to
+/// [`tick()`](RcTransmitter::tick).
+#[derive(Debug)]
+pub(crate) struct RcTransmitter {
+    /// The code word being sent.
+    code: RcCode,
+
+    /// Timing of the code's protocol.
+    timing: RcProtocol,
+
+    /// Length of one pulse unit, in ticks.
+    pulse_ticks: u32,
+
+    /// Number of times the code word still has to be sent, the current one included.
+    repeats_left: u8,
+
+    /// Index of the next pulse part within the code word: two per bit, then the sync pulse.
+    part: u8,
+
+    /// Ticks left of the current pulse part.
+    remaining: u32,
+}
+
+impl RcTransmitter {
+    /// Prepares sending `code` `repeats` times at `tick_hz`.
+    ///
+    /// Returns `None` if the code has an unknown protocol, no bits or more than 32 bits.
+    pub(crate) fn new(code: &RcCode, repeats: u8, tick_hz: u32) -> Option<Self> {
+        let timing = *code.timing()?;
+        if !(1..=32).contains(&code.bit_len) {
+            return None;
+        }
+        let pulse_ticks = (u64::from(code.pulse_len_us) * u64::from(tick_hz) + 500_000) / 1_000_000;
+        Some(Self {
+            code: *code,
+            timing,
+            pulse_ticks: pulse_ticks.clamp(1, u64::from(u32::MAX / 255)) as u32,
+            repeats_left: repeats,
+            part: 0,
+            remaining: 0,
+        })
+    }
+
+    /// Returns `true` once every repeat has been sent.
+    pub(crate) fn is_done(&self) -> bool {
+        self.repeats_left == 0 && self.remaining == 0
+    }
+
+    /// Advances by one tick.
+    ///
+    /// Returns the level to drive the TX pin to when a new pulse part starts,
+    /// `None` while the current part lasts or once [`is_done()`](RcTransmitter::is_done).
+    pub(crate) fn tick(&mut self) -> Option<bool> {
+        self.remaining = self.remaining.saturating_sub(1);
+        if self.remaining > 0 || self.repeats_left == 0 {
+            return None;
+        }
+        let bit_parts = 2 * self.code.bit_len;
+        let pulse = if self.part < bit_parts {
+            let bit = self.code.bit_len - 1 - self.part / 2;
+            if (self.code.code >> bit) & 1 == 1 {
+                self.timing.one
+            } else {
+                self.timing.zero
+            }
+        } else {
+            self.timing.sync
+        };
+        // The first part of a pulse is high, unless the protocol is inverted
+        let (first, units) = if self.part.is_multiple_of(2) {
+            (true, pulse.high)
+        } else {
+            (false, pulse.low)
+        };
+        self.remaining = self.pulse_ticks * u32::from(units);
+        self.part += 1;
+        if self.part >= bit_parts + 2 {
+            self.part = 0;
+            self.repeats_left -= 1;
+        }
+        Some(first != self.timing.inverted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sends `code` with `protocol` three times, sampled at 16 kHz.
+    fn send(decoder: &mut RcSwitchDecoder, protocol: &RcProtocol, code: u32, bit_len: u8) {
+        let pulse = |decoder: &mut RcSwitchDecoder, pulse: RcPulse| {
+            let ticks = |len: u8| u32::from(len) * u32::from(protocol.pulse_len_us) * 16 / 1000;
+            let high = !protocol.inverted;
+            for (level, len) in [(high, pulse.high), (!high, pulse.low)] {
+                for _ in 0..ticks(len) {
+                    decoder.update(level);
+                }
+            }
+        };
+        for _ in 0..3 {
+            for bit in (0..bit_len).rev() {
+                let one = (code >> bit) & 1 == 1;
+                pulse(decoder, if one { protocol.one } else { protocol.zero });
+            }
+            pulse(decoder, protocol.sync);
+        }
+        decoder.update(!protocol.inverted);
+    }
+
+    #[test]
+    fn test_decodes_every_protocol() {
+        // The sync gap of protocol 4 is shorter than the separation limit
+        for (id, protocol) in (1..).zip(RC_PROTOCOLS.iter()).filter(|(id, _)| *id != 4) {
+            let mut decoder = RcSwitchDecoder::new(16_000);
+            send(&mut decoder, protocol, 0x00c3_a5f0, 24);
+            let Some(code) = decoder.take_code() else {
+                panic!("protocol {id}")
+            };
+            assert_eq!(
+                (code.code, code.bit_len, code.protocol),
+                (0x00c3_a5f0, 24, id)
+            );
+            assert!(code.pulse_len_us.abs_diff(protocol.pulse_len_us) < protocol.pulse_len_us / 10);
+            assert_eq!(decoder.take_code(), None);
+        }
+    }
+
+    #[test]
+    fn test_tri_states() {
+        let code = RcCode {
+            code: 0b00_11_01_00,
+            bit_len: 8,
+            pulse_len_us: 350,
+            protocol: 1,
+        };
+        let mut symbols = code.tri_states().unwrap();
+        assert_eq!(symbols.next(), Some(TriState::Zero));
+        assert_eq!(symbols.next(), Some(TriState::One));
+        assert_eq!(symbols.next(), Some(TriState::Floating));
+        assert_eq!(symbols.next(), Some(TriState::Zero));
+        assert_eq!(symbols.next(), None);
+        assert!(
+            RcCode {
+                code: 0b10,
+                bit_len: 2,
+                ..code
+            }
+            .tri_states()
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_transmitter_output_decodes() {
+        let sent = RcCode::from_tri_states(1, "0FFF0FFFFF0F").unwrap();
+        assert_eq!(sent.bit_len, 24);
+        assert_eq!(sent.tri_states().unwrap().nth(1), Some(TriState::Floating));
+        assert!(RcCode::from_tri_states(1, "0FX").is_none());
+        assert!(RcCode::new(0, 1, 1).is_none());
+        assert!(RcCode::new(1, 1, 33).is_none());
+
+        for protocol in [1, 2, 3, 5, 6] {
+            let sent = RcCode::new(protocol, 0x00ab_cdef, 24).unwrap();
+            let mut transmitter = RcTransmitter::new(&sent, 3, 16_000).unwrap();
+            let mut decoder = RcSwitchDecoder::new(16_000);
+            let mut level = false;
+            while !transmitter.is_done() {
+                level = transmitter.tick().unwrap_or(level);
+                decoder.update(level);
+            }
+            decoder.update(!level);
+            // Pulses are whole ticks of 62.5 µs long
+            let received = decoder.take_code().unwrap();
+            assert_eq!(received.with_pulse_len(sent.pulse_len_us), sent);
+            assert!(received.pulse_len_us.abs_diff(sent.pulse_len_us) <= 63);
+        }
+    }
+}
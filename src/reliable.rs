@@ -0,0 +1,584 @@
+//! Acknowledged, retried datagrams on top of [`AskDriver`].
+//!
+//! [`ReliableDatagram`] implements the protocol of RadioHead's `RHReliableDatagram`,
+//! so it interoperates with Arduino nodes using that class:
+//!
+//! - Every unicast message gets a fresh header `id`.
+//! - The receiver answers it with an ACK frame: same `id`, the [`ASK_FLAGS_ACK`](crate::consts::ASK_FLAGS_ACK)
+//!   flag set and a single `!` as payload.
+//! - If no ACK arrives within the timeout, the sender retransmits the message
+//!   with the [`ASK_FLAGS_RETRY`](crate::consts::ASK_FLAGS_RETRY) flag set, up to the configured number of retries.
+//! - Receivers acknowledge retransmissions again but only deliver them once.
+//!
+//! Broadcast messages, and messages to group addresses the driver is
+//! [subscribed](AskDriver::subscribe) to, are sent once and never acknowledged.
+//!
+//! Everything is non-blocking: [`ReliableDatagram::poll()`] is called from the main
+//! loop and reports received messages as well as the delivery outcome of sent ones
+//! as [`ReliableEvent`]s. Timeouts are measured in `tick()` calls.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+//! use ask433::config::AskConfig;
+//! use ask433::driver::AskDriver;
+//! use ask433::reliable::{ReliableDatagram, ReliableEvent};
+//!
+//! # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
+//! # let rx_pin = Pin::new(&[]);
+//! let config = AskConfig::builder().tick_hz(16_000).address(0x01).build().unwrap();
+//! let driver: AskDriver<Pin, Pin, Pin> = AskDriver::new(tx_pin, rx_pin, None, config).unwrap();
+//! let mut manager = ReliableDatagram::new(driver);
+//!
+//! let id = manager.send_to(0x02, b"21.5C").unwrap();
+//! loop {
+//!     // manager.tick() runs every 62.5 µs in the timer interrupt
+//!     match manager.poll() {
+//!         Ok(Some(ReliableEvent::Delivered { id: acked, .. })) if acked == id => break,
+//!         Ok(Some(ReliableEvent::Failed { .. })) => break,
+//!         Ok(Some(ReliableEvent::Received(packet))) => { /* handle packet */ }
+//!         _ => {}
+//!     }
+//!     # break;
+//! }
+//! # let mut driver = manager.into_inner();
+//! # driver.tx.done();
+//! # driver.rx.done();
+//! ```
+
+use crate::consts::{ASK_RX_QUEUE_LEN, ASK_TX_QUEUE_LEN};
+use crate::crc::{Checksum, Crc16Ccitt};
+use crate::driver::AskDriver;
+use crate::encoding::{FourBSixB, LineCode};
+use crate::error::{AskDriverError, AskError};
+use crate::flags::HeaderFlags;
+use crate::format::{FrameFormat, RadioHead};
+use crate::packet::AskPacket;
+use embedded_hal::digital::{InputPin, OutputPin};
+
+/// Default number of retransmissions before a message is reported as failed
+/// (RadioHead's default).
+pub const DEFAULT_RETRIES: u8 = 3;
+
+/// Default ACK timeout in milliseconds (RadioHead's default).
+pub const DEFAULT_TIMEOUT_MS: u32 = 200;
+
+/// Something that happened on the reliable link, returned by [`ReliableDatagram::poll()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReliableEvent {
+    /// A message addressed to this node (or broadcast, or to a subscribed address) was received.
+    /// Unicast messages have already been acknowledged.
+    Received(AskPacket),
+    /// The message with this `id` sent to `to` has been acknowledged.
+    Delivered {
+        /// Destination of the message.
+        to: u8,
+        /// Header id of the message.
+        id: u8,
+    },
+    /// The message with this `id` sent to `to` was not acknowledged after all retries.
+    Failed {
+        /// Destination of the message.
+        to: u8,
+        /// Header id of the message.
+        id: u8,
+    },
+}
+
+/// A message waiting for its ACK.
+#[derive(Debug)]
+struct PendingMessage {
+    packet: AskPacket,
+    retries: u8,
+    sent_at: u32,
+}
+
+/// RadioHead `RHReliableDatagram` compatible ACK/retry layer around an [`AskDriver`].
+///
+/// The wrapper owns the driver. Keep calling [`tick()`](ReliableDatagram::tick) at the
+/// driver's tick rate (when using a timer interrupt, keep the whole `ReliableDatagram`
+/// in the `critical_section` mutex instead of the bare driver), and
+/// [`poll()`](ReliableDatagram::poll) from the main loop.
+///
+/// Only one unicast message can wait for its ACK at a time.
+#[derive(Debug)]
+pub struct ReliableDatagram<
+    TX,
+    RX,
+    PTT,
+    const TXQ: usize = ASK_TX_QUEUE_LEN,
+    const RXQ: usize = ASK_RX_QUEUE_LEN,
+    LC = FourBSixB,
+    CS = Crc16Ccitt,
+    FF = RadioHead,
+    RXEN = PTT,
+    TXEN = PTT,
+> where
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+{
+    driver: AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+    sequence: u8,
+    timeout: u32,
+    retries: u8,
+    pending: Option<PendingMessage>,
+    /// Last header id seen from each source address.
+    seen_ids: [u8; 256],
+
+    /// Counter of retransmitted messages.
+    pub retransmissions: u16,
+
+    /// Counter of retransmissions that were received again and acknowledged,
+    /// but not delivered a second time.
+    pub duplicates: u16,
+}
+
+impl<TX, RX, PTT, const TXQ: usize, const RXQ: usize, LC, CS, FF, RXEN, TXEN>
+    ReliableDatagram<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>
+where
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+{
+    /// Wraps `driver`, with [`DEFAULT_RETRIES`] retries and a timeout of
+    /// [`DEFAULT_TIMEOUT_MS`] at the driver's tick rate.
+    pub fn new(driver: AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>) -> Self {
+        let timeout = driver.tick_hz() / 1000 * DEFAULT_TIMEOUT_MS;
+        Self {
+            driver,
+            sequence: 0,
+            timeout,
+            retries: DEFAULT_RETRIES,
+            pending: None,
+            seen_ids: [0; 256],
+            retransmissions: 0,
+            duplicates: 0,
+        }
+    }
+
+    /// Sets how many `tick()` calls to wait for an ACK before retransmitting.
+    ///
+    /// The timeout starts once the message has left the transmitter.
+    pub fn set_timeout(&mut self, ticks: u32) {
+        self.timeout = ticks;
+    }
+
+    /// Sets how many times an unacknowledged message is retransmitted before it is
+    /// reported as [`ReliableEvent::Failed`].
+    pub fn set_retries(&mut self, retries: u8) {
+        self.retries = retries;
+    }
+
+    /// Returns the wrapped driver.
+    pub fn driver(&self) -> &AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        &self.driver
+    }
+
+    /// Returns the wrapped driver mutably, e.g. to change its address.
+    pub fn driver_mut(&mut self) -> &mut AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        &mut self.driver
+    }
+
+    /// Unwraps the driver. A message waiting for its ACK is forgotten.
+    pub fn into_inner(self) -> AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        self.driver
+    }
+
+    /// Advances the driver by one tick; see [`AskDriver::tick()`].
+    ///
+    /// # Errors
+    /// Any error returned by [`AskDriver::tick()`].
+    pub fn tick(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        self.driver.tick()
+    }
+
+    /// Returns `true` while a unicast message is waiting for its ACK.
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Sends `payload` to `to` with the next message id.
+    ///
+    /// Unicast messages are retransmitted by [`poll()`](ReliableDatagram::poll) until
+    /// they are acknowledged or the retries run out, which `poll()` reports as
+    /// [`ReliableEvent::Delivered`] or [`ReliableEvent::Failed`]. Messages to
+    /// [`BROADCAST_ADDRESS`](crate::consts::BROADCAST_ADDRESS) or a subscribed group address
+    /// are sent once and produce no event.
+    ///
+    /// # Returns
+    /// The header id the message was sent with.
+    ///
+    /// # Errors
+    /// - [`AskError::Busy`] while a previous message is still waiting for its ACK,
+    ///   or the transmit queue is full
+    /// - Any other error returned by [`AskDriver::send_packet()`]
+    pub fn send_to(
+        &mut self,
+        to: u8,
+        payload: &[u8],
+    ) -> Result<u8, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        if self.pending.is_some() {
+            return Err(AskError::Busy);
+        }
+        let mut packet = AskPacket::new(to, self.driver.address());
+        packet.id = self.sequence.wrapping_add(1);
+        #[cfg(feature = "std")]
+        packet.payload.extend_from_slice(payload);
+        #[cfg(not(feature = "std"))]
+        packet
+            .payload
+            .extend_from_slice(payload)
+            .map_err(|_| AskError::MessageTooLong)?;
+
+        self.driver.send_packet(&packet)?;
+        self.sequence = packet.id;
+        if !self.driver.is_group_address(to) {
+            self.pending = Some(PendingMessage {
+                packet,
+                retries: 0,
+                sent_at: self.driver.ticks(),
+            });
+        }
+        Ok(self.sequence)
+    }
+
+    /// Processes received frames and ACK timeouts.
+    ///
+    /// Call this regularly from the main loop. Each call returns at most one event;
+    /// keep calling while it returns `Some`.
+    ///
+    /// # Behavior
+    /// - Unicast messages addressed to this node are acknowledged, then delivered
+    ///   unless they are a retransmission of the message last seen from that sender.
+    ///   Messages for other nodes, accepted in promiscuous mode, are delivered but
+    ///   never acknowledged
+    /// - An ACK matching the message waiting for one completes it
+    /// - Once the timeout has passed without an ACK, the message is retransmitted with
+    ///   [`ASK_FLAGS_RETRY`](crate::consts::ASK_FLAGS_RETRY) set, or reported as failed if the retries are used up
+    ///
+    /// # Errors
+    /// Any pin error of the driver. A full transmit queue is not an error here: a lost
+    /// ACK is recovered by the sender's retransmission, and a retransmission that does
+    /// not fit is tried again on the next call.
+    pub fn poll(
+        &mut self,
+    ) -> Result<Option<ReliableEvent>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        while let Some(packet) = self.driver.receive()? {
+            if packet.flags.is_ack() {
+                if let Some(event) = self.take_ack(&packet) {
+                    return Ok(Some(event));
+                }
+                continue;
+            }
+            if packet.to == self.driver.address() {
+                self.acknowledge(&packet)?;
+            }
+            let from = packet.from as usize;
+            let duplicate = packet.flags.is_retry() && self.seen_ids[from] == packet.id;
+            self.seen_ids[from] = packet.id;
+            if duplicate {
+                self.duplicates = self.duplicates.wrapping_add(1);
+                continue;
+            }
+            return Ok(Some(ReliableEvent::Received(packet)));
+        }
+        self.check_timeout()
+    }
+
+    /// Completes the pending message if `ack` acknowledges it.
+    fn take_ack(&mut self, ack: &AskPacket) -> Option<ReliableEvent> {
+        let pending = self.pending.as_ref()?;
+        if ack.to != self.driver.address()
+            || ack.from != pending.packet.to
+            || ack.id != pending.packet.id
+        {
+            return None;
+        }
+        self.pending = None;
+        Some(ReliableEvent::Delivered {
+            to: ack.from,
+            id: ack.id,
+        })
+    }
+
+    /// Sends the ACK for `packet`.
+    fn acknowledge(
+        &mut self,
+        packet: &AskPacket,
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let mut ack = AskPacket::new(packet.from, self.driver.address());
+        ack.id = packet.id;
+        ack.flags = HeaderFlags::ACK;
+        #[cfg(feature = "std")]
+        ack.payload.push(b'!');
+        #[cfg(not(feature = "std"))]
+        let _ = ack.payload.push(b'!');
+        match self.driver.send_packet(&ack) {
+            Err(AskError::Busy) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Retransmits or fails the pending message once its timeout has passed.
+    fn check_timeout(
+        &mut self,
+    ) -> Result<Option<ReliableEvent>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let now = self.driver.ticks();
+        let tx_busy = self.driver.is_tx_busy();
+        let Some(pending) = self.pending.as_mut() else {
+            return Ok(None);
+        };
+        if tx_busy {
+            // The timeout only runs once the message is off the air
+            pending.sent_at = now;
+            return Ok(None);
+        }
+        if now.wrapping_sub(pending.sent_at) < self.timeout {
+            return Ok(None);
+        }
+        if pending.retries >= self.retries {
+            let event = ReliableEvent::Failed {
+                to: pending.packet.to,
+                id: pending.packet.id,
+            };
+            self.pending = None;
+            return Ok(Some(event));
+        }
+        pending.packet.flags.insert(HeaderFlags::RETRY);
+        match self.driver.send_packet(&pending.packet) {
+            Err(AskError::Busy) => return Ok(None),
+            result => result?,
+        }
+        pending.retries += 1;
+        pending.sent_at = now;
+        self.retransmissions = self.retransmissions.wrapping_add(1);
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AskConfig;
+    use crate::driver::{AddressKind, AskMode, EnablePin, test_packet};
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+
+    type Manager = ReliableDatagram<PinMock, PinMock, PinMock, 4, 4>;
+
+    fn manager(tx: &[PinTransaction], rx: &[PinTransaction]) -> Manager {
+        let config = AskConfig::builder().address(0x01).build().unwrap();
+        let driver = AskDriver::new(PinMock::new(tx), PinMock::new(rx), None, config).unwrap();
+        ReliableDatagram::new(driver)
+    }
+
+    #[test]
+    fn test_send_is_delivered_on_ack() {
+        let low = PinTransaction::set(PinState::Low);
+        let mut manager = manager(&[low.clone(), low.clone(), low], &[]);
+
+        assert_eq!(manager.send_to(0x02, b"Hi"), Ok(1));
+        assert!(manager.is_pending());
+        assert_eq!(manager.send_to(0x02, b"Hi"), Err(AskError::Busy));
+        let sent = manager.driver().sent_packet();
+        assert_eq!(
+            (sent.to, sent.from, sent.id, sent.flags),
+            (0x02, 0x01, 1, HeaderFlags::NONE)
+        );
+
+        manager.driver_mut().set_mode_idle().unwrap();
+        // An ACK for another id is ignored
+        manager
+            .driver_mut()
+            .load_rx_packet(&test_packet(0x01, 0x02, 7, HeaderFlags::ACK, &[b"!"]));
+        assert_eq!(manager.poll(), Ok(None));
+        manager
+            .driver_mut()
+            .load_rx_packet(&test_packet(0x01, 0x02, 1, HeaderFlags::ACK, &[b"!"]));
+        assert_eq!(
+            manager.poll(),
+            Ok(Some(ReliableEvent::Delivered { to: 0x02, id: 1 }))
+        );
+        assert!(!manager.is_pending());
+        assert_eq!(manager.send_to(0x02, b"Hi"), Ok(2));
+        manager.driver_mut().done();
+    }
+
+    #[test]
+    fn test_wraps_driver_with_power_pins_of_their_own_type() {
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let config = AskConfig::builder().address(0x01).build().unwrap();
+        let driver: AskDriver<
+            PinMock,
+            PinMock,
+            PinMock,
+            4,
+            4,
+            FourBSixB,
+            Crc16Ccitt,
+            RadioHead,
+            EnablePin,
+            EnablePin,
+        > = AskDriver::new(tx, PinMock::new(&[]), None, config).unwrap();
+        let mut manager = ReliableDatagram::new(driver);
+        manager
+            .driver_mut()
+            .set_power_pins(Some(EnablePin(false)), Some(EnablePin(false)))
+            .unwrap();
+        assert_eq!(
+            manager.send_to(crate::consts::BROADCAST_ADDRESS, b"Hi"),
+            Ok(1)
+        );
+        assert!(!manager.is_pending());
+
+        let mut driver = manager.into_inner();
+        assert!(driver.rx_enable.as_ref().is_some_and(|pin| pin.0));
+        driver.tx.done();
+        driver.rx.done();
+    }
+
+    #[test]
+    fn test_send_retries_then_fails() {
+        let low = PinTransaction::set(PinState::Low);
+        let rx: [PinTransaction; 20] = core::array::from_fn(|_| PinTransaction::get(PinState::Low));
+        let mut manager = manager(
+            &[low.clone(), low.clone(), low.clone(), low.clone(), low],
+            &rx,
+        );
+        manager.set_timeout(10);
+        manager.set_retries(1);
+
+        let id = manager.send_to(0x02, b"Hi").unwrap();
+        for attempt in 0..2 {
+            // Transmission done, listen for the ACK that never comes
+            manager.driver_mut().set_mode_idle().unwrap();
+            assert_eq!(manager.poll(), Ok(None));
+            assert_eq!(manager.driver().mode, AskMode::Rx);
+            for _ in 0..10 {
+                manager.tick().unwrap();
+            }
+            if attempt == 0 {
+                assert_eq!(manager.poll(), Ok(None));
+                let sent = manager.driver().sent_packet();
+                assert_eq!((sent.id, sent.flags), (id, HeaderFlags::RETRY));
+                assert_eq!(manager.retransmissions, 1);
+            } else {
+                assert_eq!(
+                    manager.poll(),
+                    Ok(Some(ReliableEvent::Failed { to: 0x02, id }))
+                );
+            }
+        }
+        assert!(!manager.is_pending());
+        manager.driver_mut().done();
+    }
+
+    #[test]
+    fn test_receive_acks_and_suppresses_retries() {
+        let low = PinTransaction::set(PinState::Low);
+        let mut manager = manager(&[low.clone(), low.clone(), low.clone(), low], &[]);
+
+        manager.driver_mut().load_rx_packet(&test_packet(
+            0x01,
+            0x10,
+            5,
+            HeaderFlags::NONE,
+            &[b"data"],
+        ));
+        let Ok(Some(ReliableEvent::Received(received))) = manager.poll() else {
+            panic!("message not delivered");
+        };
+        assert_eq!(received.payload.as_slice(), b"data");
+        let ack = manager.driver().sent_packet();
+        assert_eq!(
+            (ack.to, ack.from, ack.id, ack.flags),
+            (0x10, 0x01, 5, HeaderFlags::ACK)
+        );
+        assert_eq!(ack.payload.as_slice(), b"!");
+
+        // The sender missed the ACK and retries: acknowledged again, not delivered
+        manager.driver_mut().set_mode_idle().unwrap();
+        manager.driver_mut().load_rx_packet(&test_packet(
+            0x01,
+            0x10,
+            5,
+            HeaderFlags::RETRY,
+            &[b"data"],
+        ));
+        assert_eq!(manager.poll(), Ok(None));
+        assert_eq!(manager.duplicates, 1);
+        assert_eq!(manager.driver().mode, AskMode::Tx);
+        manager.driver_mut().done();
+    }
+
+    #[test]
+    fn test_group_messages_are_not_acknowledged() {
+        let low = PinTransaction::set(PinState::Low);
+        let mut manager = manager(&[low.clone(), low], &[]);
+        manager
+            .driver_mut()
+            .subscribe(0xe2, AddressKind::Group)
+            .unwrap();
+
+        manager.driver_mut().load_rx_packet(&test_packet(
+            0xe2,
+            0x10,
+            5,
+            HeaderFlags::NONE,
+            &[b"on"],
+        ));
+        assert!(matches!(
+            manager.poll(),
+            Ok(Some(ReliableEvent::Received(_)))
+        ));
+        assert_eq!(manager.driver().tx_queue_len(), 0);
+
+        // Nor does a message sent to the group wait for an ACK
+        assert_eq!(manager.send_to(0xe2, b"off"), Ok(1));
+        assert!(!manager.is_pending());
+        manager.driver_mut().done();
+    }
+
+    #[test]
+    fn test_promiscuous_messages_for_others_are_not_acknowledged() {
+        let low = PinTransaction::set(PinState::Low);
+        let mut manager = manager(&[low.clone(), low.clone(), low.clone(), low], &[]);
+        manager.driver_mut().set_promiscuous(true);
+
+        manager.driver_mut().load_rx_packet(&test_packet(
+            0x05,
+            0x10,
+            5,
+            HeaderFlags::NONE,
+            &[b"data"],
+        ));
+        assert!(matches!(
+            manager.poll(),
+            Ok(Some(ReliableEvent::Received(_)))
+        ));
+        assert_eq!(manager.driver().tx_queue_len(), 0);
+
+        // Nor does an ACK meant for another node complete our message
+        assert_eq!(manager.send_to(0x02, b"Hi"), Ok(1));
+        manager.driver_mut().set_mode_idle().unwrap();
+        manager
+            .driver_mut()
+            .load_rx_packet(&test_packet(0x05, 0x02, 1, HeaderFlags::ACK, &[b"!"]));
+        assert_eq!(manager.poll(), Ok(None));
+        assert!(manager.is_pending());
+        manager.driver_mut().done();
+    }
+}
@@ -0,0 +1,783 @@
+//! Multi-hop routing on top of [`ReliableDatagram`].
+//!
+//! [`Router`] implements the protocol of RadioHead's `RHRouter`, so it shares a
+//! network with Arduino nodes using that class. Every message carries an extra
+//! [`ROUTER_HEADER_LEN`] byte header at the start of the frame payload:
+//!
+//! | Byte | Field    | Meaning |
+//! |------|----------|---------|
+//! | 0    | `dest`   | Final destination |
+//! | 1    | `source` | Original sender |
+//! | 2    | `hops`   | Number of times the message has been forwarded |
+//! | 3    | `id`     | End-to-end message id |
+//! | 4    | `flags`  | End-to-end flags |
+//!
+//! Each hop is a [`ReliableDatagram`] transfer to the next hop found in a static
+//! [`RoutingTable`]. Nodes forward transit messages automatically while they are
+//! polled, up to [`DEFAULT_MAX_HOPS`] hops.
+//!
+//! ## Example
+//!
+//! ```rust
+//! # use embedded_hal_mock::eh1::digital::{Mock as Pin, State as PinState, Transaction as PinTransaction};
+//! use ask433::config::AskConfig;
+//! use ask433::driver::AskDriver;
+//! use ask433::router::{RouteState, Router, RouterEvent};
+//!
+//! # let tx_pin = Pin::new(&[PinTransaction::set(PinState::Low)]);
+//! # let rx_pin = Pin::new(&[]);
+//! let config = AskConfig::builder().address(0x01).build().unwrap();
+//! let driver: AskDriver<Pin, Pin, Pin> = AskDriver::new(tx_pin, rx_pin, None, config).unwrap();
+//! let mut router = Router::new(driver);
+//!
+//! // 0x03 is out of range, but 0x02 can reach it
+//! router.routing_table_mut().add_route_to(0x03, 0x02, RouteState::Valid);
+//! router.send_to(0x03, b"21.5C", 0).unwrap();
+//!
+//! match router.poll() {
+//!     Ok(Some(RouterEvent::Received(message))) => { /* message.source sent message.payload */ }
+//!     Ok(Some(RouterEvent::Failed { dest, .. })) => { /* the next hop to dest didn't answer */ }
+//!     _ => {}
+//! }
+//! # let mut driver = router.into_inner().into_inner();
+//! # driver.tx.done();
+//! # driver.rx.done();
+//! ```
+
+#[cfg(not(feature = "std"))]
+use crate::consts::ASK_MAX_MESSAGE_LEN_USIZE;
+use crate::consts::{ASK_MAX_MESSAGE_LEN, ASK_RX_QUEUE_LEN, ASK_TX_QUEUE_LEN, BROADCAST_ADDRESS};
+use crate::crc::{Checksum, Crc16Ccitt};
+use crate::driver::AskDriver;
+use crate::encoding::{FourBSixB, LineCode};
+use crate::error::{AskDriverError, AskError};
+use crate::format::{FrameFormat, RadioHead};
+use crate::packet::AskPacket;
+use crate::reliable::{ReliableDatagram, ReliableEvent};
+use core::fmt;
+use embedded_hal::digital::{InputPin, OutputPin};
+
+#[cfg(not(feature = "std"))]
+use heapless::Vec;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// Length (in bytes) of the routing header at the start of every routed payload.
+pub const ROUTER_HEADER_LEN: usize = 5;
+
+/// Number of entries in the routing table (RadioHead's `RH_ROUTING_TABLE_SIZE`).
+pub const ROUTING_TABLE_SIZE: usize = 10;
+
+/// Default maximum number of hops before a message is dropped (RadioHead's `RH_DEFAULT_MAX_HOPS`).
+pub const DEFAULT_MAX_HOPS: u8 = 30;
+
+/// State of a [`RouteEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteState {
+    /// A route is being looked for, but none is known yet.
+    Discovering,
+    /// The route can be used.
+    Valid,
+}
+
+/// A single route: messages for `dest` are passed to `next_hop`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteEntry {
+    /// Final destination.
+    pub dest: u8,
+    /// Neighbour to pass messages for `dest` to.
+    pub next_hop: u8,
+    /// Whether the route can be used.
+    pub state: RouteState,
+}
+
+/// A fixed-size table of routes, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct RoutingTable {
+    routes: [Option<RouteEntry>; ROUTING_TABLE_SIZE],
+}
+
+impl RoutingTable {
+    /// Adds a route to `dest` via `next_hop`, or updates the existing one.
+    ///
+    /// If the table is full, the oldest route is retired to make room.
+    pub fn add_route_to(&mut self, dest: u8, next_hop: u8, state: RouteState) {
+        let entry = RouteEntry {
+            dest,
+            next_hop,
+            state,
+        };
+        if let Some(slot) = self.routes.iter_mut().flatten().find(|r| r.dest == dest) {
+            *slot = entry;
+            return;
+        }
+        if self.routes[ROUTING_TABLE_SIZE - 1].is_some() {
+            self.retire_oldest_route();
+        }
+        if let Some(slot) = self.routes.iter_mut().find(|r| r.is_none()) {
+            *slot = Some(entry);
+        }
+    }
+
+    /// Returns the route to `dest`, if there is one.
+    pub fn get_route_to(&self, dest: u8) -> Option<&RouteEntry> {
+        self.routes.iter().flatten().find(|r| r.dest == dest)
+    }
+
+    /// Removes the route to `dest`. Returns `false` if there was none.
+    pub fn delete_route_to(&mut self, dest: u8) -> bool {
+        match self
+            .routes
+            .iter()
+            .position(|r| r.is_some_and(|r| r.dest == dest))
+        {
+            Some(index) => {
+                self.delete_route(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the oldest route.
+    pub fn retire_oldest_route(&mut self) {
+        self.delete_route(0);
+    }
+
+    /// Removes every route.
+    pub fn clear(&mut self) {
+        self.routes = [None; ROUTING_TABLE_SIZE];
+    }
+
+    /// Iterates over the routes, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &RouteEntry> {
+        self.routes.iter().flatten()
+    }
+
+    /// Removes the route at `index`, keeping the others in order.
+    fn delete_route(&mut self, index: usize) {
+        self.routes[index..].rotate_left(1);
+        self.routes[ROUTING_TABLE_SIZE - 1] = None;
+    }
+}
+
+/// A message with its routing header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutedMessage {
+    /// Final destination.
+    pub dest: u8,
+    /// Original sender.
+    pub source: u8,
+    /// Number of times the message has been forwarded.
+    pub hops: u8,
+    /// End-to-end message id.
+    pub id: u8,
+    /// End-to-end flags.
+    pub flags: u8,
+    /// The user data carried by the message.
+    #[cfg(not(feature = "std"))]
+    pub payload: Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE>,
+    /// The user data carried by the message.
+    #[cfg(feature = "std")]
+    pub payload: Vec<u8>,
+    /// Neighbour the message was received from.
+    ///
+    /// Ignored when sending.
+    pub last_hop: u8,
+}
+
+impl RoutedMessage {
+    /// Splits the routing header off a received packet's payload.
+    ///
+    /// Returns `None` if the payload is too short to hold the header.
+    pub fn from_packet(packet: &AskPacket) -> Option<Self> {
+        let [dest, source, hops, id, flags, ref data @ ..] = *packet.payload.as_slice() else {
+            return None;
+        };
+        Some(Self {
+            dest,
+            source,
+            hops,
+            id,
+            flags,
+            #[cfg(feature = "std")]
+            payload: Vec::from(data),
+            #[cfg(not(feature = "std"))]
+            payload: Vec::from_slice(data).ok()?,
+            last_hop: packet.from,
+        })
+    }
+
+    /// Writes the routing header and payload into `buf` and returns the used length.
+    ///
+    /// Returns `None` if they don't fit.
+    fn write_to(&self, buf: &mut [u8]) -> Option<usize> {
+        let len = ROUTER_HEADER_LEN + self.payload.len();
+        let frame = buf.get_mut(..len)?;
+        frame[..ROUTER_HEADER_LEN].copy_from_slice(&[
+            self.dest,
+            self.source,
+            self.hops,
+            self.id,
+            self.flags,
+        ]);
+        frame[ROUTER_HEADER_LEN..].copy_from_slice(&self.payload);
+        Some(len)
+    }
+}
+
+/// Something that happened on the routed network, returned by [`Router::poll()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouterEvent {
+    /// A message addressed to this node (or broadcast) arrived.
+    Received(RoutedMessage),
+    /// The next hop acknowledged a message sent with [`Router::send_to()`].
+    Delivered {
+        /// Final destination of the message.
+        dest: u8,
+        /// End-to-end id of the message.
+        id: u8,
+    },
+    /// The next hop did not acknowledge a message sent with [`Router::send_to()`].
+    Failed {
+        /// Final destination of the message.
+        dest: u8,
+        /// End-to-end id of the message.
+        id: u8,
+    },
+    /// A transit message could not be passed on, because there was no route or the
+    /// next hop did not acknowledge it.
+    ForwardFailed {
+        /// Final destination of the message.
+        dest: u8,
+        /// Original sender of the message.
+        source: u8,
+        /// Neighbour the message was received from.
+        last_hop: u8,
+    },
+}
+
+/// What the message waiting for a hop-level ACK is.
+#[derive(Debug, Clone, Copy)]
+enum InFlight {
+    Local { dest: u8, id: u8 },
+    Forward { dest: u8, source: u8, last_hop: u8 },
+}
+
+/// Errors returned when sending a routed message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouterError<E> {
+    /// No valid route to the destination is known.
+    NoRoute,
+    /// The message could not be sent to the next hop.
+    Driver(E),
+}
+
+impl<TXE, RXE, PTTE, RXENE, TXENE> From<AskError<TXE, RXE, PTTE, RXENE, TXENE>>
+    for RouterError<AskError<TXE, RXE, PTTE, RXENE, TXENE>>
+{
+    fn from(e: AskError<TXE, RXE, PTTE, RXENE, TXENE>) -> Self {
+        RouterError::Driver(e)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for RouterError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouterError::NoRoute => write!(f, "no route to destination"),
+            RouterError::Driver(e) => write!(f, "message not sent: {e}"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> core::error::Error for RouterError<E> {}
+
+/// The [`RouterError`] type produced by a router around an `AskDriver<TX, RX, PTT>`,
+/// or around one with enable pins of their own types.
+pub type RouterDriverError<TX, RX, PTT, RXEN = PTT, TXEN = PTT> =
+    RouterError<AskDriverError<TX, RX, PTT, RXEN, TXEN>>;
+
+/// RadioHead `RHRouter` compatible multi-hop layer around a [`ReliableDatagram`].
+///
+/// Call [`tick()`](Router::tick) at the driver's tick rate and [`poll()`](Router::poll)
+/// from the main loop; transit messages are only forwarded while `poll()` runs.
+///
+/// One transit message can wait while the link is busy with another message;
+/// further ones are dropped and counted in [`dropped`](Router::dropped).
+#[derive(Debug)]
+pub struct Router<
+    TX,
+    RX,
+    PTT,
+    const TXQ: usize = ASK_TX_QUEUE_LEN,
+    const RXQ: usize = ASK_RX_QUEUE_LEN,
+    LC = FourBSixB,
+    CS = Crc16Ccitt,
+    FF = RadioHead,
+    RXEN = PTT,
+    TXEN = PTT,
+> where
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+{
+    reliable: ReliableDatagram<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+    table: RoutingTable,
+    max_hops: u8,
+    sequence: u8,
+    in_flight: Option<InFlight>,
+    waiting_forward: Option<RoutedMessage>,
+
+    /// Counter of transit messages passed on to the next hop.
+    pub forwarded: u16,
+
+    /// Counter of transit messages dropped because they had travelled too many hops
+    /// or the link was busy.
+    pub dropped: u16,
+}
+
+impl<TX, RX, PTT, const TXQ: usize, const RXQ: usize, LC, CS, FF, RXEN, TXEN>
+    Router<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>
+where
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+{
+    /// Wraps `driver` in a [`ReliableDatagram`] with an empty routing table.
+    ///
+    /// The node's address is the driver's; change it with [`AskDriver::set_address()`].
+    pub fn new(driver: AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>) -> Self {
+        Self {
+            reliable: ReliableDatagram::new(driver),
+            table: RoutingTable::default(),
+            max_hops: DEFAULT_MAX_HOPS,
+            sequence: 0,
+            in_flight: None,
+            waiting_forward: None,
+            forwarded: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Sets the number of hops after which transit messages are dropped.
+    pub fn set_max_hops(&mut self, max_hops: u8) {
+        self.max_hops = max_hops;
+    }
+
+    /// Returns the number of hops after which transit messages are dropped.
+    pub fn max_hops(&self) -> u8 {
+        self.max_hops
+    }
+
+    /// Returns the routing table.
+    pub fn routing_table(&self) -> &RoutingTable {
+        &self.table
+    }
+
+    /// Returns the routing table mutably, e.g. to add static routes.
+    pub fn routing_table_mut(&mut self) -> &mut RoutingTable {
+        &mut self.table
+    }
+
+    /// Returns the wrapped [`ReliableDatagram`], e.g. to change its timeout.
+    pub fn reliable_mut(
+        &mut self,
+    ) -> &mut ReliableDatagram<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        &mut self.reliable
+    }
+
+    /// Returns the driver.
+    pub fn driver(&self) -> &AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        self.reliable.driver()
+    }
+
+    /// Returns the driver mutably.
+    pub fn driver_mut(&mut self) -> &mut AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        self.reliable.driver_mut()
+    }
+
+    /// Unwraps the [`ReliableDatagram`].
+    pub fn into_inner(self) -> ReliableDatagram<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
+        self.reliable
+    }
+
+    /// Advances the driver by one tick; see [`AskDriver::tick()`].
+    ///
+    /// # Errors
+    /// Any error returned by [`AskDriver::tick()`].
+    pub fn tick(&mut self) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        self.reliable.tick()
+    }
+
+    /// Sends `payload` to `dest`, via the next hop from the routing table.
+    ///
+    /// Messages to [`BROADCAST_ADDRESS`] go to all neighbours and are not forwarded.
+    /// The outcome of unicast messages is reported by [`poll()`](Router::poll) as
+    /// [`RouterEvent::Delivered`] or [`RouterEvent::Failed`].
+    ///
+    /// # Returns
+    /// The end-to-end id the message was sent with.
+    ///
+    /// # Errors
+    /// - [`RouterError::NoRoute`] if there is no valid route to `dest`
+    /// - [`RouterError::Driver`] with [`AskError::MessageTooLong`] if `payload` plus the routing header exceeds
+    ///   the driver's [`max_message_len()`](AskDriver::max_message_len)
+    /// - [`RouterError::Driver`] with any error returned by [`ReliableDatagram::send_to()`]
+    pub fn send_to(
+        &mut self,
+        dest: u8,
+        payload: &[u8],
+        flags: u8,
+    ) -> Result<u8, RouterDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let source = self.driver().address();
+        self.send_from_source(dest, source, payload, flags)
+    }
+
+    /// Like [`send_to()`](Router::send_to), but with `source` in the routing header
+    /// instead of this node's address, for re-broadcasting on another node's behalf.
+    pub(crate) fn send_from_source(
+        &mut self,
+        dest: u8,
+        source: u8,
+        payload: &[u8],
+        flags: u8,
+    ) -> Result<u8, RouterDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let next_hop = self.next_hop(dest).ok_or(RouterError::NoRoute)?;
+        let mut message = RoutedMessage {
+            dest,
+            source,
+            hops: 0,
+            id: self.sequence.wrapping_add(1),
+            flags,
+            payload: Vec::new(),
+            last_hop: 0,
+        };
+        #[cfg(feature = "std")]
+        message.payload.extend_from_slice(payload);
+        #[cfg(not(feature = "std"))]
+        message
+            .payload
+            .extend_from_slice(payload)
+            .map_err(|_| AskError::MessageTooLong)?;
+
+        self.send_via(next_hop, &message)?;
+        self.sequence = message.id;
+        if next_hop != BROADCAST_ADDRESS {
+            self.in_flight = Some(InFlight::Local {
+                dest,
+                id: message.id,
+            });
+        }
+        Ok(message.id)
+    }
+
+    /// Receives, forwards and acknowledges messages and reports the outcome of sent ones.
+    ///
+    /// Call this regularly from the main loop. Each call returns at most one event;
+    /// keep calling while it returns `Some`.
+    ///
+    /// # Errors
+    /// Any pin error of the driver.
+    pub fn poll(&mut self) -> Result<Option<RouterEvent>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        self.poll_with(|_, _| {})
+    }
+
+    /// Like [`poll()`](Router::poll), but shows every received message, transit ones
+    /// included, to `peek` before it is delivered or forwarded.
+    pub(crate) fn poll_with(
+        &mut self,
+        mut peek: impl FnMut(&RoutedMessage, &mut RoutingTable),
+    ) -> Result<Option<RouterEvent>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        while let Some(event) = self.reliable.poll()? {
+            let event = match event {
+                ReliableEvent::Received(packet) => {
+                    let Some(message) = RoutedMessage::from_packet(&packet) else {
+                        continue;
+                    };
+                    peek(&message, &mut self.table);
+                    self.route_received(message)?
+                }
+                ReliableEvent::Delivered { .. } => match self.in_flight.take() {
+                    Some(InFlight::Local { dest, id }) => Some(RouterEvent::Delivered { dest, id }),
+                    Some(InFlight::Forward { .. }) => {
+                        self.forwarded = self.forwarded.wrapping_add(1);
+                        None
+                    }
+                    None => None,
+                },
+                ReliableEvent::Failed { .. } => match self.in_flight.take() {
+                    Some(InFlight::Local { dest, id }) => Some(RouterEvent::Failed { dest, id }),
+                    Some(InFlight::Forward {
+                        dest,
+                        source,
+                        last_hop,
+                    }) => Some(RouterEvent::ForwardFailed {
+                        dest,
+                        source,
+                        last_hop,
+                    }),
+                    None => None,
+                },
+            };
+            if event.is_some() {
+                return Ok(event);
+            }
+        }
+
+        if !self.reliable.is_pending()
+            && let Some(message) = self.waiting_forward.take()
+        {
+            return self.forward(message);
+        }
+        Ok(None)
+    }
+
+    /// Delivers `message` here, or passes it on towards its destination.
+    fn route_received(
+        &mut self,
+        mut message: RoutedMessage,
+    ) -> Result<Option<RouterEvent>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let this_address = self.driver().address();
+        if message.dest == this_address || message.dest == BROADCAST_ADDRESS {
+            return Ok(Some(RouterEvent::Received(message)));
+        }
+        if message.hops >= self.max_hops {
+            self.dropped = self.dropped.wrapping_add(1);
+            return Ok(None);
+        }
+        message.hops += 1;
+        if self.reliable.is_pending() {
+            // The link is busy, keep the message until it is free
+            if self.waiting_forward.is_some() {
+                self.dropped = self.dropped.wrapping_add(1);
+            } else {
+                self.waiting_forward = Some(message);
+            }
+            return Ok(None);
+        }
+        self.forward(message)
+    }
+
+    /// Sends a transit message on to its next hop.
+    fn forward(
+        &mut self,
+        message: RoutedMessage,
+    ) -> Result<Option<RouterEvent>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let failed = RouterEvent::ForwardFailed {
+            dest: message.dest,
+            source: message.source,
+            last_hop: message.last_hop,
+        };
+        let Some(next_hop) = self.next_hop(message.dest) else {
+            return Ok(Some(failed));
+        };
+        match self.send_via(next_hop, &message) {
+            Ok(()) => {}
+            Err(AskError::Busy) => {
+                self.dropped = self.dropped.wrapping_add(1);
+                return Ok(None);
+            }
+            Err(AskError::MessageTooLong) => return Ok(Some(failed)),
+            Err(e) => return Err(e),
+        }
+        self.in_flight = Some(InFlight::Forward {
+            dest: message.dest,
+            source: message.source,
+            last_hop: message.last_hop,
+        });
+        Ok(None)
+    }
+
+    /// The neighbour to pass messages for `dest` to, if there is a valid route.
+    fn next_hop(&self, dest: u8) -> Option<u8> {
+        if dest == BROADCAST_ADDRESS {
+            return Some(BROADCAST_ADDRESS);
+        }
+        self.table
+            .get_route_to(dest)
+            .filter(|route| route.state == RouteState::Valid)
+            .map(|route| route.next_hop)
+    }
+
+    fn send_via(
+        &mut self,
+        next_hop: u8,
+        message: &RoutedMessage,
+    ) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+        let mut buf = [0; ASK_MAX_MESSAGE_LEN as usize];
+        let max_len = usize::from(self.driver().max_message_len());
+        let len = message
+            .write_to(&mut buf[..max_len])
+            .ok_or(AskError::MessageTooLong)?;
+        self.reliable.send_to(next_hop, &buf[..len]).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AskConfig;
+    use crate::driver::test_packet;
+    use crate::flags::HeaderFlags;
+    use embedded_hal_mock::eh1::digital::{
+        Mock as PinMock, State as PinState, Transaction as PinTransaction,
+    };
+
+    type TestRouter = Router<PinMock, PinMock, PinMock, 4, 4>;
+
+    fn router(address: u8, tx_lows: usize) -> TestRouter {
+        let tx: [PinTransaction; 8] = core::array::from_fn(|_| PinTransaction::set(PinState::Low));
+        let config = AskConfig::builder().address(address).build().unwrap();
+        let driver = AskDriver::new(
+            PinMock::new(&tx[..tx_lows]),
+            PinMock::new(&[]),
+            None,
+            config,
+        )
+        .unwrap();
+        Router::new(driver)
+    }
+
+    #[test]
+    fn test_routing_table_updates_and_retires_oldest() {
+        let mut table = RoutingTable::default();
+        table.add_route_to(0x10, 0x02, RouteState::Valid);
+        table.add_route_to(0x10, 0x03, RouteState::Valid);
+        assert_eq!(table.iter().count(), 1);
+        assert_eq!(table.get_route_to(0x10).unwrap().next_hop, 0x03);
+
+        for dest in 0..ROUTING_TABLE_SIZE as u8 {
+            table.add_route_to(0x20 + dest, 0x02, RouteState::Discovering);
+        }
+        // The route to 0x10 was the oldest
+        assert!(table.get_route_to(0x10).is_none());
+        assert_eq!(table.iter().count(), ROUTING_TABLE_SIZE);
+        assert_eq!(table.iter().next().unwrap().dest, 0x20);
+
+        assert!(table.delete_route_to(0x25));
+        assert!(!table.delete_route_to(0x25));
+        assert_eq!(table.iter().count(), ROUTING_TABLE_SIZE - 1);
+        table.clear();
+        assert_eq!(table.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_send_uses_next_hop() {
+        let mut router = router(0x01, 3);
+        assert_eq!(router.send_to(0x03, b"Hi", 0), Err(RouterError::NoRoute));
+        router
+            .routing_table_mut()
+            .add_route_to(0x03, 0x02, RouteState::Discovering);
+        assert_eq!(router.send_to(0x03, b"Hi", 0), Err(RouterError::NoRoute));
+
+        router
+            .routing_table_mut()
+            .add_route_to(0x03, 0x02, RouteState::Valid);
+        assert_eq!(router.send_to(0x03, b"Hi", 0x05), Ok(1));
+        let sent = router.driver().sent_packet();
+        assert_eq!((sent.to, sent.from), (0x02, 0x01));
+        assert_eq!(
+            sent.payload.as_slice(),
+            &[0x03, 0x01, 0, 1, 0x05, b'H', b'i']
+        );
+
+        // The next hop acknowledges
+        router.driver_mut().set_mode_idle().unwrap();
+        let mut ack = AskPacket::new(0x01, 0x02);
+        ack.id = 1;
+        ack.flags = HeaderFlags::ACK;
+        router.driver_mut().load_rx_packet(&ack);
+        assert_eq!(
+            router.poll(),
+            Ok(Some(RouterEvent::Delivered { dest: 0x03, id: 1 }))
+        );
+        router.driver_mut().done();
+    }
+
+    #[test]
+    fn test_receives_own_and_forwards_transit_messages() {
+        let mut router = router(0x02, 4);
+        router
+            .routing_table_mut()
+            .add_route_to(0x03, 0x04, RouteState::Valid);
+
+        router.driver_mut().load_rx_packet(&test_packet(
+            0x02,
+            0x01,
+            9,
+            HeaderFlags::NONE,
+            &[&[0x02, 0x01, 1, 7, 0], b"me"],
+        ));
+        let Ok(Some(RouterEvent::Received(message))) = router.poll() else {
+            panic!("message not delivered");
+        };
+        assert_eq!((message.source, message.hops, message.id), (0x01, 1, 7));
+        assert_eq!(message.last_hop, 0x01);
+        assert_eq!(message.payload.as_slice(), b"me");
+
+        // A message for 0x03 is passed on to 0x04 with the hop count raised
+        router.driver_mut().set_mode_idle().unwrap();
+        router.driver_mut().load_rx_packet(&test_packet(
+            0x02,
+            0x01,
+            9,
+            HeaderFlags::NONE,
+            &[&[0x03, 0x01, 0, 8, 0], b"you"],
+        ));
+        assert_eq!(router.poll(), Ok(None));
+        let sent = router.driver().sent_packet();
+        assert_eq!((sent.to, sent.from), (0x04, 0x02));
+        assert_eq!(
+            sent.payload.as_slice(),
+            &[0x03, 0x01, 1, 8, 0, b'y', b'o', b'u']
+        );
+        router.driver_mut().done();
+    }
+
+    #[test]
+    fn test_forward_without_route_fails() {
+        let mut router = router(0x02, 4);
+        router.driver_mut().load_rx_packet(&test_packet(
+            0x02,
+            0x01,
+            9,
+            HeaderFlags::NONE,
+            &[&[0x05, 0x01, 0, 8, 0], b""],
+        ));
+        assert_eq!(
+            router.poll(),
+            Ok(Some(RouterEvent::ForwardFailed {
+                dest: 0x05,
+                source: 0x01,
+                last_hop: 0x01
+            }))
+        );
+
+        // Messages that travelled too far are dropped
+        router.driver_mut().set_mode_idle().unwrap();
+        router.set_max_hops(3);
+        router
+            .routing_table_mut()
+            .add_route_to(0x05, 0x04, RouteState::Valid);
+        router.driver_mut().load_rx_packet(&test_packet(
+            0x02,
+            0x01,
+            9,
+            HeaderFlags::NONE,
+            &[&[0x05, 0x01, 3, 9, 0], b""],
+        ));
+        assert_eq!(router.poll(), Ok(None));
+        assert_eq!(router.dropped, 1);
+        router.driver_mut().done();
+    }
+}
@@ -0,0 +1,121 @@
+//! Link statistics.
+//!
+//! [`AskStats`] is a snapshot of every counter kept by an
+//! [`AskDriver`](crate::driver::AskDriver), with receive errors broken down by
+//! cause. All counters are `u32` and saturate instead of wrapping, so a counter
+//! that reads `u32::MAX` has overflowed.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use ask433::stats::AskStats;
+//!
+//! let stats = AskStats {
+//!     rx_good: 40,
+//!     rx_crc_errors: 3,
+//!     rx_bad_length: 1,
+//!     ..AskStats::default()
+//! };
+//! assert_eq!(stats.rx_errors(), 4);
+//! ```
+
+use crate::pll::FrameDrop;
+
+/// Counters of a driver's transmit and receive activity.
+///
+/// Read with [`AskDriver::stats()`](crate::driver::AskDriver::stats) and cleared with
+/// [`AskDriver::reset_stats()`](crate::driver::AskDriver::reset_stats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AskStats {
+    /// Frames transmitted in full.
+    pub tx_good: u32,
+
+    /// Random backoffs taken by CSMA because the channel was busy.
+    pub csma_backoffs: u32,
+
+    /// Frames dropped by CSMA because the channel stayed busy for every attempt.
+    pub csma_failures: u32,
+
+    /// Channel activity detection windows that found the channel busy.
+    pub cad_busy: u32,
+
+    /// Channel activity detection windows that found the channel clear.
+    pub cad_clear: u32,
+
+    /// Frames received, validated and queued for [`receive()`](crate::driver::AskDriver::receive).
+    pub rx_good: u32,
+
+    /// Frames dropped because their CRC did not match.
+    pub rx_crc_errors: u32,
+
+    /// Frames dropped because their length byte was out of range.
+    pub rx_bad_length: u32,
+
+    /// Frames aborted because they contained an invalid 6-bit symbol.
+    pub rx_bad_symbols: u32,
+
+    /// Frames aborted because they did not fit the receive buffer.
+    pub rx_overruns: u32,
+
+    /// Frames aborted because forward error correction found a codeword with
+    /// more flipped bits than it can correct.
+    pub rx_uncorrectable: u32,
+
+    /// Bits corrected by forward error correction.
+    pub rx_corrected_bits: u32,
+
+    /// Valid frames dropped because they were addressed to another node.
+    /// Never incremented in promiscuous mode.
+    pub rx_filtered: u32,
+
+    /// Valid frames dropped by the duplicate filter.
+    pub rx_duplicates: u32,
+
+    /// Valid frames dropped because the receive queue was full.
+    pub rx_queue_overflows: u32,
+}
+
+impl AskStats {
+    /// Frames lost to reception errors: CRC failures, bad length bytes, invalid
+    /// symbols, buffer overruns and uncorrectable FEC codewords.
+    ///
+    /// Frames dropped on purpose (filtered or duplicates) and receive queue
+    /// overflows are not counted.
+    pub fn rx_errors(&self) -> u32 {
+        self.rx_crc_errors
+            .saturating_add(self.rx_bad_length)
+            .saturating_add(self.rx_bad_symbols)
+            .saturating_add(self.rx_overruns)
+            .saturating_add(self.rx_uncorrectable)
+    }
+
+    /// Counts a frame aborted by the PLL.
+    pub(crate) fn record_drop(&mut self, drop: FrameDrop) {
+        let counter = match drop {
+            FrameDrop::BadLength => &mut self.rx_bad_length,
+            FrameDrop::BadSymbol => &mut self.rx_bad_symbols,
+            FrameDrop::Overrun => &mut self.rx_overruns,
+            FrameDrop::Uncorrectable => &mut self.rx_uncorrectable,
+        };
+        *counter = counter.saturating_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_drop_saturates() {
+        let mut stats = AskStats {
+            rx_overruns: u32::MAX,
+            ..AskStats::default()
+        };
+        stats.record_drop(FrameDrop::BadLength);
+        stats.record_drop(FrameDrop::BadSymbol);
+        stats.record_drop(FrameDrop::Overrun);
+        assert_eq!((stats.rx_bad_length, stats.rx_bad_symbols), (1, 1));
+        assert_eq!(stats.rx_overruns, u32::MAX);
+        assert_eq!(stats.rx_errors(), u32::MAX);
+    }
+}
@@ -1,4 +1,8 @@
+use crate::crc::Checksum;
 use crate::driver::AskDriver;
+use crate::encoding::LineCode;
+use crate::error::AskDriverError;
+use crate::format::FrameFormat;
 use embedded_hal::delay::DelayNs;
 use embedded_hal::digital::{InputPin, OutputPin};
 
@@ -19,39 +23,58 @@ use embedded_hal::digital::{InputPin, OutputPin};
 /// # use embedded_hal_mock::eh1::digital::{
 /// #     Mock as Pin, State as PinState, Transaction as PinTransaction,
 /// # };
+/// use ask433::config::AskConfig;
 /// use ask433::driver::AskDriver;
 /// use ask433::timer::run_ask_tick_loop;
 /// # let tx = Pin::new(&[PinTransaction::set(PinState::Low)]);
 /// # let rx = Pin::new(&[]);
-/// let mut driver: AskDriver<Pin, Pin, Pin> = AskDriver::new(tx, rx, None, 8, None, None);
+/// let mut driver: AskDriver<Pin, Pin, Pin> = AskDriver::new(tx, rx, None, AskConfig::default()).unwrap();
 /// let mut delay = Delay::new();
-/// run_ask_tick_loop(&mut driver, &mut delay, 63);
+/// run_ask_tick_loop(&mut driver, &mut delay, 63).unwrap();
 /// # driver.tx.done();
 /// # driver.rx.done();
 /// ```
 ///
+/// # Errors
+/// Returns any error raised by [`AskDriver::tick()`]. The delay is skipped in that case.
+///
 /// # Notes
 /// - This loop will never return; it is intended for single-purpose polling firmware.
 /// - For more efficient or concurrent applications, prefer interrupt-driven tick scheduling.
 /// - `delay.delay_us()` errors are ignored, which is acceptable in typical HALs where
 ///   the only error case is an uninitialized peripheral or transient underrun.
-pub fn run_ask_tick_loop<D, TX, RX, PTT>(
-    driver: &mut AskDriver<TX, RX, PTT>,
+pub fn run_ask_tick_loop<
+    D,
+    TX,
+    RX,
+    PTT,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    driver: &mut AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
     delay: &mut D,
     tick_us: u32,
-) where
+) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>>
+where
     D: DelayNs,
     TX: OutputPin,
     RX: InputPin,
     PTT: OutputPin,
 {
-    driver.tick();
+    driver.tick()?;
     delay.delay_us(tick_us);
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::AskConfig;
     use embedded_hal_mock::eh1::delay::NoopDelay as MockDelay;
     use embedded_hal_mock::eh1::digital::{
         Mock as PinMock, State as PinState, Transaction as PinTransaction,
@@ -64,12 +87,13 @@ mod tests {
         let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
         let rx = PinMock::new(&[]);
         let ptt = PinMock::new(&[]);
-        let mut driver = AskDriver::new(tx, rx, Some(ptt), 8, Some(false), Some(false));
+        let mut driver: AskDriver<PinMock, PinMock, PinMock> =
+            AskDriver::new(tx, rx, Some(ptt), AskConfig::default()).unwrap();
         let mut delay = MockDelay::new();
 
         // Just call tick manually to simulate one loop iteration
         delay.delay_us(63);
-        run_ask_tick_loop(&mut driver, &mut delay, 63);
+        run_ask_tick_loop(&mut driver, &mut delay, 63).unwrap();
         driver.tx.done();
         driver.rx.done();
         let _ = driver.ptt.map(|mut ptt| ptt.done());
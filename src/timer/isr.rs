@@ -1,11 +1,33 @@
-#[cfg(not(feature = "std"))]
-use crate::consts::ASK_MAX_MESSAGE_LEN_USIZE;
+use crate::config::AskConfig;
+use crate::consts::{ASK_RX_QUEUE_LEN, ASK_TX_QUEUE_LEN};
+use crate::crc::{Checksum, Crc16Ccitt};
 use crate::driver::AskDriver;
+use crate::encoding::{FourBSixB, LineCode};
+use crate::error::{AskDriverError, AskError};
+use crate::format::{FrameFormat, RadioHead};
+use crate::packet::AskPacket;
+use crate::rcswitch::RcCode;
+use crate::stats::AskStats;
 use core::cell::RefCell;
 use critical_section::Mutex;
 use embedded_hal::digital::{InputPin, OutputPin};
-#[cfg(not(feature = "std"))]
-use heapless::Vec;
+
+/// A global `AskDriver` slot shared between the main thread and an ISR.
+///
+/// This is the type created by [`global_ask_driver_init`] and
+/// [`init_ask_driver!`](crate::init_ask_driver).
+pub type GlobalAskDriver<
+    TX,
+    RX,
+    PTT,
+    const TXQ: usize = ASK_TX_QUEUE_LEN,
+    const RXQ: usize = ASK_RX_QUEUE_LEN,
+    LC = FourBSixB,
+    CS = Crc16Ccitt,
+    FF = RadioHead,
+    RXEN = PTT,
+    TXEN = PTT,
+> = Mutex<RefCell<Option<AskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>>>>;
 
 /// Used to initialize the global static `AskDriver` for use with
 /// `critical_section`.
@@ -23,10 +45,20 @@ use heapless::Vec;
 /// use ask433::timer::global_ask_driver_init;
 ///
 /// static ASK_DRIVER: Mutex<RefCell<Option<AskDriver<Pin, Pin, Pin>>>> =
-///     global_ask_driver_init::<Pin, Pin, Pin>();
+///     global_ask_driver_init();
 /// ```
-pub const fn global_ask_driver_init<TX: OutputPin, RX: InputPin, PTT: OutputPin>()
--> Mutex<RefCell<Option<AskDriver<TX, RX, PTT>>>> {
+pub const fn global_ask_driver_init<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>() -> GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN> {
     Mutex::new(RefCell::new(None))
 }
 
@@ -36,15 +68,22 @@ pub const fn global_ask_driver_init<TX: OutputPin, RX: InputPin, PTT: OutputPin>
 /// * The global static `AskDriver`
 /// * The tx pin
 /// * The rx pin
-/// * The number of ticks per bit such that:
-///     `interrupt frequency / ticks per bit = 2000 bits per second`
-///     e.g. For the Atmega328P with an interrupt frequency of `~62.5µs`:
-///         ```rust
-///         // 8 ticks / bit = 1 second / 2000 bits * 1 tick / 6.25e-5 seconds
-///         const TICKS_PER_BIT: u8 = (1/2000)*(1/625e-7_f32) as u8
-///         ```
+/// * The optional PTT pin
+/// * The [`AskConfig`] for the driver. Its ticks per bit should satisfy
+///   `interrupt frequency / ticks per bit = bit rate` (2000 bits per second by default)
+///   e.g. For the Atmega328P with an interrupt frequency of `~62.5µs`:
+///   ```rust
+///   // 8 ticks / bit = 1 second / 2000 bits * 1 tick / 6.25e-5 seconds
+///   const TICKS_PER_BIT: u8 = ((1.0 / 2000.0) * (1.0 / 625e-7_f32)) as u8;
+///   ```
+///
+/// # Errors
+/// Returns the pin error if the new driver could not drive its pins into the idle state.
+/// The global slot is left untouched in that case.
+///
 ///# Example
 /// ```rust
+/// use ask433::config::AskConfig;
 /// use ask433::driver::AskDriver;
 /// use core::cell::RefCell;
 /// use critical_section::Mutex;
@@ -53,45 +92,53 @@ pub const fn global_ask_driver_init<TX: OutputPin, RX: InputPin, PTT: OutputPin>
 /// use ask433::timer::{global_ask_driver_init, global_ask_driver_setup};
 ///
 /// static ASK_DRIVER: Mutex<RefCell<Option<AskDriver<Pin, Pin, Pin>>>> =
-///     global_ask_driver_init::<Pin, Pin, Pin>();
-///
-/// fn main() {
-///     # let tx = Pin::new(&[PinTransaction::set(PinState::Low)]);
-///     # let rx = Pin::new(&[]);
-///     global_ask_driver_setup::<Pin, Pin, Pin>(&ASK_DRIVER, tx, rx, None, 8, None, None);
-///     # critical_section::with(|cs| {
-///     #    if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
-///     #       driver.tx.done();
-///     #       driver.rx.done();
-///     #   }
-///     # });
-/// }
+///     global_ask_driver_init();
+///
+/// # let tx = Pin::new(&[PinTransaction::set(PinState::Low)]);
+/// # let rx = Pin::new(&[]);
+/// let config = AskConfig::builder().ticks_per_bit(8).build().unwrap();
+/// global_ask_driver_setup(&ASK_DRIVER, tx, rx, None, config).unwrap();
+/// # critical_section::with(|cs| {
+/// #    if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
+/// #       driver.tx.done();
+/// #       driver.rx.done();
+/// #   }
+/// # });
 /// ```
-pub fn global_ask_driver_setup<TX: OutputPin, RX: InputPin, PTT: OutputPin>(
-    global_driver: &'static Mutex<RefCell<Option<AskDriver<TX, RX, PTT>>>>,
+pub fn global_ask_driver_setup<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
     tx: TX,
     rx: RX,
     ptt: Option<PTT>,
-    ticks_per_bit: u8,
-    ptt_inverted: Option<bool>,
-    rx_inverted: Option<bool>,
-) {
+    config: AskConfig,
+) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+    let driver = AskDriver::new(tx, rx, ptt, config)?;
     critical_section::with(|cs| {
-        let _ = global_driver.borrow(cs).replace(Some(AskDriver::new(
-            tx,
-            rx,
-            ptt,
-            ticks_per_bit,
-            ptt_inverted,
-            rx_inverted,
-        )));
+        let _ = global_driver.borrow(cs).replace(Some(driver));
     });
+    Ok(())
 }
 
 /// Runs the tick at each interrupt
 ///
 /// # Arguments
 /// * The global static `AskDriver`
+///
+/// # Errors
+/// - [`AskError::Uninitialized`] if the global driver has not been set up yet
+/// - Any error returned by [`AskDriver::tick()`]
+///
 ///# Example
 /// ```rust,ignore
 /// # use embedded_hal_mock::eh1::digital::Mock as Pin;
@@ -99,84 +146,41 @@ pub fn global_ask_driver_setup<TX: OutputPin, RX: InputPin, PTT: OutputPin>(
 /// use ask433::timer::isr::{global_ask_driver_init, global_ask_driver_tick};
 ///
 /// static ASK_DRIVER: Mutex<RefCell<Option<AskDriver<Pin, Pin, Pin>>>> =
-///     global_ask_driver_init::<Pin, Pin, Pin>();
+///     global_ask_driver_init();
 /// #[interrupt]
 /// fn TIM2() {
-///     global_ask_driver_tick(ASK_DRIVER);
-/// }
-/// ```
-pub fn global_ask_timer_tick<TX: OutputPin, RX: InputPin, PTT: OutputPin>(
-    global_driver: &'static Mutex<RefCell<Option<AskDriver<TX, RX, PTT>>>>,
-) {
-    critical_section::with(|cs| {
-        if let Some(driver) = global_driver.borrow(cs).borrow_mut().as_mut() {
-            driver.tick();
-        }
-    });
-}
-
-/// Attempts to receive a message from a global `AskDriver` instance wrapped in a `Mutex`.
-///
-/// This function checks whether a valid and complete message is available using the
-/// driver's `availabile()` method. If a message is present, it returns a heapless `Vec`
-/// containing the decoded payload. If the buffer is invalid or incomplete, `None` is returned.
-///
-/// # Type Parameters
-/// - `TX`: Type of the TX pin (must implement `OutputPin`)
-/// - `RX`: Type of the RX pin (must implement `InputPin`)
-/// - `PTT`: Type of the Push-To-Talk (PTT) control pin (must implement `OutputPin`)
-///
-/// # Arguments
-/// - `global_driver`: A reference to a global `Mutex<RefCell<Option<AskDriver>>>`
-///   that wraps the ASK driver state, typically created using `critical_section`.
-///
-/// # Returns
-/// - `Some(Vec<u8>)`: A heapless vector containing the decoded message payload
-/// - `None`: If no valid message is currently available
-///
-/// # Safety
-/// - This function must be called from a context where `critical_section` access is safe,
-///   such as inside `main()` or an interrupt-free routine.
-///
-/// # Example
-/// ```rust
-/// # use embedded_hal_mock::eh1::digital::Mock as Pin;
-/// use critical_section::Mutex;
-/// use core::cell::RefCell;
-/// use ask433::driver::AskDriver;
-/// use ask433::timer::{global_ask_driver_init, receive_from_global_ask};
-///
-/// static ASK_DRIVER: Mutex<RefCell<Option<AskDriver<Pin, Pin, Pin>>>> =
-///     global_ask_driver_init::<Pin, Pin, Pin>();
-/// // ...
-/// if let Some(msg) = receive_from_global_ask(&ASK_DRIVER) {
-///     // Use the received message
+///     let _ = global_ask_driver_tick(ASK_DRIVER);
 /// }
 /// ```
-///
-/// # See also
-/// - [`AskDriver::availabile()`]
-/// - [`AskDriver::receive()`]
-#[cfg(not(feature = "std"))]
-pub fn receive_from_global_ask<TX: OutputPin, RX: InputPin, PTT: OutputPin>(
-    global_driver: &'static Mutex<RefCell<Option<AskDriver<TX, RX, PTT>>>>,
-) -> Option<Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE>> {
+pub fn global_ask_timer_tick<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
     critical_section::with(|cs| {
-        let mut guard = global_driver.borrow(cs).borrow_mut();
-        let driver = guard.as_mut()?;
-        if driver.availabile() {
-            driver.receive()
-        } else {
-            None
-        }
+        global_driver
+            .borrow(cs)
+            .borrow_mut()
+            .as_mut()
+            .ok_or(AskError::Uninitialized)?
+            .tick()
     })
 }
 
 /// Attempts to receive a message from a global `AskDriver` instance wrapped in a `Mutex`.
 ///
 /// This function checks whether a valid and complete message is available using the
-/// driver's `availabile()` method. If a message is present, it returns a heapless `Vec`
-/// containing the decoded payload. If the buffer is invalid or incomplete, `None` is returned.
+/// driver's `availabile()` method. If a message is present, the oldest one is taken out
+/// of the receive queue and returned as an [`AskPacket`]. Otherwise `None` is returned.
 ///
 /// # Type Parameters
 /// - `TX`: Type of the TX pin (must implement `OutputPin`)
@@ -188,8 +192,12 @@ pub fn receive_from_global_ask<TX: OutputPin, RX: InputPin, PTT: OutputPin>(
 ///   that wraps the ASK driver state, typically created using `critical_section`.
 ///
 /// # Returns
-/// - `Some(Vec<u8>)`: A vector containing the decoded message payload
-/// - `None`: If no valid message is currently available
+/// - `Ok(Some(AskPacket))`: The headers, payload and reception metadata of the message
+/// - `Ok(None)`: If no valid message is currently available
+///
+/// # Errors
+/// - [`AskError::Uninitialized`] if the global driver has not been set up yet
+/// - Any error returned by [`AskDriver::receive()`]
 ///
 /// # Safety
 /// - This function must be called from a context where `critical_section` access is safe,
@@ -204,37 +212,49 @@ pub fn receive_from_global_ask<TX: OutputPin, RX: InputPin, PTT: OutputPin>(
 /// use ask433::timer::{global_ask_driver_init, receive_from_global_ask};
 ///
 /// static ASK_DRIVER: Mutex<RefCell<Option<AskDriver<Pin, Pin, Pin>>>> =
-///     global_ask_driver_init::<Pin, Pin, Pin>();
+///     global_ask_driver_init();
 /// // ...
-/// if let Some(msg) = receive_from_global_ask(&ASK_DRIVER) {
-///     // Use the received message
+/// if let Ok(Some(packet)) = receive_from_global_ask(&ASK_DRIVER) {
+///     // Use packet.from, packet.payload, ...
 /// }
 /// ```
 ///
 /// # See also
 /// - [`AskDriver::availabile()`]
 /// - [`AskDriver::receive()`]
-#[cfg(feature = "std")]
-pub fn receive_from_global_ask<TX: OutputPin, RX: InputPin, PTT: OutputPin>(
-    global_driver: &'static Mutex<RefCell<Option<AskDriver<TX, RX, PTT>>>>,
-) -> Option<Vec<u8>> {
+pub fn receive_from_global_ask<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+) -> Result<Option<AskPacket>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
     critical_section::with(|cs| {
         let mut guard = global_driver.borrow(cs).borrow_mut();
-        let driver = guard.as_mut()?;
-        if driver.availabile() {
+        let driver = guard.as_mut().ok_or(AskError::Uninitialized)?;
+        if driver.availabile()? {
             driver.receive()
         } else {
-            None
+            Ok(None)
         }
     })
 }
 
 /// Sends a message using a global `AskDriver` instance wrapped in a `Mutex`.
 ///
-/// This function attempts to send the provided message buffer using the global
-/// ASK driver. If the driver is currently idle and the message length is valid,
+/// This function attempts to queue the provided message using the global
+/// ASK driver. If the transmitter is free and the message length is valid,
 /// it transitions to transmit mode and begins sending the encoded message
-/// (including headers, preamble, and CRC).
+/// (including headers, preamble, and CRC). It never waits for the transmitter:
+/// the critical section is held only for the duration of a single
+/// [`AskDriver::try_send()`] call.
 ///
 /// # Type Parameters
 /// - `TX`: The TX pin type (must implement `OutputPin`)
@@ -244,134 +264,347 @@ pub fn receive_from_global_ask<TX: OutputPin, RX: InputPin, PTT: OutputPin>(
 /// # Arguments
 /// - `global_driver`: A reference to a global `Mutex<RefCell<Option<AskDriver>>>`,
 ///   typically declared using [`init_ask_driver!`](crate::init_ask_driver) and initialized via [`setup_ask_driver!`](crate::setup_ask_driver).
-/// - `vec`: A `heapless::Vec<u8>` containing the payload to send. Must not exceed [`ASK_MAX_MESSAGE_LEN`](crate::consts::ASK_MAX_MESSAGE_LEN).
+/// - `msg`: The payload to send. Must not exceed [`AskDriver::max_message_len()`].
 ///
-/// # Returns
-/// - `true` if the message was successfully queued for transmission
-/// - `false` if the driver was uninitialized or busy
+/// # Errors
+/// - [`nb::Error::WouldBlock`] while a previous frame is still being transmitted
+/// - [`AskError::Uninitialized`] if the global driver has not been set up yet
+/// - Any other error returned by [`AskDriver::try_send()`]
 ///
 /// # Example
 ///
 /// ```rust
 /// # use embedded_hal_mock::eh1::digital::Mock as Pin;
 /// use critical_section::Mutex;
-/// use heapless::Vec;
 /// use core::cell::RefCell;
 /// use ask433::driver::AskDriver;
 /// use ask433::timer::{global_ask_driver_init, send_from_global_ask};
-/// use ask433::consts::ASK_MAX_MESSAGE_LEN_USIZE;
 ///
 /// static ASK_DRIVER: Mutex<RefCell<Option<AskDriver<Pin, Pin, Pin>>>> =
-///     global_ask_driver_init::<Pin, Pin, Pin>();
+///     global_ask_driver_init();
 ///
-/// let mut vec: Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE> = Vec::new();
-/// vec.extend_from_slice(b"Hello").unwrap();
-/// let sent = send_from_global_ask(&ASK_DRIVER, vec);
-/// if sent {
-///     // Message is being transmitted
+/// match send_from_global_ask(&ASK_DRIVER, b"Hello") {
+///     Ok(()) => { /* Message is being transmitted */ }
+///     Err(nb::Error::WouldBlock) => { /* Try again after a few ticks */ }
+///     Err(nb::Error::Other(e)) => { /* Report the error */ }
 /// }
 /// ```
 ///
 /// # Notes
-/// - This function does not block. Transmission occurs incrementally via repeated `tick()` calls.
-/// - Returns `false` if the driver is uninitialized or already transmitting a message.
+/// - Transmission occurs incrementally via repeated `tick()` calls.
+/// - `nb::block!(send_from_global_ask(&ASK_DRIVER, msg))` is safe from the main loop,
+///   since every retry re-enters the critical section and lets the timer ISR run in between.
+///   Never call it from inside another `critical_section::with` block.
 /// - The PTT pin is asserted automatically during transmission.
 ///
 /// # See also
-/// - [`AskDriver::send()`]
-#[cfg(not(feature = "std"))]
-pub fn send_from_global_ask<TX: OutputPin, RX: InputPin, PTT: OutputPin>(
-    global_driver: &'static Mutex<RefCell<Option<AskDriver<TX, RX, PTT>>>>,
-    msg: Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE>,
-) -> bool {
+/// - [`AskDriver::try_send()`]
+/// - [`global_ask_tx_busy()`]
+/// - [`global_ask_tx_complete()`]
+pub fn send_from_global_ask<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+    msg: &[u8],
+) -> nb::Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
     critical_section::with(|cs| {
-        if let Some(driver) = global_driver.borrow(cs).borrow_mut().as_mut() {
-            driver.send(msg)
-        } else {
-            false
-        }
+        global_driver
+            .borrow(cs)
+            .borrow_mut()
+            .as_mut()
+            .ok_or(nb::Error::Other(AskError::Uninitialized))?
+            .try_send(msg)
     })
 }
 
-/// Sends a message using a global `AskDriver` instance wrapped in a `Mutex`.
+/// Checks whether a global `AskDriver` is still transmitting a frame.
 ///
-/// This function attempts to send the provided message buffer using the global
-/// ASK driver. If the driver is currently idle and the message length is valid,
-/// it transitions to transmit mode and begins sending the encoded message
-/// (including headers, preamble, and CRC).
+/// # Errors
+/// Returns [`AskError::Uninitialized`] if the global driver has not been set up yet.
 ///
-/// # Type Parameters
-/// - `TX`: The TX pin type (must implement `OutputPin`)
-/// - `RX`: The RX pin type (must implement `InputPin`)
-/// - `PTT`: The Push-To-Talk (PTT) control pin type (must implement `OutputPin`)
+/// # See also
+/// - [`AskDriver::is_tx_busy()`]
+pub fn global_ask_tx_busy<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+) -> Result<bool, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+    critical_section::with(|cs| {
+        global_driver
+            .borrow(cs)
+            .borrow()
+            .as_ref()
+            .map(AskDriver::is_tx_busy)
+            .ok_or(AskError::Uninitialized)
+    })
+}
+
+/// Takes the "TX complete" flag of a global `AskDriver`.
 ///
-/// # Arguments
-/// - `global_driver`: A reference to a global `Mutex<RefCell<Option<AskDriver>>>`,
-///   typically declared using [`init_global_ask_driver!`] and initialized via [`setup_global_ask_driver!`].
-/// - `vec`: A `Vec<u8>` containing the payload to send.
+/// Returns `Ok(true)` once after a frame has finished transmitting.
 ///
-/// # Returns
-/// - `true` if the message was successfully queued for transmission
-/// - `false` if the driver was uninitialized or busy
+/// # Errors
+/// Returns [`AskError::Uninitialized`] if the global driver has not been set up yet.
 ///
-/// # Example
-/// ```rust
-/// # use embedded_hal_mock::eh1::digital::Mock as Pin;
-/// use critical_section::Mutex;
-/// use core::cell::RefCell;
-/// use ask433::driver::AskDriver;
-/// use ask433::timer::{global_ask_driver_init, send_from_global_ask};
+/// # See also
+/// - [`AskDriver::take_tx_complete()`]
+pub fn global_ask_tx_complete<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+) -> Result<bool, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+    critical_section::with(|cs| {
+        global_driver
+            .borrow(cs)
+            .borrow_mut()
+            .as_mut()
+            .map(AskDriver::take_tx_complete)
+            .ok_or(AskError::Uninitialized)
+    })
+}
+
+/// Starts a channel activity detection window on a global `AskDriver`.
+///
+/// # Errors
+/// - [`AskError::Uninitialized`] if the global driver has not been set up yet
+/// - Any error returned by [`AskDriver::start_cad()`]
+pub fn global_ask_start_cad<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+) -> Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+    critical_section::with(|cs| {
+        global_driver
+            .borrow(cs)
+            .borrow_mut()
+            .as_mut()
+            .ok_or(AskError::Uninitialized)?
+            .start_cad()
+    })
+}
+
+/// Reads the outcome of the last channel activity detection window of a global `AskDriver`.
+///
+/// Returns `Ok(true)` if the channel was busy. Retry with `nb::block!` from the main
+/// loop while the window is still open; the critical section is only held for the check.
+///
+/// # Errors
+/// - [`nb::Error::WouldBlock`] while the window is still open
+/// - [`AskError::Uninitialized`] if the global driver has not been set up yet
+/// - Any other error returned by [`AskDriver::cad_result()`]
+pub fn global_ask_cad_result<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+) -> nb::Result<bool, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+    critical_section::with(|cs| {
+        global_driver
+            .borrow(cs)
+            .borrow()
+            .as_ref()
+            .ok_or(nb::Error::Other(AskError::Uninitialized))?
+            .cad_result()
+    })
+}
+
+/// Reads the link statistics of a global `AskDriver`.
 ///
-/// static ASK_DRIVER: Mutex<RefCell<Option<AskDriver<Pin, Pin, Pin>>>> =
-///     global_ask_driver_init::<Pin, Pin, Pin>();
-/// let mut vec: Vec<u8> = Vec::new();
-/// vec.extend(b"Hello");
-/// let sent = send_from_global_ask(&ASK_DRIVER, vec);
-/// if sent {
-///     // Message is being transmitted
-/// }
-/// ```
+/// The snapshot is taken inside a critical section, so the counters are consistent
+/// with each other even while the timer ISR keeps ticking the driver.
 ///
-/// # Notes
-/// - This function does not block. Transmission occurs incrementally via repeated `tick()` calls.
-/// - Returns `false` if the driver is uninitialized or already transmitting a message.
-/// - The PTT pin is asserted automatically during transmission.
+/// # Errors
+/// Returns [`AskError::Uninitialized`] if the global driver has not been set up yet.
 ///
 /// # See also
-/// - [`AskDriver::send()`]
-#[cfg(feature = "std")]
-pub fn send_from_global_ask<TX: OutputPin, RX: InputPin, PTT: OutputPin>(
-    global_driver: &'static Mutex<RefCell<Option<AskDriver<TX, RX, PTT>>>>,
-    msg: Vec<u8>,
-) -> bool {
+/// - [`AskDriver::stats()`]
+/// - [`global_ask_reset_stats()`]
+pub fn global_ask_stats<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+) -> Result<AskStats, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
     critical_section::with(|cs| {
-        if let Some(driver) = global_driver.borrow(cs).borrow_mut().as_mut() {
-            driver.send(msg)
-        } else {
-            false
+        global_driver
+            .borrow(cs)
+            .borrow()
+            .as_ref()
+            .map(AskDriver::stats)
+            .ok_or(AskError::Uninitialized)
+    })
+}
+
+/// Reads and clears the link statistics of a global `AskDriver` in one critical section.
+///
+/// Returns the counters as they were before the reset, so no event is lost between
+/// reading and clearing them.
+///
+/// # Errors
+/// Returns [`AskError::Uninitialized`] if the global driver has not been set up yet.
+///
+/// # See also
+/// - [`AskDriver::reset_stats()`]
+pub fn global_ask_reset_stats<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+) -> Result<AskStats, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+    critical_section::with(|cs| {
+        let mut guard = global_driver.borrow(cs).borrow_mut();
+        let driver = guard.as_mut().ok_or(AskError::Uninitialized)?;
+        let stats = driver.stats();
+        driver.reset_stats();
+        Ok(stats)
+    })
+}
+
+/// Sends a code word to a fixed-code receiver from a global `AskDriver`.
+///
+/// # Errors
+/// - [`nb::Error::WouldBlock`] while the transmitter is busy
+/// - [`nb::Error::Other`] with [`AskError::Uninitialized`] if the global driver has not
+///   been set up yet, or any other error listed on [`AskDriver::send_rc_code()`]
+///
+/// # See also
+/// - [`AskDriver::send_rc_code()`]
+pub fn global_ask_send_rc_code<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+    code: &RcCode,
+    repeats: u8,
+) -> nb::Result<(), AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+    critical_section::with(|cs| {
+        let mut guard = global_driver.borrow(cs).borrow_mut();
+        let driver = guard
+            .as_mut()
+            .ok_or(nb::Error::Other(AskError::Uninitialized))?;
+        match driver.send_rc_code(code, repeats) {
+            Err(AskError::Busy) => Err(nb::Error::WouldBlock),
+            result => result.map_err(nb::Error::Other),
         }
     })
 }
 
+/// Takes the last code received from a fixed-code remote by a global `AskDriver`.
+///
+/// # Errors
+/// Returns [`AskError::Uninitialized`] if the global driver has not been set up yet.
+///
+/// # See also
+/// - [`AskDriver::receive_rc_code()`]
+pub fn global_ask_receive_rc_code<
+    TX: OutputPin,
+    RX: InputPin,
+    PTT: OutputPin,
+    const TXQ: usize,
+    const RXQ: usize,
+    LC: LineCode,
+    CS: Checksum,
+    FF: FrameFormat,
+    RXEN: OutputPin,
+    TXEN: OutputPin,
+>(
+    global_driver: &'static GlobalAskDriver<TX, RX, PTT, TXQ, RXQ, LC, CS, FF, RXEN, TXEN>,
+) -> Result<Option<RcCode>, AskDriverError<TX, RX, PTT, RXEN, TXEN>> {
+    critical_section::with(|cs| {
+        global_driver
+            .borrow(cs)
+            .borrow_mut()
+            .as_mut()
+            .map(AskDriver::receive_rc_code)
+            .ok_or(AskError::Uninitialized)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use core::cell::RefCell;
     use embedded_hal_mock::eh1::digital::{
         Mock as PinMock, State as PinState, Transaction as PinTransaction,
     };
 
     #[test]
     fn test_global_driver_init_and_setup() {
-        static GLOBAL_DRIVER: Mutex<RefCell<Option<AskDriver<PinMock, PinMock, PinMock>>>> =
-            global_ask_driver_init::<PinMock, PinMock, PinMock>();
-        static TICKS: u8 = 8;
+        static GLOBAL_DRIVER: GlobalAskDriver<PinMock, PinMock, PinMock> = global_ask_driver_init();
+        let config = AskConfig::builder().ticks_per_bit(8).build().unwrap();
 
         let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
         let rx = PinMock::new(&[]);
         let ptt = Some(PinMock::new(&[]));
 
-        global_ask_driver_setup(&GLOBAL_DRIVER, tx, rx, ptt, TICKS, Some(false), Some(false));
+        global_ask_driver_setup(&GLOBAL_DRIVER, tx, rx, ptt, config).unwrap();
 
         critical_section::with(|cs| {
             assert!(GLOBAL_DRIVER.borrow(cs).borrow().is_some());
@@ -388,17 +621,16 @@ mod tests {
 
     #[test]
     fn test_global_tick_function_calls_tick() {
-        static GLOBAL_DRIVER: Mutex<RefCell<Option<AskDriver<PinMock, PinMock, PinMock>>>> =
-            global_ask_driver_init::<PinMock, PinMock, PinMock>();
-        static TICKS: u8 = 8;
+        static GLOBAL_DRIVER: GlobalAskDriver<PinMock, PinMock, PinMock> = global_ask_driver_init();
+        let config = AskConfig::builder().ticks_per_bit(8).build().unwrap();
 
         let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
         let rx = PinMock::new(&[]);
         let ptt = Some(PinMock::new(&[]));
 
-        global_ask_driver_setup(&GLOBAL_DRIVER, tx, rx, ptt, TICKS, Some(false), Some(false));
+        global_ask_driver_setup(&GLOBAL_DRIVER, tx, rx, ptt, config).unwrap();
 
-        global_ask_timer_tick(&GLOBAL_DRIVER);
+        global_ask_timer_tick(&GLOBAL_DRIVER).unwrap();
 
         critical_section::with(|cs| {
             if let Some(driver) = GLOBAL_DRIVER.borrow(cs).borrow_mut().as_mut() {
@@ -411,9 +643,8 @@ mod tests {
 
     #[test]
     fn test_global_send_and_receive() {
-        static GLOBAL_DRIVER: Mutex<RefCell<Option<AskDriver<PinMock, PinMock, PinMock>>>> =
-            global_ask_driver_init::<PinMock, PinMock, PinMock>();
-        static TICKS: u8 = 8;
+        static GLOBAL_DRIVER: GlobalAskDriver<PinMock, PinMock, PinMock> = global_ask_driver_init();
+        let config = AskConfig::builder().ticks_per_bit(8).build().unwrap();
 
         let tx = PinMock::new(&[
             PinTransaction::set(PinState::Low),
@@ -422,29 +653,32 @@ mod tests {
         let rx = PinMock::new(&[]);
         let ptt = Some(PinMock::new(&[PinTransaction::set(PinState::High)]));
 
-        global_ask_driver_setup(&GLOBAL_DRIVER, tx, rx, ptt, TICKS, Some(false), Some(false));
+        global_ask_driver_setup(&GLOBAL_DRIVER, tx, rx, ptt, config).unwrap();
 
         for _ in 0..5 {
-            global_ask_timer_tick(&GLOBAL_DRIVER);
+            global_ask_timer_tick(&GLOBAL_DRIVER).unwrap();
         }
 
-        let mut msg = Vec::new();
-        #[cfg(not(feature = "std"))]
-        let _ = msg.extend_from_slice(b"Hello");
-        #[cfg(feature = "std")]
-        msg.extend_from_slice(b"Hello");
-
-        assert!(send_from_global_ask(&GLOBAL_DRIVER, msg));
+        send_from_global_ask(&GLOBAL_DRIVER, b"Hello").unwrap();
+        assert!(global_ask_tx_busy(&GLOBAL_DRIVER).unwrap());
+        for _ in 0..ASK_TX_QUEUE_LEN {
+            send_from_global_ask(&GLOBAL_DRIVER, b"Hello").unwrap();
+        }
+        assert_eq!(
+            send_from_global_ask(&GLOBAL_DRIVER, b"Hello"),
+            Err(nb::Error::WouldBlock)
+        );
+        assert!(!global_ask_tx_complete(&GLOBAL_DRIVER).unwrap());
 
         for _ in 0..5 {
-            global_ask_timer_tick(&GLOBAL_DRIVER);
+            global_ask_timer_tick(&GLOBAL_DRIVER).unwrap();
         }
 
-        let received = receive_from_global_ask(&GLOBAL_DRIVER);
+        let received = receive_from_global_ask(&GLOBAL_DRIVER).unwrap();
         assert!(received.is_none()); // No message has been received yet
 
         for _ in 0..5 {
-            global_ask_timer_tick(&GLOBAL_DRIVER);
+            global_ask_timer_tick(&GLOBAL_DRIVER).unwrap();
         }
 
         critical_section::with(|cs| {
@@ -455,4 +689,81 @@ mod tests {
             }
         });
     }
+
+    #[test]
+    fn test_global_receive_rc_code() {
+        static GLOBAL_DRIVER: GlobalAskDriver<PinMock, PinMock, PinMock> = global_ask_driver_init();
+        assert!(matches!(
+            global_ask_receive_rc_code(&GLOBAL_DRIVER),
+            Err(AskError::Uninitialized)
+        ));
+
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        global_ask_driver_setup(&GLOBAL_DRIVER, tx, rx, None, AskConfig::default()).unwrap();
+        assert_eq!(global_ask_receive_rc_code(&GLOBAL_DRIVER).unwrap(), None);
+
+        critical_section::with(|cs| {
+            if let Some(driver) = GLOBAL_DRIVER.borrow(cs).borrow_mut().as_mut() {
+                driver.tx.done();
+                driver.rx.done();
+            }
+        });
+    }
+
+    #[test]
+    fn test_global_send_rc_code() {
+        static GLOBAL_DRIVER: GlobalAskDriver<PinMock, PinMock, PinMock> = global_ask_driver_init();
+        let code = RcCode::new(1, 0b10, 2).unwrap();
+        assert!(matches!(
+            global_ask_send_rc_code(&GLOBAL_DRIVER, &code, 1),
+            Err(nb::Error::Other(AskError::Uninitialized))
+        ));
+
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        global_ask_driver_setup(&GLOBAL_DRIVER, tx, rx, None, AskConfig::default()).unwrap();
+        global_ask_send_rc_code(&GLOBAL_DRIVER, &code, 1).unwrap();
+        assert!(global_ask_tx_busy(&GLOBAL_DRIVER).unwrap());
+        assert_eq!(
+            global_ask_send_rc_code(&GLOBAL_DRIVER, &code, 1),
+            Err(nb::Error::WouldBlock)
+        );
+
+        critical_section::with(|cs| {
+            if let Some(driver) = GLOBAL_DRIVER.borrow(cs).borrow_mut().as_mut() {
+                driver.tx.done();
+                driver.rx.done();
+            }
+        });
+    }
+
+    #[test]
+    fn test_global_stats_read_and_reset() {
+        static GLOBAL_DRIVER: GlobalAskDriver<PinMock, PinMock, PinMock> = global_ask_driver_init();
+        assert!(matches!(
+            global_ask_stats(&GLOBAL_DRIVER),
+            Err(AskError::Uninitialized)
+        ));
+
+        let tx = PinMock::new(&[PinTransaction::set(PinState::Low)]);
+        let rx = PinMock::new(&[]);
+        global_ask_driver_setup(&GLOBAL_DRIVER, tx, rx, None, AskConfig::default()).unwrap();
+
+        assert_eq!(
+            global_ask_stats(&GLOBAL_DRIVER).unwrap(),
+            AskStats::default()
+        );
+        assert_eq!(
+            global_ask_reset_stats(&GLOBAL_DRIVER).unwrap(),
+            AskStats::default()
+        );
+
+        critical_section::with(|cs| {
+            if let Some(driver) = GLOBAL_DRIVER.borrow(cs).borrow_mut().as_mut() {
+                driver.tx.done();
+                driver.rx.done();
+            }
+        });
+    }
 }
@@ -8,7 +8,11 @@
 /// - `$tx`: The concrete type of the TX pin (must implement `OutputPin`)
 /// - `$rx`: The concrete type of the RX pin (must implement `InputPin`)
 /// - `$ptt`: The concrete type of the PRR pin (must implement `OutputPin`). **NOTE**: While you
-/// might not have a PTT pin, it is imperative that you pass a type here.
+///   might not have a PTT pin, it is imperative that you pass a type here.
+/// - `$txq`: The optional transmit queue depth (defaults to
+///   [`ASK_TX_QUEUE_LEN`](crate::consts::ASK_TX_QUEUE_LEN))
+/// - `$rxq`: The optional receive queue depth (defaults to
+///   [`ASK_RX_QUEUE_LEN`](crate::consts::ASK_RX_QUEUE_LEN))
 ///
 /// # Example
 /// ```rust
@@ -16,6 +20,13 @@
 /// use embedded_hal_mock::eh1::digital::{Mock as Pin};
 /// init_ask_driver!(Pin, Pin, Pin);
 /// ```
+///
+/// With room for eight outgoing and sixteen received frames:
+/// ```rust
+/// use ask433::init_ask_driver;
+/// use embedded_hal_mock::eh1::digital::{Mock as Pin};
+/// init_ask_driver!(Pin, Pin, Pin, 8, 16);
+/// ```
 #[cfg_attr(feature = "timer-isr", macro_export)]
 macro_rules! init_ask_driver {
     ($tx:ty, $rx:ty, $ptt:ty) => {
@@ -24,6 +35,20 @@ macro_rules! init_ask_driver {
             core::cell::RefCell<Option<$crate::driver::AskDriver<$tx, $rx, $ptt>>>,
         > = $crate::critical_section::Mutex::new(core::cell::RefCell::new(None));
     };
+    ($tx:ty, $rx:ty, $ptt:ty, $txq:expr) => {
+        #[allow(unused)]
+        pub static ASK_DRIVER: $crate::critical_section::Mutex<
+            core::cell::RefCell<Option<$crate::driver::AskDriver<$tx, $rx, $ptt, { $txq }>>>,
+        > = $crate::critical_section::Mutex::new(core::cell::RefCell::new(None));
+    };
+    ($tx:ty, $rx:ty, $ptt:ty, $txq:expr, $rxq:expr) => {
+        #[allow(unused)]
+        pub static ASK_DRIVER: $crate::critical_section::Mutex<
+            core::cell::RefCell<
+                Option<$crate::driver::AskDriver<$tx, $rx, $ptt, { $txq }, { $rxq }>>,
+            >,
+        > = $crate::critical_section::Mutex::new(core::cell::RefCell::new(None));
+    };
 }
 
 /// Initializes the global `ASK_DRIVER` singleton with a new driver instance.
@@ -35,13 +60,17 @@ macro_rules! init_ask_driver {
 /// - `$tx`: The TX pin (must implement `OutputPin`)
 /// - `$rx`: The RX pin (must implement `InputPin`)
 /// - `$ptt`: The optional PTT pin (must implement `OutputPin`)
-/// - `$tpb`: The number of ticks per bit (e.g., 8 for a radio at 2 kbps and an interrupt at ~2MHz)
-/// - `$ptt_inverted`: The optional boolean specifying whether the PTT pin should be inverted
-/// - `$rx_inverted`: The optional boolean specifying whether the RX pin should be inverted
+/// - `$config`: The optional [`AskConfig`](crate::config::AskConfig) (defaults to
+///   `AskConfig::default()`)
+///
+/// # Returns
+/// `Result<(), AskError>`: the pin error if the driver could not drive its pins into the
+/// idle state, in which case `ASK_DRIVER` is left untouched.
 ///
 /// # Example
 /// ```rust
 /// use embedded_hal_mock::eh1::digital::{Mock as Pin, Transaction as Tx, State as St};
+/// use ask433::config::AskConfig;
 /// use ask433::{init_ask_driver, setup_ask_driver};
 ///
 /// init_ask_driver!(Pin, Pin, Pin);
@@ -49,7 +78,8 @@ macro_rules! init_ask_driver {
 /// fn main() {
 ///     # let tx = Pin::new(&[Tx::set(St::Low)]);
 ///     # let rx = Pin::new(&[]);
-///     setup_ask_driver!(tx, rx, None, 8, None, None);
+///     let config = AskConfig::builder().ticks_per_bit(8).address(0x42).build().unwrap();
+///     setup_ask_driver!(tx, rx, None, config).unwrap();
 ///     # critical_section::with(|cs| {
 ///     #    if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
 ///     #       driver.tx.done();
@@ -64,19 +94,15 @@ macro_rules! init_ask_driver {
 /// - Requires `init_ask_driver!` to have been used earlier.
 #[cfg_attr(feature = "timer-isr", macro_export)]
 macro_rules! setup_ask_driver {
-    ( $tx:expr, $rx:expr, $ptt:expr, $ticks_per_bit:expr, $ptt_inverted:expr, $rx_inverted:expr ) => {
-        $crate::critical_section::with(|cs| {
-            let _ = ASK_DRIVER
-                .borrow(cs)
-                .replace(Some($crate::driver::AskDriver::new(
-                    $tx,
-                    $rx,
-                    $ptt,
-                    $ticks_per_bit,
-                    $ptt_inverted,
-                    $rx_inverted,
-                )));
-        });
+    ( $tx:expr, $rx:expr, $ptt:expr ) => {
+        $crate::setup_ask_driver!($tx, $rx, $ptt, $crate::config::AskConfig::default())
+    };
+    ( $tx:expr, $rx:expr, $ptt:expr, $config:expr ) => {
+        $crate::driver::AskDriver::new($tx, $rx, $ptt, $config).map(|driver| {
+            $crate::critical_section::with(|cs| {
+                let _ = ASK_DRIVER.borrow(cs).replace(Some(driver));
+            });
+        })
     };
 }
 
@@ -85,37 +111,44 @@ macro_rules! setup_ask_driver {
 /// This macro is intended to be invoked from a timer ISR or scheduler to
 /// advance the ASK state machine at regular intervals (e.g., every 62.5 µs).
 ///
+/// # Returns
+/// `Result<(), AskError>`: [`AskError::Uninitialized`](crate::error::AskError::Uninitialized)
+/// if the driver hasn't been set up yet, or any error returned by `tick()`.
+///
 /// # Example
 /// ```rust,ignore
 /// use ask433::tick_ask_timer;
 ///
 /// #[interrupt]
 /// fn TIM2() {
-///     tick_ask_timer!();
+///     let _ = tick_ask_timer!();
 /// }
 /// ```
 ///
 /// # Notes
 /// - This macro assumes `ASK_DRIVER` was declared with `init_ask_driver!`
 ///   and initialized via `setup_ask_driver!`.
-/// - Safe to call repeatedly — will silently do nothing if the driver hasn't been set up yet.
+/// - Safe to call repeatedly — returns an error without touching any pins if the driver
+///   hasn't been set up yet.
 #[cfg_attr(feature = "timer-isr", macro_export)]
 macro_rules! tick_ask_timer {
     () => {
         $crate::critical_section::with(|cs| {
-            if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
-                driver.tick();
-            }
-        });
+            ASK_DRIVER
+                .borrow(cs)
+                .borrow_mut()
+                .as_mut()
+                .ok_or($crate::error::AskError::Uninitialized)
+                .and_then(|driver| driver.tick())
+        })
     };
 }
 
 /// Attempts to receive a completed message from the global ASK driver instance.
 ///
 /// This macro checks whether a valid and complete message is available using
-/// `driver.availabile()`, and if so, returns a reference to the decoded message
-/// payload slice. If no message is available, or the driver is currently transmitting,
-/// it returns `None`.
+/// `driver.availabile()`, and if so, takes the oldest message out of the receive
+/// queue and returns it. If the queue is empty, it returns `None`.
 ///
 /// # Requirements
 /// - The global `ASK_DRIVER` instance must have been initialized using
@@ -123,8 +156,9 @@ macro_rules! tick_ask_timer {
 /// - Must be called in a context where `critical_section` is available and safe to use.
 ///
 /// # Returns
-/// - `Some(&[u8])`: A reference to the message payload if available
-/// - `None`: If no message is currently available
+/// - `Ok(Some(AskPacket))`: The oldest received [`AskPacket`](crate::packet::AskPacket) if available
+/// - `Ok(None)`: If no message is currently available
+/// - `Err(AskError)`: If the driver hasn't been set up yet or the RX path failed
 ///
 /// # Example
 /// ```rust
@@ -135,112 +169,47 @@ macro_rules! tick_ask_timer {
 ///
 /// fn main() {
 ///     // ...
-///     if let Some(msg) = receive_from_ask!() {
-///         // Process message payload
+///     if let Ok(Some(packet)) = receive_from_ask!() {
+///         // Process packet.payload
 ///     }
 /// }
 /// ```
 #[cfg_attr(feature = "timer-isr", macro_export)]
 macro_rules! receive_from_ask {
     () => {
-        $crate::critical_section::with(|cs| {
-            if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
-                if driver.availabile() {
-                    driver.receive()
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+        $crate::critical_section::with(|cs| match ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
+            Some(driver) => match driver.availabile() {
+                Ok(true) => driver.receive(),
+                Ok(false) => Ok(None),
+                Err(e) => Err(e),
+            },
+            None => Err($crate::error::AskError::Uninitialized),
         })
     };
 }
 
-/// Sends a message from the global ASK driver using a heapless `Vec<u8>`.
+/// Queues a message on the global ASK driver without waiting for the transmitter.
 ///
-/// This macro supports two usage patterns:
-/// 1. Repeating a single element: `send_from_ask!(0xAA; 10)`
-/// 2. Sending an explicit sequence of bytes: `send_from_ask!(0x01, 0x02, 0x03)`
+/// This macro supports three usage patterns:
+/// 1. Sending the bytes of a string: `send_from_ask!("Hello")`
+/// 2. Repeating a single element: `send_from_ask!(0xAA; 10)`
+/// 3. Sending an explicit sequence of bytes: `send_from_ask!(0x01, 0x02, 0x03)`
 ///
-/// The message is internally collected into a `heapless::Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE>`
-/// and passed to the `send()` method on the global driver.
-///
-/// # Requirements
-/// - The driver must be initialized via `init_global_ask_driver!` and `setup_global_ask_driver!`.
-///
-/// # Panics
-/// - Will panic at runtime if the message exceeds `ASK_MAX_MESSAGE_LEN`
-///
-/// # Example
-/// ```rust,ignore
-/// use ask433::{send_from_ask, init_ask_driver};
-/// # use embedded_hal_mock::eh1::digital::{Mock as Pin};
-///
-/// init_ask_driver!(Pin, Pin, Pin);
-///
-/// fn main() {
-///
-///     // ...
-///
-///     let sent1 = send_from_ask!(0xAB; 4);         // send [0xAB, 0xAB, 0xAB, 0xAB]
-///     let sent2 = send_from_ask!(1, 2, 3, 4, 5);   // send [1, 2, 3, 4, 5]
-///     let sent3 = send_from_ask!("Hello, World!");  // send [0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x2c, 0x20, 0x57, 0x6f, 0x72, 0x6c, 0x64, 0x21]
-/// }
-/// ```
-#[cfg(not(feature = "std"))]
-#[cfg_attr(all(feature = "timer-isr", not(feature = "std")), macro_export)]
-macro_rules! send_from_ask {
-    ($elem:expr) => {
-        $crate::critical_section::with(|cs| {
-            if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
-                let message = $crate::heapless::Vec::from_slice($elem.as_bytes()).unwrap();
-                driver.send(message)
-            } else {
-                false
-            }
-        })
-    };
-    ($elem:expr; $n:expr) => {
-        $crate::critical_section::with(|cs| {
-            if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
-                let mut message = $crate::heapless::Vec::new();
-                message.extend([$elem; $n]);
-                driver.send(message)
-            } else {
-                false
-            }
-        })
-    };
-    ($($x:expr),+ $(,)?) => {
-        $crate::critical_section::with(|cs| {
-            if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
-                let mut message = $crate::heapless::Vec::new();
-                for item in [$($x),+] {
-                    let _ = message.push(item);
-                }
-                driver.send(message)
-            } else {
-                false
-            }
-        })
-    };
-}
-
-/// Sends a message from the global ASK driver using a heapless `Vec<u8>`.
-///
-/// This macro supports two usage patterns:
-/// 1. Repeating a single element: `send_from_ask!(0xAA; 10)`
-/// 2. Sending an explicit sequence of bytes: `send_from_ask!(0x01, 0x02, 0x03)`
-///
-/// The message is internally collected into a `heapless::Vec<u8, ASK_MAX_MESSAGE_LEN_USIZE>`
-/// and passed to the `send()` method on the global driver.
+/// The bytes are passed as a slice to the `try_send()` method on the global driver.
+/// The critical section is held only for that single call, so the macro can never
+/// spin while the timer ISR is locked out.
 ///
 /// # Requirements
 /// - The driver must be initialized via `init_ask_driver!` and `setup_ask_driver!`.
 ///
-/// # Panics
-/// - Will panic at runtime if the message exceeds `ASK_MAX_MESSAGE_LEN`
+/// # Returns
+/// `nb::Result<(), AskError>`:
+/// - `nb::Error::WouldBlock` while a previous frame is still being transmitted
+/// - [`AskError::MessageTooLong`](crate::error::AskError::MessageTooLong) if the message
+///   exceeds the driver's `max_message_len()`
+/// - [`AskError::Uninitialized`](crate::error::AskError::Uninitialized) if the driver
+///   hasn't been set up yet
+/// - Any other error returned by `try_send()`
 ///
 /// # Example
 /// ```rust
@@ -253,46 +222,38 @@ macro_rules! send_from_ask {
 ///
 ///     // ...
 ///
-///     let sent1: bool = send_from_ask![0xAB; 4];         // send [0xAB, 0xAB, 0xAB, 0xAB]
-///     let sent2: bool = send_from_ask![1, 2, 3, 4, 5];   // send [1, 2, 3, 4, 5]
-///     let sent3: bool = send_from_ask!("Hello, World!");  // send [0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x2c, 0x20, 0x57, 0x6f, 0x72, 0x6c, 0x64, 0x21]
+///     let sent1 = send_from_ask![0xAB; 4];         // send [0xAB, 0xAB, 0xAB, 0xAB]
+///     let sent2 = send_from_ask![1, 2, 3, 4, 5];   // send [1, 2, 3, 4, 5]
+///     let sent3 = send_from_ask!("Hello, World!");  // send [0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x2c, 0x20, 0x57, 0x6f, 0x72, 0x6c, 0x64, 0x21]
 ///
+///     // Retry from the main loop until the transmitter is free
+///     let sent4 = nb::block!(send_from_ask!("Hello again"));
+/// #   assert!(sent4.is_err());
 /// }
 /// ```
-#[cfg(feature = "std")]
-#[cfg_attr(all(feature = "timer-isr", feature = "std"), macro_export)]
+#[cfg_attr(feature = "timer-isr", macro_export)]
 macro_rules! send_from_ask {
     ($elem:expr) => {
         $crate::critical_section::with(|cs| {
-            if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
-                let message = ::std::vec::Vec::from($elem.as_bytes());
-                driver.send(message)
-            } else {
-                false
+            match ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
+                Some(driver) => driver.try_send($elem.as_bytes()),
+                None => Err($crate::nb::Error::Other($crate::error::AskError::Uninitialized)),
             }
         })
     };
     ($elem:expr; $n:expr) => {
         $crate::critical_section::with(|cs| {
-            if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
-                let mut message = ::std::vec::Vec::new();
-                message.extend([$elem; $n]);
-                driver.send(message)
-            } else {
-                false
+            match ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
+                Some(driver) => driver.try_send(&[$elem; $n]),
+                None => Err($crate::nb::Error::Other($crate::error::AskError::Uninitialized)),
             }
         })
     };
     ($($x:expr),+ $(,)?) => {
         $crate::critical_section::with(|cs| {
-            if let Some(driver) = ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
-                let mut message = ::std::vec::Vec::new();
-                for item in [$($x),+] {
-                    message.push(item);
-                }
-                driver.send(message)
-            } else {
-                false
+            match ASK_DRIVER.borrow(cs).borrow_mut().as_mut() {
+                Some(driver) => driver.try_send(&[$($x),+]),
+                None => Err($crate::nb::Error::Other($crate::error::AskError::Uninitialized)),
             }
         })
     };
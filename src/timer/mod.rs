@@ -9,7 +9,7 @@
 //! - `const_ocr_value`: compile-time OCR calculator
 //! - `run_ask_tick_loop`: blocking driver loop for DelayUs (feature `delay-loop`)
 //! - `global_ask_timer_tick` and `tick_ask_timer!()`: interrupt-based tick callback wrapper
-//! (feature `timer-isr`)
+//!   (feature `timer-isr`)
 //!
 //! Common prescalers: (For use with `compute_ocr_value` and `const_ocr_value`)
 //!
@@ -21,6 +21,7 @@
 //! |      1024 |          125 |              8 ms |
 //! |      1024 |          250 |             16 ms |
 
+use crate::config::BitRate;
 use libm::round;
 
 #[cfg(feature = "delay-loop")]
@@ -41,13 +42,13 @@ mod macros;
 #[cfg(feature = "timer-isr")]
 pub use macros::*;
 
-/// 2 kilobits / second == 2000 bits / second
+/// Bits per second at the default [`BitRate::Bps2000`]
 pub const BITS_PER_SECOND: u16 = 2_000;
-/// (2 kilobits / second)^-1 == 0.0005 seconds / bit
+/// Seconds per bit at the default [`BitRate::Bps2000`]
 pub const SECONDS_PER_BIT: f32 = 0.0005;
-/// 2 kilobits / second == 2 * 10^-12 bits / picosecond
+/// Bits per picosecond at the default [`BitRate::Bps2000`]
 pub const BITS_PER_PICOSECOND: f32 = 2E-12_f32;
-/// (2 * 10^-12 bits / picosecond)^-1 = 500,000,000,000 picoseconds / bit
+/// Picoseconds per bit at the default [`BitRate::Bps2000`]
 pub const PICOSECONDS_PER_BIT: u64 = 500_000_000_000;
 /// 1,000,000 picoseconds = 1 microsecond
 pub const PICOSECONDS_PER_MICROSECOND: u32 = 1_000_000;
@@ -58,15 +59,28 @@ pub const PICOSECONDS_PER_MICROSECOND: u32 = 1_000_000;
 /// - `f_cpu`: CPU frequency in Hz
 /// - `prescaler`: timer prescaler (e.g., 8, 64, 256)
 /// - `tick_us`: desired tick interval in microseconds (e.g., 62.5)
+/// - `bit_rate`: the on-air bit rate
 ///
 /// # Returns
 /// - OCR value for OCRnA (rounds to nearest integer)
-/// - Number of ticks per bit (for initializing the `AskDriver`)
-pub fn compute_ocr_value(f_cpu: u32, prescaler: u32, tick_us: f32) -> (u16, u8) {
-    let ticks_per_second: f32 = f_cpu as f32 / prescaler as f32;
-    let ticks_per_bit: u8 = (SECONDS_PER_BIT * ticks_per_second) as u8;
-    let ticks_per_tick: f32 = ticks_per_second * (tick_us / 1_000_000.0);
-    (round(ticks_per_tick as f64) as u16, ticks_per_bit)
+/// - Number of ticks per bit (for initializing the `AskDriver`, rounds to nearest integer)
+///
+/// # Example
+/// ```rust
+/// use ask433::config::BitRate;
+/// use ask433::timer::compute_ocr_value;
+///
+/// // 16 MHz AVR, prescaler 8, 62.5 µs tick
+/// assert_eq!(compute_ocr_value(16_000_000, 8, 62.5, BitRate::Bps2000), (125, 8));
+/// assert_eq!(compute_ocr_value(16_000_000, 8, 62.5, BitRate::Bps1000), (125, 16));
+/// ```
+pub fn compute_ocr_value(f_cpu: u32, prescaler: u32, tick_us: f32, bit_rate: BitRate) -> (u16, u8) {
+    let timer_counts_per_second: f32 = f_cpu as f32 / prescaler as f32;
+    let timer_counts_per_tick: f32 = timer_counts_per_second * (tick_us / 1_000_000.0);
+    (
+        round(timer_counts_per_tick as f64) as u16,
+        ticks_per_bit(tick_us, bit_rate),
+    )
 }
 
 /// Compile-time OCR value calculator
@@ -75,38 +89,95 @@ pub fn compute_ocr_value(f_cpu: u32, prescaler: u32, tick_us: f32) -> (u16, u8)
 /// - `f_cpu`: CPU frequency in Hz
 /// - `prescaler`: timer prescaler (e.g., 8, 64, 256)
 /// - `tick_us`: desired tick interval in microseconds (e.g., 62.5)
+/// - `bit_rate`: the on-air bit rate
 ///
 /// # Returns
-/// - OCR value for OCRnA (rounds to nearest integer)
-/// - Number of ticks per bit (for initializing the `AskDriver`)
-pub const fn const_ocr_value(f_cpu: u32, prescaler: u32, tick_us: f32) -> (u16, u8) {
-    let tick_ps = ((tick_us as f64) * (PICOSECONDS_PER_MICROSECOND as f64)) as u32; // convert µs to picoseconds to preserve precision
-    let ticks_per_bit: u8 = (PICOSECONDS_PER_BIT * (tick_ps as u64)) as u8;
+/// - OCR value for OCRnA (truncated to an integer)
+/// - Number of ticks per bit (for initializing the `AskDriver`, rounds to nearest integer)
+///
+/// # Example
+/// ```rust
+/// use ask433::config::BitRate;
+/// use ask433::timer::const_ocr_value;
+///
+/// const OCR: (u16, u8) = const_ocr_value(16_000_000, 8, 62.5, BitRate::Bps500);
+/// assert_eq!(OCR, (125, 32));
+/// ```
+pub const fn const_ocr_value(
+    f_cpu: u32,
+    prescaler: u32,
+    tick_us: f32,
+    bit_rate: BitRate,
+) -> (u16, u8) {
+    let tick_ps = ((tick_us as f64) * (PICOSECONDS_PER_MICROSECOND as f64)) as u64; // convert µs to picoseconds to preserve precision
     let ticks_per_tick =
-        (f_cpu / prescaler) as u64 * tick_ps as u64 / (PICOSECONDS_PER_MICROSECOND as u64);
-    (ticks_per_tick as u16, ticks_per_bit)
+        (f_cpu / prescaler) as u64 * tick_ps / (PICOSECONDS_PER_MICROSECOND as u64 * 1_000_000);
+    (
+        ticks_per_tick as u16,
+        const_ticks_per_bit(tick_us, bit_rate),
+    )
 }
 
 /// Compute ticks per bit value
 ///
 /// # Arguments
 /// - `tick_us`: desired tick interval in microseconds (e.g., 62.5)
+/// - `bit_rate`: the on-air bit rate
 ///
 /// # Returns
-/// - Number of ticks per bit (for initializing the `AskDriver`)
-pub fn ticks_per_bit(tick_us: f32) -> u8 {
-    let tick_ps = ((tick_us as f64) * (PICOSECONDS_PER_MICROSECOND as f64)) as u32;
-    (PICOSECONDS_PER_BIT * (tick_ps as u64)) as u8
+/// - Number of ticks per bit (for initializing the `AskDriver`, rounds to nearest integer,
+///   saturating at `u8::MAX`)
+pub fn ticks_per_bit(tick_us: f32, bit_rate: BitRate) -> u8 {
+    const_ticks_per_bit(tick_us, bit_rate)
 }
 
 /// Compile-time ticks per bit value
 ///
 /// # Arguments
 /// - `tick_us`: desired tick interval in microseconds (e.g., 62.5)
+/// - `bit_rate`: the on-air bit rate
 ///
 /// # Returns
-/// - Number of ticks per bit (for initializing the `AskDriver`)
-pub const fn const_ticks_per_bit(tick_us: f32) -> u8 {
-    let tick_ps = ((tick_us as f64) * (PICOSECONDS_PER_MICROSECOND as f64)) as u32;
-    (PICOSECONDS_PER_BIT * (tick_ps as u64)) as u8
+/// - Number of ticks per bit (for initializing the `AskDriver`, rounds to nearest integer,
+///   saturating at `u8::MAX`)
+pub const fn const_ticks_per_bit(tick_us: f32, bit_rate: BitRate) -> u8 {
+    let tick_ps = ((tick_us as f64) * (PICOSECONDS_PER_MICROSECOND as f64)) as u64;
+    if tick_ps == 0 {
+        return u8::MAX;
+    }
+    let ticks = (bit_rate.picoseconds_per_bit() + tick_ps / 2) / tick_ps;
+    if ticks > u8::MAX as u64 {
+        u8::MAX
+    } else {
+        ticks as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticks_per_bit_follows_bit_rate() {
+        assert_eq!(ticks_per_bit(62.5, BitRate::Bps2000), 8);
+        assert_eq!(ticks_per_bit(62.5, BitRate::Bps1000), 16);
+        assert_eq!(ticks_per_bit(62.5, BitRate::Bps500), 32);
+        assert_eq!(ticks_per_bit(62.5, BitRate::Bps4000), 4);
+        assert_eq!(ticks_per_bit(13.0, BitRate::Bps9600), 8);
+        assert_eq!(ticks_per_bit(0.5, BitRate::Bps500), u8::MAX);
+    }
+
+    #[test]
+    fn test_ocr_calculators_agree() {
+        for rate in [BitRate::Bps500, BitRate::Bps2000, BitRate::Bps9600] {
+            assert_eq!(
+                compute_ocr_value(16_000_000, 8, 62.5, rate),
+                const_ocr_value(16_000_000, 8, 62.5, rate)
+            );
+        }
+        assert_eq!(
+            compute_ocr_value(16_000_000, 64, 100.0, BitRate::Bps1000),
+            (25, 10)
+        );
+    }
 }